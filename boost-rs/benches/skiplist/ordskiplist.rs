@@ -1,9 +1,25 @@
-use boost_rs::collection::skiplist::OrdSkipList;
+use boost_rs::collection::skiplist::level_generator::DefaultLevelGenerator;
+use boost_rs::collection::skiplist::{Options, OrdSkipList};
 use criterion::{black_box, AxisScale, BenchmarkId, Criterion, PlotConfiguration};
 use rand::prelude::*;
 
 const STEPS: [usize; 4] = [100, 1000, 10_000, 100_000];
 
+/// Creates a skiplist with a seeded level generator, so that its shape is the
+/// same in every run.
+fn seeded_skiplist<I: Iterator<Item = usize>>(iter: I) -> OrdSkipList<usize> {
+    let mut sl = OrdSkipList::ord_with_options(Options {
+        cmp: None,
+        level_bound: None,
+        level_generator: Some(Box::new(
+            DefaultLevelGenerator::with_seed(16, 0.5, 0x1234abcd).unwrap(),
+        )),
+    })
+    .unwrap();
+    sl.extend(iter);
+    sl
+}
+
 pub fn insert(c: &mut Criterion) {
     let mut group = c.benchmark_group("OrdSkipList Insert");
     group.plot_config(PlotConfiguration::default().summary_scale(AxisScale::Logarithmic));
@@ -11,7 +27,7 @@ pub fn insert(c: &mut Criterion) {
     for i in STEPS {
         group.bench_function(BenchmarkId::from_parameter(i), |b| {
             let mut rng = StdRng::seed_from_u64(0x1234abcd);
-            let mut sl: OrdSkipList<usize> = std::iter::repeat_with(|| rng.gen()).take(i).collect();
+            let mut sl = seeded_skiplist(std::iter::repeat_with(|| rng.gen()).take(i));
 
             b.iter(|| {
                 sl.insert(rng.gen()).unwrap();
@@ -27,7 +43,7 @@ pub fn rand_access(c: &mut Criterion) {
     for i in STEPS {
         group.bench_function(BenchmarkId::from_parameter(i), |b| {
             let mut rng = StdRng::seed_from_u64(0x1234abcd);
-            let sl: OrdSkipList<usize> = std::iter::repeat_with(|| rng.gen()).take(i).collect();
+            let sl = seeded_skiplist(std::iter::repeat_with(|| rng.gen()).take(i));
             let indices: Vec<_> = std::iter::repeat_with(|| rng.gen_range(0..sl.length()))
                 .take(10)
                 .collect();
@@ -44,7 +60,7 @@ pub fn rand_access(c: &mut Criterion) {
 pub fn iter(c: &mut Criterion) {
     c.bench_function("OrdSkipList Iter", |b| {
         let mut rng = StdRng::seed_from_u64(0x1234abcd);
-        let sl: OrdSkipList<usize> = std::iter::repeat_with(|| rng.gen()).take(100_000).collect();
+        let sl = seeded_skiplist(std::iter::repeat_with(|| rng.gen()).take(100_000));
 
         b.iter(|| {
             for el in sl.iter() {
@@ -0,0 +1,320 @@
+//! A bidirectional map, a one-to-one map between left and right values.
+//!
+//! Every pair can be looked up by either side in *O*(1) expected time. Both
+//! sides are stored in a hash map, so they have to be `Clone`.
+//!
+//! Wikipedia:
+//!  - https://en.wikipedia.org/wiki/Bidirectional_map
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::hash::Hash;
+
+use crate::collection::error::CollectionError;
+
+/// Create a **BiMap** from a list of left-right pairs
+///
+/// Panics if a left or a right value is repeated.
+///
+/// ## Example
+///
+/// ```
+/// # fn main() {
+/// use boost_rs::bimap;
+/// let codes = bimap!{
+///     "us" => 1,
+///     "fr" => 33,
+/// };
+/// assert_eq!(codes.get_by_left("fr"), Some(&33));
+/// assert_eq!(codes.get_by_right(&1), Some(&"us"));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! bimap {
+    // trailing comma case
+    ($($left:expr => $right:expr,)+) => (bimap!($($left => $right),+));
+
+    ( $($left:expr => $right:expr),* ) => {
+        {
+            let mut _map = $crate::collection::bimap::BiMap::new();
+            $(
+                if let Err(e) = _map.insert($left, $right) {
+                    panic!("bimap!: {}", e);
+                }
+            )*
+            _map
+        }
+    };
+}
+
+/// A one-to-one map between left values of `L` and right values of `R`.
+#[derive(Clone)]
+pub struct BiMap<L, R> {
+    left_to_right: HashMap<L, R>,
+    right_to_left: HashMap<R, L>,
+}
+
+impl<L, R> Default for BiMap<L, R> {
+    fn default() -> Self {
+        BiMap {
+            left_to_right: HashMap::new(),
+            right_to_left: HashMap::new(),
+        }
+    }
+}
+
+impl<L, R> BiMap<L, R>
+where
+    L: Hash + Eq + Clone,
+    R: Hash + Eq + Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty map with space for `capacity` pairs.
+    pub fn with_capacity(capacity: usize) -> Self {
+        BiMap {
+            left_to_right: HashMap::with_capacity(capacity),
+            right_to_left: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the number of pairs.
+    #[inline]
+    pub fn length(&self) -> usize {
+        self.left_to_right.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.left_to_right.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.left_to_right.clear();
+        self.right_to_left.clear();
+    }
+
+    /// Inserts a pair.
+    ///
+    /// Returns [`CollectionError::DuplicateKey`] if the map already contains
+    /// the left or the right value, and the map is not changed.
+    pub fn insert(&mut self, left: L, right: R) -> Result<(), CollectionError> {
+        if self.left_to_right.contains_key(&left) || self.right_to_left.contains_key(&right) {
+            return Err(CollectionError::DuplicateKey);
+        }
+        self.left_to_right.insert(left.clone(), right.clone());
+        self.right_to_left.insert(right, left);
+        Ok(())
+    }
+
+    /// Returns the right value paired with `left`.
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.left_to_right.get(left)
+    }
+
+    /// Returns the left value paired with `right`.
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&L>
+    where
+        R: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.right_to_left.get(right)
+    }
+
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.left_to_right.contains_key(left)
+    }
+
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.right_to_left.contains_key(right)
+    }
+
+    /// Removes the pair of the left value, and returns it.
+    pub fn remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (left, right) = self.left_to_right.remove_entry(left)?;
+        self.right_to_left.remove(&right);
+        Some((left, right))
+    }
+
+    /// Removes the pair of the right value, and returns it.
+    pub fn remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        R: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (right, left) = self.right_to_left.remove_entry(right)?;
+        self.left_to_right.remove(&left);
+        Some((left, right))
+    }
+}
+
+impl<L, R> BiMap<L, R> {
+    /// Gets an iterator over the pairs in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&L, &R)> + '_ {
+        self.left_to_right.iter()
+    }
+
+    /// Gets an iterator over the left values in arbitrary order.
+    pub fn lefts(&self) -> impl Iterator<Item = &L> + '_ {
+        self.left_to_right.keys()
+    }
+
+    /// Gets an iterator over the right values in arbitrary order.
+    pub fn rights(&self) -> impl Iterator<Item = &R> + '_ {
+        self.right_to_left.keys()
+    }
+}
+
+impl<L: Hash + Eq, R: PartialEq> PartialEq for BiMap<L, R> {
+    fn eq(&self, other: &Self) -> bool {
+        self.left_to_right == other.left_to_right
+    }
+}
+
+impl<L: Hash + Eq, R: Eq> Eq for BiMap<L, R> {}
+
+impl<L: Debug, R: Debug> Debug for BiMap<L, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.left_to_right.iter()).finish()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use std::hash::Hash;
+
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::collection::bimap::BiMap;
+
+    impl<L: Serialize, R: Serialize> Serialize for BiMap<L, R> {
+        /// Serializes the pairs as a sequence.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.iter())
+        }
+    }
+
+    impl<'de, L, R> Deserialize<'de> for BiMap<L, R>
+    where
+        L: Deserialize<'de> + Hash + Eq + Clone,
+        R: Deserialize<'de> + Hash + Eq + Clone,
+    {
+        /// Deserializes a sequence of pairs, a repeated left or right value
+        /// is rejected.
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let pairs = Vec::<(L, R)>::deserialize(deserializer)?;
+            let mut map = BiMap::with_capacity(pairs.len());
+            for (left, right) in pairs {
+                map.insert(left, right).map_err(D::Error::custom)?;
+            }
+            Ok(map)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collection::bimap::BiMap;
+    use crate::collection::error::CollectionError;
+    use crate::convert_args;
+
+    #[test]
+    fn test_insert_and_get() {
+        let mut m = BiMap::new();
+        m.insert("one", 1).unwrap();
+        m.insert("two", 2).unwrap();
+        assert!(matches!(
+            m.insert("one", 3),
+            Err(CollectionError::DuplicateKey)
+        ));
+        assert!(matches!(
+            m.insert("three", 2),
+            Err(CollectionError::DuplicateKey)
+        ));
+        assert!(!m.contains_left("three"));
+        assert!(!m.contains_right(&3));
+
+        assert_eq!(m.length(), 2);
+        assert_eq!(m.get_by_left("two"), Some(&2));
+        assert_eq!(m.get_by_right(&1), Some(&"one"));
+        assert_eq!(m.get_by_right(&3), None);
+        let mut rights: Vec<_> = m.rights().copied().collect();
+        rights.sort_unstable();
+        assert_eq!(rights, vec![1, 2]);
+        assert_eq!(m.lefts().count(), 2);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut m = bimap! {'a' => 1, 'b' => 2, 'c' => 3};
+        assert_eq!(m.remove_by_left(&'a'), Some(('a', 1)));
+        assert_eq!(m.remove_by_right(&2), Some(('b', 2)));
+        assert_eq!(m.remove_by_right(&2), None);
+        assert!(!m.contains_right(&1));
+        assert!(!m.contains_left(&'b'));
+        assert_eq!(m.iter().collect::<Vec<_>>(), vec![(&'c', &3)]);
+
+        m.insert('a', 2).unwrap();
+        assert_eq!(m.length(), 2);
+        m.clear();
+        assert!(m.is_empty());
+    }
+
+    #[test]
+    fn test_macro() {
+        let m: BiMap<String, u8> = convert_args!(
+            keys = String::from,
+            bimap!(
+                "x" => 1,
+                "y" => 2,
+            )
+        );
+        assert_eq!(m.get_by_right(&2).map(String::as_str), Some("y"));
+        assert_eq!(m, m.clone());
+        assert_eq!(format!("{:?}", bimap! {1 => 'x'}), "{1: 'x'}");
+
+        let empty: BiMap<i32, i32> = bimap! {};
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "key already exists")]
+    fn test_macro_conflict() {
+        let _ = bimap! {1 => 'x', 2 => 'x'};
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let m = bimap! {"a" => 1};
+        let json = serde_json::to_string(&m).unwrap();
+        assert_eq!(json, r#"[["a",1]]"#);
+        assert_eq!(
+            serde_json::from_str::<BiMap<String, i32>>(&json)
+                .unwrap()
+                .length(),
+            1
+        );
+        assert!(serde_json::from_str::<BiMap<String, i32>>(r#"[["a",1],["b",1]]"#).is_err());
+    }
+}
@@ -0,0 +1,751 @@
+//! Compact sets of small integers, and matrices of bits.
+//!
+//! [`BitSet`] stores one bit for every integer below its capacity, and grows
+//! as larger integers are inserted. The set operations work on whole 64-bit
+//! words at a time.
+//!
+//! [`BitMatrix`] stores a `rows × cols` grid of bits, every row padded to a
+//! whole number of words, e.g. the adjacency matrix of a dense graph.
+//!
+//! Wikipedia:
+//!  - https://en.wikipedia.org/wiki/Bit_array
+//!  - https://en.wikipedia.org/wiki/Succinct_data_structure
+
+use std::fmt::{self, Debug};
+use std::iter::{FromIterator, FusedIterator};
+
+use bitvec::order::Lsb0;
+use bitvec::vec::BitVec;
+
+use crate::collection::error::CollectionError;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A set of `usize`s, stored as a vector of bits.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::bitset::BitSet;
+///
+/// let mut even: BitSet = (0..20).step_by(2).collect();
+/// let mut small = BitSet::with_capacity(10);
+/// small.extend(0..10);
+///
+/// even.intersect_with(&small);
+/// assert_eq!(even.iter().collect::<Vec<_>>(), vec![0, 2, 4, 6, 8]);
+///
+/// // Rank and select are inverses on the set bits.
+/// assert_eq!(even.rank(6), 3);
+/// assert_eq!(even.select(3), Some(6));
+/// assert_eq!(even.select(5), None);
+/// ```
+#[derive(Clone, Default)]
+pub struct BitSet {
+    // The length is always a multiple of the word size, so the raw words hold
+    // exactly the bits of the set.
+    bits: BitVec<u64, Lsb0>,
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty set which holds the integers below `capacity` without
+    /// growing.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut set = Self::new();
+        set.grow(capacity);
+        set
+    }
+
+    /// Returns the number of integers the set holds without growing.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Grows the set to hold the integers below `capacity` without growing
+    /// again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the capacity rounded up to whole words overflows `usize`.
+    pub fn grow(&mut self, capacity: usize) {
+        if capacity > self.bits.len() {
+            let capacity = capacity
+                .div_ceil(WORD_BITS)
+                .checked_mul(WORD_BITS)
+                .expect("capacity overflow");
+            self.bits.resize(capacity, false);
+        }
+    }
+
+    /// Returns the number of integers in the set.
+    ///
+    /// This operation should compute in *O*(*n* / 64) time, where *n* is the
+    /// capacity.
+    pub fn count_ones(&self) -> usize {
+        self._words().iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns `true` if the set contains no integers.
+    pub fn is_empty(&self) -> bool {
+        self._words().iter().all(|&w| w == 0)
+    }
+
+    /// Removes all integers, keeping the capacity.
+    pub fn clear(&mut self) {
+        self.bits.fill(false);
+    }
+
+    /// Adds an integer to the set, and returns `false` if the set contains it
+    /// already.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the set has to grow for `value` and its capacity overflows,
+    /// which is always the case for `usize::MAX`.
+    pub fn insert(&mut self, value: usize) -> bool {
+        self.grow(value.checked_add(1).expect("capacity overflow"));
+        !self.bits.replace(value, true)
+    }
+
+    /// Removes an integer from the set, and returns `true` if the set
+    /// contained it.
+    pub fn remove(&mut self, value: usize) -> bool {
+        value < self.bits.len() && self.bits.replace(value, false)
+    }
+
+    /// Returns `true` if the set contains the integer.
+    pub fn contains(&self, value: usize) -> bool {
+        self.bits.get(value).is_some_and(|bit| *bit)
+    }
+
+    /// Adds the integers of `other` to the set.
+    pub fn union_with(&mut self, other: &BitSet) {
+        self.grow(other.capacity());
+        for (w, o) in self._words_mut().iter_mut().zip(other._words()) {
+            *w |= o;
+        }
+    }
+
+    /// Removes the integers which are not in `other` from the set.
+    pub fn intersect_with(&mut self, other: &BitSet) {
+        let others = other._words();
+        for (i, w) in self._words_mut().iter_mut().enumerate() {
+            *w &= others.get(i).copied().unwrap_or(0);
+        }
+    }
+
+    /// Removes the integers of `other` from the set.
+    pub fn difference_with(&mut self, other: &BitSet) {
+        for (w, o) in self._words_mut().iter_mut().zip(other._words()) {
+            *w &= !o;
+        }
+    }
+
+    /// Keeps the integers in exactly one of the set and `other`.
+    pub fn symmetric_difference_with(&mut self, other: &BitSet) {
+        self.grow(other.capacity());
+        for (w, o) in self._words_mut().iter_mut().zip(other._words()) {
+            *w ^= o;
+        }
+    }
+
+    /// Returns `true` if all integers of the set are in `other`.
+    pub fn is_subset(&self, other: &BitSet) -> bool {
+        let others = other._words();
+        self._words()
+            .iter()
+            .enumerate()
+            .all(|(i, w)| w & !others.get(i).copied().unwrap_or(0) == 0)
+    }
+
+    /// Returns `true` if the set and `other` have no integers in common.
+    pub fn is_disjoint(&self, other: &BitSet) -> bool {
+        self._words()
+            .iter()
+            .zip(other._words())
+            .all(|(w, o)| w & o == 0)
+    }
+
+    /// Returns the number of integers in the set which are less than `value`.
+    ///
+    /// This operation should compute in *O*(`value` / 64) time.
+    pub fn rank(&self, value: usize) -> usize {
+        let value = value.min(self.bits.len());
+        let (full, rest) = (value / WORD_BITS, value % WORD_BITS);
+        let words = self._words();
+        let mut rank: usize = words[..full].iter().map(|w| w.count_ones() as usize).sum();
+        if rest > 0 {
+            rank += (words[full] & ((1 << rest) - 1)).count_ones() as usize;
+        }
+        rank
+    }
+
+    /// Returns the integer of the set with `rank` smaller integers, that is
+    /// the `rank`-th integer counting from 0, or `None` if the set has no
+    /// more than `rank` integers.
+    ///
+    /// This operation should compute in *O*(*n* / 64) time, where *n* is the
+    /// capacity.
+    pub fn select(&self, rank: usize) -> Option<usize> {
+        let mut rank = rank;
+        for (i, &w) in self._words().iter().enumerate() {
+            let ones = w.count_ones() as usize;
+            if rank < ones {
+                let mut w = w;
+                for _ in 0..rank {
+                    // Clear the lowest set bit.
+                    w &= w - 1;
+                }
+                return Some(i * WORD_BITS + w.trailing_zeros() as usize);
+            }
+            rank -= ones;
+        }
+        None
+    }
+
+    /// Gets an iterator over the integers of the set, in ascending order.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter::new(self._words())
+    }
+
+    #[inline]
+    pub(crate) fn _words(&self) -> &[u64] {
+        self.bits.as_raw_slice()
+    }
+
+    #[inline]
+    pub(crate) fn _words_mut(&mut self) -> &mut [u64] {
+        self.bits.as_raw_mut_slice()
+    }
+}
+
+impl PartialEq for BitSet {
+    /// Compares the integers of the sets, regardless of the capacities.
+    fn eq(&self, other: &Self) -> bool {
+        let (short, long) = if self.capacity() <= other.capacity() {
+            (self._words(), other._words())
+        } else {
+            (other._words(), self._words())
+        };
+        short == &long[..short.len()] && long[short.len()..].iter().all(|&w| w == 0)
+    }
+}
+
+impl Eq for BitSet {}
+
+impl Debug for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl Extend<usize> for BitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<'a> IntoIterator for &'a BitSet {
+    type Item = usize;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the set bits of some words, in ascending order.
+pub struct Iter<'a> {
+    words: std::slice::Iter<'a, u64>,
+    // The remaining bits of the current word, and the number of its first
+    // bit.
+    word: u64,
+    base: usize,
+}
+
+impl<'a> Iter<'a> {
+    fn new(words: &'a [u64]) -> Self {
+        Iter {
+            words: words.iter(),
+            word: 0,
+            base: 0usize.wrapping_sub(WORD_BITS),
+        }
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.word == 0 {
+            self.word = *self.words.next()?;
+            self.base = self.base.wrapping_add(WORD_BITS);
+        }
+        let bit = self.word.trailing_zeros() as usize;
+        // Clear the lowest set bit.
+        self.word &= self.word - 1;
+        Some(self.base + bit)
+    }
+}
+
+impl<'a> FusedIterator for Iter<'a> {}
+
+/// A `rows × cols` matrix of bits.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::bitset::BitMatrix;
+///
+/// // The adjacency matrix of the graph 0 -> 1 -> 2.
+/// let mut adj = BitMatrix::new(3, 3);
+/// adj.insert(0, 1).unwrap();
+/// adj.insert(1, 2).unwrap();
+/// assert!(adj.contains(0, 1));
+/// assert!(adj.insert(3, 0).is_err());
+///
+/// let reach = adj.transitive_closure().unwrap();
+/// assert_eq!(reach.iter_row(0).unwrap().collect::<Vec<_>>(), vec![1, 2]);
+/// assert_eq!(adj.transpose().iter_row(2).unwrap().collect::<Vec<_>>(), vec![1]);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct BitMatrix {
+    rows: usize,
+    cols: usize,
+    // The bits of the row `r` are in the words
+    // `r * row_words..(r + 1) * row_words`.
+    row_words: usize,
+    bits: BitSet,
+}
+
+impl BitMatrix {
+    /// Creates a `rows × cols` matrix of unset bits.
+    pub fn new(rows: usize, cols: usize) -> Self {
+        let row_words = cols.div_ceil(WORD_BITS);
+        BitMatrix {
+            rows,
+            cols,
+            row_words,
+            bits: BitSet::with_capacity(rows * row_words * WORD_BITS),
+        }
+    }
+
+    /// Returns the number of rows.
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns.
+    #[inline]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.bits.count_ones()
+    }
+
+    /// Unsets all bits.
+    pub fn clear(&mut self) {
+        self.bits.clear()
+    }
+
+    /// Sets the bit at `(row, col)`, and returns `false` if it was set
+    /// already.
+    pub fn insert(&mut self, row: usize, col: usize) -> Result<bool, CollectionError> {
+        let i = self._bit(row, col)?;
+        Ok(self.bits.insert(i))
+    }
+
+    /// Unsets the bit at `(row, col)`, and returns `true` if it was set.
+    pub fn remove(&mut self, row: usize, col: usize) -> Result<bool, CollectionError> {
+        let i = self._bit(row, col)?;
+        Ok(self.bits.remove(i))
+    }
+
+    /// Returns `true` if the bit at `(row, col)` is set, the bits out of the
+    /// matrix are unset.
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        self._bit(row, col).is_ok_and(|i| self.bits.contains(i))
+    }
+
+    /// Gets an iterator over the columns of the set bits of the row, in
+    /// ascending order.
+    pub fn iter_row(&self, row: usize) -> Result<Iter<'_>, CollectionError> {
+        self._check_row(row)?;
+        Ok(Iter::new(self._row(row)))
+    }
+
+    /// Returns the number of set bits of the row.
+    pub fn row_count_ones(&self, row: usize) -> Result<usize, CollectionError> {
+        self._check_row(row)?;
+        Ok(self._row(row).iter().map(|w| w.count_ones() as usize).sum())
+    }
+
+    /// Sets the bits of the row `dst` which are set in the row `src`, and
+    /// returns `true` if the row `dst` changed.
+    pub fn union_rows(&mut self, dst: usize, src: usize) -> Result<bool, CollectionError> {
+        self._check_row(dst)?;
+        self._check_row(src)?;
+        let n = self.row_words;
+        let words = self.bits._words_mut();
+        let mut changed = false;
+        for k in 0..n {
+            let merged = words[dst * n + k] | words[src * n + k];
+            changed |= merged != words[dst * n + k];
+            words[dst * n + k] = merged;
+        }
+        Ok(changed)
+    }
+
+    /// Returns the transposed `cols × rows` matrix.
+    pub fn transpose(&self) -> BitMatrix {
+        let mut t = BitMatrix::new(self.cols, self.rows);
+        for row in 0..self.rows {
+            for col in Iter::new(self._row(row)) {
+                t.bits.insert(col * t.row_words * WORD_BITS + row);
+            }
+        }
+        t
+    }
+
+    /// Returns the reachability matrix of a square matrix seen as the
+    /// adjacency matrix of a graph, where the bit at `(u, v)` is set if there
+    /// is a non-empty path from `u` to `v`.
+    ///
+    /// Returns [`CollectionError::InvalidParameter`] if the matrix is not
+    /// square.
+    ///
+    /// This operation should compute in *O*(*n*³ / 64) time.
+    pub fn transitive_closure(&self) -> Result<BitMatrix, CollectionError> {
+        if self.rows != self.cols {
+            return Err(CollectionError::InvalidParameter(format!(
+                "the matrix is {} × {}, not square",
+                self.rows, self.cols
+            )));
+        }
+        // Warshall's algorithm: after the step `k`, the paths through the
+        // vertices `0..=k` are known.
+        let mut reach = self.clone();
+        for k in 0..self.rows {
+            for u in 0..self.rows {
+                if reach.contains(u, k) {
+                    reach.union_rows(u, k)?;
+                }
+            }
+        }
+        Ok(reach)
+    }
+
+    fn _row(&self, row: usize) -> &[u64] {
+        &self.bits._words()[row * self.row_words..(row + 1) * self.row_words]
+    }
+
+    fn _check_row(&self, row: usize) -> Result<(), CollectionError> {
+        if row >= self.rows {
+            return Err(CollectionError::IndexOutOfRange {
+                index: row,
+                len: self.rows,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the index of the bit at `(row, col)` in the set.
+    fn _bit(&self, row: usize, col: usize) -> Result<usize, CollectionError> {
+        self._check_row(row)?;
+        if col >= self.cols {
+            return Err(CollectionError::IndexOutOfRange {
+                index: col,
+                len: self.cols,
+            });
+        }
+        Ok(row * self.row_words * WORD_BITS + col)
+    }
+}
+
+impl Debug for BitMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows: Vec<String> = (0..self.rows)
+            .map(|r| {
+                (0..self.cols)
+                    .map(|c| if self.contains(r, c) { '1' } else { '0' })
+                    .collect()
+            })
+            .collect();
+        f.debug_list().entries(rows).finish()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use bitvec::vec::BitVec;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::collection::bitset::{BitMatrix, BitSet, WORD_BITS};
+    use crate::collection::error::CollectionError;
+
+    impl Serialize for BitSet {
+        /// Serializes the bits as a sequence of 64-bit words, the first bit
+        /// in the lowest bit of the first word.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self._words().serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for BitSet {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let words = Vec::<u64>::deserialize(deserializer)?;
+            Ok(BitSet {
+                bits: BitVec::from_vec(words),
+            })
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "BitMatrix")]
+    struct BitMatrixRepr {
+        rows: usize,
+        cols: usize,
+        bits: BitSet,
+    }
+
+    impl Serialize for BitMatrix {
+        /// Serializes the shape and the bits, every row padded to whole
+        /// words.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            BitMatrixRepr {
+                rows: self.rows,
+                cols: self.cols,
+                bits: self.bits.clone(),
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for BitMatrix {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let repr = BitMatrixRepr::deserialize(deserializer)?;
+            let row_words = repr.cols.div_ceil(WORD_BITS);
+            // The shape is checked against the bits before anything is
+            // allocated for it.
+            let capacity = repr
+                .rows
+                .checked_mul(row_words)
+                .and_then(|words| words.checked_mul(WORD_BITS));
+            if capacity != Some(repr.bits.capacity())
+                || repr
+                    .bits
+                    .iter()
+                    .any(|i| i % (row_words * WORD_BITS) >= repr.cols)
+            {
+                return Err(D::Error::custom(CollectionError::InvalidParameter(
+                    "the shape and the bits of the matrix don't match".to_string(),
+                )));
+            }
+            Ok(BitMatrix {
+                rows: repr.rows,
+                cols: repr.cols,
+                row_words,
+                bits: repr.bits,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::collection::bitset::{BitMatrix, BitSet};
+    use crate::collection::error::CollectionError;
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut s = BitSet::new();
+        assert!(s.is_empty());
+        assert!(s.insert(3));
+        assert!(!s.insert(3));
+        assert!(s.insert(130));
+        assert_eq!(s.capacity(), 192);
+        assert!(s.contains(130));
+        assert!(!s.contains(1000));
+        assert_eq!(s.count_ones(), 2);
+
+        assert!(s.remove(3));
+        assert!(!s.remove(3));
+        assert!(!s.remove(5000));
+        assert_eq!(format!("{:?}", s), "{130}");
+        s.clear();
+        assert!(s.is_empty());
+        assert_eq!(s.capacity(), 192);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn test_insert_max() {
+        BitSet::new().insert(usize::MAX);
+    }
+
+    #[test]
+    fn test_set_ops() {
+        let a: BitSet = vec![1, 5, 64, 100, 200].into_iter().collect();
+        let b: BitSet = vec![5, 64, 65, 300].into_iter().collect();
+        let ones = |s: &BitSet| s.iter().collect::<Vec<_>>();
+
+        let mut u = a.clone();
+        u.union_with(&b);
+        assert_eq!(ones(&u), vec![1, 5, 64, 65, 100, 200, 300]);
+        let mut i = a.clone();
+        i.intersect_with(&b);
+        assert_eq!(ones(&i), vec![5, 64]);
+        let mut d = a.clone();
+        d.difference_with(&b);
+        assert_eq!(ones(&d), vec![1, 100, 200]);
+        let mut x = b.clone();
+        x.symmetric_difference_with(&a);
+        assert_eq!(ones(&x), vec![1, 65, 100, 200, 300]);
+
+        assert!(i.is_subset(&a) && i.is_subset(&b));
+        assert!(!a.is_subset(&b));
+        assert!(d.is_disjoint(&b));
+        assert!(!a.is_disjoint(&b));
+
+        // The capacities don't matter for equality.
+        let mut big = BitSet::with_capacity(1000);
+        big.extend(vec![5, 64]);
+        assert_eq!(big, i);
+        assert_eq!(i, big);
+    }
+
+    #[test]
+    fn test_rank_and_select() {
+        let s: BitSet = (0..500).filter(|x| x % 3 == 0 || x % 7 == 0).collect();
+        let members: Vec<usize> = s.iter().collect();
+        for (rank, &x) in members.iter().enumerate() {
+            assert_eq!(s.select(rank), Some(x));
+            assert_eq!(s.rank(x), rank);
+            assert_eq!(s.rank(x + 1), rank + 1);
+        }
+        assert_eq!(s.select(members.len()), None);
+        assert_eq!(s.rank(100_000), members.len());
+        assert_eq!(BitSet::new().rank(10), 0);
+    }
+
+    #[test]
+    fn test_random_consistency() {
+        use crate::collection::test_util::random_ops;
+        use rand::Rng;
+
+        let mut s = BitSet::new();
+        let mut t = BTreeSet::new();
+        random_ops(5000, |rng, _| {
+            let x = rng.gen_range(0..700);
+            if rng.gen_bool(0.6) {
+                assert_eq!(s.insert(x), t.insert(x));
+            } else {
+                assert_eq!(s.remove(x), t.remove(&x));
+            }
+            let y = rng.gen_range(0..800);
+            assert_eq!(s.rank(y), t.range(..y).count());
+        });
+        assert!(s.iter().eq(t.iter().copied()));
+        assert_eq!(s.count_ones(), t.len());
+    }
+
+    #[test]
+    fn test_matrix() {
+        let mut m = BitMatrix::new(3, 70);
+        assert!(m.insert(0, 69).unwrap());
+        assert!(!m.insert(0, 69).unwrap());
+        m.insert(2, 0).unwrap();
+        m.insert(2, 64).unwrap();
+        assert!(matches!(
+            m.insert(0, 70),
+            Err(CollectionError::IndexOutOfRange { index: 70, len: 70 })
+        ));
+        assert!(m.iter_row(3).is_err());
+        assert!(!m.contains(5, 5));
+        assert_eq!(m.count_ones(), 3);
+        assert_eq!(m.row_count_ones(2).unwrap(), 2);
+
+        assert!(m.union_rows(1, 2).unwrap());
+        assert!(!m.union_rows(1, 2).unwrap());
+        assert_eq!(m.iter_row(1).unwrap().collect::<Vec<_>>(), vec![0, 64]);
+        assert!(m.remove(1, 0).unwrap());
+
+        let t = m.transpose();
+        assert_eq!((t.rows(), t.cols()), (70, 3));
+        assert_eq!(t.iter_row(64).unwrap().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(t.transpose(), m);
+        assert!(m.transitive_closure().is_err());
+    }
+
+    #[test]
+    fn test_transitive_closure() {
+        // 0 -> 1 -> 2 -> 0, and 3 -> 4.
+        let mut m = BitMatrix::new(5, 5);
+        for (u, v) in [(0, 1), (1, 2), (2, 0), (3, 4)] {
+            m.insert(u, v).unwrap();
+        }
+        let r = m.transitive_closure().unwrap();
+        for u in 0..3 {
+            assert_eq!(r.iter_row(u).unwrap().collect::<Vec<_>>(), vec![0, 1, 2]);
+        }
+        assert_eq!(r.iter_row(3).unwrap().collect::<Vec<_>>(), vec![4]);
+        assert_eq!(r.row_count_ones(4).unwrap(), 0);
+        assert_eq!(
+            format!("{:?}", m),
+            r#"["01000", "00100", "10000", "00001", "00000"]"#
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let s: BitSet = vec![0, 65].into_iter().collect();
+        let json = serde_json::to_string(&s).unwrap();
+        assert_eq!(json, "[1,2]");
+        assert_eq!(serde_json::from_str::<BitSet>(&json).unwrap(), s);
+
+        let mut m = BitMatrix::new(2, 3);
+        m.insert(1, 2).unwrap();
+        let json = serde_json::to_string(&m).unwrap();
+        assert_eq!(json, r#"{"rows":2,"cols":3,"bits":[0,4]}"#);
+        assert_eq!(serde_json::from_str::<BitMatrix>(&json).unwrap(), m);
+        assert!(serde_json::from_str::<BitMatrix>(r#"{"rows":2,"cols":3,"bits":[8,0]}"#).is_err());
+        assert!(serde_json::from_str::<BitMatrix>(r#"{"rows":2,"cols":3,"bits":[0]}"#).is_err());
+        // Huge or overflowing shapes are rejected without allocating.
+        assert!(
+            serde_json::from_str::<BitMatrix>(r#"{"rows":1000000000000,"cols":64,"bits":[]}"#)
+                .is_err()
+        );
+        let overflow = format!(
+            r#"{{"rows":{},"cols":{},"bits":[]}}"#,
+            usize::MAX,
+            usize::MAX
+        );
+        assert!(serde_json::from_str::<BitMatrix>(&overflow).is_err());
+        let empty: BitMatrix =
+            serde_json::from_str(r#"{"rows":1000000000000,"cols":0,"bits":[]}"#).unwrap();
+        assert_eq!(empty.rows(), 1000000000000);
+    }
+}
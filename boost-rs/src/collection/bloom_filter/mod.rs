@@ -7,16 +7,85 @@ use std::collections::hash_map::{DefaultHasher, RandomState};
 use std::hash::{BuildHasher, Hash, Hasher};
 use std::marker::PhantomData;
 
-use bitvec::vec::BitVec;
+use crate::collection::bitset::BitSet;
+use crate::collection::error::CollectionError;
 
 const DEFAULT_CAPACITY: usize = 10240;
 
 type HasherArray = Box<[Box<dyn BuildHasher<Hasher = DefaultHasher>>]>;
 
+/// The version of the algorithm of [`SeededHasher`], which is serialized with
+/// a seeded filter, so that a filter hashed otherwise is rejected.
+#[cfg_attr(not(feature = "serde"), allow(dead_code))]
+const SEEDED_HASH_VERSION: u32 = 1;
+
+/// A hasher whose algorithm is specified here, so that a seeded filter hashes
+/// the same in every build and on every target.
+///
+/// It is 64-bit FNV-1a, starting from the offset basis xor the seed, with the
+/// splitmix64 finalizer applied to the result. Integers are hashed as
+/// little-endian bytes, and `usize`/`isize` as 64-bit integers.
+struct SeededHasher(u64);
+
+impl SeededHasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    fn new(seed: u64) -> Self {
+        SeededHasher(Self::OFFSET_BASIS ^ seed)
+    }
+}
+
+impl Hasher for SeededHasher {
+    fn finish(&self) -> u64 {
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64)
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_u64(i as i64 as u64)
+    }
+}
+
+enum Hashers {
+    // Hashers given by the user, or randomly keyed ones.
+    Custom(HasherArray),
+    // The seeds of `SeededHasher`s.
+    Seeded(Vec<u64>),
+}
+
 pub struct BloomFilter<T: ?Sized + Hash> {
     cap: usize,
-    bit_array: BitVec,
-    hashers: HasherArray,
+    bits: BitSet,
+    hashers: Hashers,
     _phantom: PhantomData<T>,
 }
 
@@ -28,57 +97,71 @@ impl<T: ?Sized + Hash> BloomFilter<T> {
     pub fn with_capacity(cap: usize) -> Self {
         let v: Vec<Box<dyn BuildHasher<Hasher = DefaultHasher>>> =
             vec![Box::new(RandomState::new())];
-        let hash_arr = HasherArray::from(v);
-        BloomFilter {
-            cap,
-            bit_array: BitVec::repeat(false, cap),
-            hashers: hash_arr,
-            _phantom: Default::default(),
-        }
+        Self::_with_hashers(cap, Hashers::Custom(HasherArray::from(v)))
     }
 
     pub fn with_hashers<const N: usize>(
         hashers: [Box<dyn BuildHasher<Hasher = DefaultHasher>>; N],
     ) -> Self {
-        let hash_arr = HasherArray::from(hashers);
-        BloomFilter {
-            cap: DEFAULT_CAPACITY,
-            bit_array: BitVec::repeat(false, DEFAULT_CAPACITY),
-            hashers: hash_arr,
-            _phantom: Default::default(),
-        }
+        Self::with_cap_and_hashers(DEFAULT_CAPACITY, hashers)
     }
 
     pub fn with_cap_and_hashers<const N: usize>(
         cap: usize,
         hashers: [Box<dyn BuildHasher<Hasher = DefaultHasher>>; N],
     ) -> Self {
-        let hash_arr = HasherArray::from(hashers);
+        Self::_with_hashers(cap, Hashers::Custom(HasherArray::from(hashers)))
+    }
+
+    /// Creates a filter with a hash function for every seed.
+    ///
+    /// Unlike the other filters, its hash functions are specified and don't
+    /// depend on the build, so only a seeded filter can be serialized and
+    /// restored elsewhere.
+    ///
+    /// Returns [`CollectionError::InvalidParameter`] if the capacity is 0 or
+    /// there are no seeds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use boost_rs::collection::bloom_filter::BloomFilter;
+    ///
+    /// let mut f: BloomFilter<str> = BloomFilter::with_seeds(1024, &[1, 2, 3]).unwrap();
+    /// f.set("apple");
+    /// assert!(f.might_contain("apple"));
+    /// assert!(BloomFilter::<str>::with_seeds(1024, &[]).is_err());
+    /// ```
+    pub fn with_seeds(cap: usize, seeds: &[u64]) -> Result<Self, CollectionError> {
+        if cap == 0 || seeds.is_empty() {
+            return Err(CollectionError::InvalidParameter(
+                "a seeded bloom filter needs a positive capacity and a seed".to_string(),
+            ));
+        }
+        Ok(Self::_with_hashers(cap, Hashers::Seeded(seeds.to_vec())))
+    }
+
+    fn _with_hashers(cap: usize, hashers: Hashers) -> Self {
         BloomFilter {
             cap,
-            bit_array: BitVec::repeat(false, cap),
-            hashers: hash_arr,
+            bits: BitSet::with_capacity(cap),
+            hashers,
             _phantom: Default::default(),
         }
     }
 
     pub fn set(&mut self, item: &T) {
-        for i in 0..self.hashers.len() {
+        for i in 0..self._hasher_count() {
             let bit_offset = self.calculate_hash(i, item) as usize;
-            self.bit_array.set(bit_offset, true);
+            self.bits.insert(bit_offset);
         }
     }
 
     pub fn might_contain(&self, item: &T) -> bool {
-        for i in 0..self.hashers.len() {
+        for i in 0..self._hasher_count() {
             let bit_offset = self.calculate_hash(i, item) as usize;
-            match self.bit_array.get(bit_offset) {
-                None => return false,
-                Some(res) => {
-                    if !res {
-                        return false;
-                    }
-                }
+            if !self.bits.contains(bit_offset) {
+                return false;
             }
         }
         true
@@ -88,23 +171,115 @@ impl<T: ?Sized + Hash> BloomFilter<T> {
         self.cap
     }
 
+    fn _hasher_count(&self) -> usize {
+        match &self.hashers {
+            Hashers::Custom(hashers) => hashers.len(),
+            Hashers::Seeded(seeds) => seeds.len(),
+        }
+    }
+
+    // `hash_one` can't be called on a trait object.
+    #[allow(clippy::manual_hash_one)]
     fn calculate_hash(&self, idx: usize, item: &T) -> u64 {
-        let mut hasher = self.hashers[idx].build_hasher();
-        item.hash(&mut hasher);
-        hasher.finish() % (self.cap as u64)
+        let hash = match &self.hashers {
+            Hashers::Custom(hashers) => {
+                let mut hasher = hashers[idx].build_hasher();
+                item.hash(&mut hasher);
+                hasher.finish()
+            }
+            Hashers::Seeded(seeds) => {
+                let mut hasher = SeededHasher::new(seeds[idx]);
+                item.hash(&mut hasher);
+                hasher.finish()
+            }
+        };
+        hash % (self.cap as u64)
     }
 }
 
 impl<T: ?Sized + Hash> Default for BloomFilter<T> {
     fn default() -> Self {
-        let v: Vec<Box<dyn BuildHasher<Hasher = DefaultHasher>>> =
-            vec![Box::new(RandomState::new())];
-        let hash_arr = HasherArray::from(v);
-        BloomFilter {
-            bit_array: BitVec::repeat(false, DEFAULT_CAPACITY),
-            cap: DEFAULT_CAPACITY,
-            hashers: hash_arr,
-            _phantom: Default::default(),
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use std::hash::Hash;
+
+    use serde::de::Error as DeError;
+    use serde::ser::Error as SerError;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::collection::bloom_filter::{BloomFilter, Hashers, SEEDED_HASH_VERSION};
+    use crate::collection::error::CollectionError;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "BloomFilter")]
+    struct BloomFilterRepr {
+        cap: usize,
+        // The version of the algorithm of the seeded hashers.
+        version: u32,
+        seeds: Vec<u64>,
+        // The bits packed into bytes, the first bit in the lowest bit.
+        bits: Vec<u8>,
+    }
+
+    impl<T: ?Sized + Hash> Serialize for BloomFilter<T> {
+        /// Serializes the capacity, the hash version, the seeds of the
+        /// hashers and the bits.
+        ///
+        /// Only filters created by [`BloomFilter::with_seeds`] can be
+        /// serialized, as the other ones hash differently in every process.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let seeds = match &self.hashers {
+                Hashers::Seeded(seeds) => seeds.clone(),
+                Hashers::Custom(_) => {
+                    return Err(S::Error::custom(
+                        "only a seeded bloom filter can be serialized",
+                    ))
+                }
+            };
+            let mut bits = vec![0u8; self.cap.div_ceil(8)];
+            for i in self.bits.iter() {
+                bits[i / 8] |= 1 << (i % 8);
+            }
+            BloomFilterRepr {
+                cap: self.cap,
+                version: SEEDED_HASH_VERSION,
+                seeds,
+                bits,
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de, T: ?Sized + Hash> Deserialize<'de> for BloomFilter<T> {
+        /// Deserializes a seeded filter, which is rejected if it was hashed
+        /// by another version of the algorithm.
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let repr = BloomFilterRepr::deserialize(deserializer)?;
+            if repr.version != SEEDED_HASH_VERSION {
+                return Err(D::Error::custom(CollectionError::InvalidParameter(
+                    format!(
+                        "the bloom filter is hashed by version {} but only version {} is supported",
+                        repr.version, SEEDED_HASH_VERSION
+                    ),
+                )));
+            }
+            if repr.bits.len() != repr.cap.div_ceil(8) {
+                return Err(D::Error::custom(CollectionError::InvalidParameter(
+                    "the capacity and bits of the bloom filter don't match".to_string(),
+                )));
+            }
+
+            let mut f = BloomFilter::with_seeds(repr.cap, &repr.seeds).map_err(D::Error::custom)?;
+            for i in 0..repr.cap {
+                if repr.bits[i / 8] & (1 << (i % 8)) != 0 {
+                    f.bits.insert(i);
+                }
+            }
+            Ok(f)
         }
     }
 }
@@ -140,4 +315,55 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_seeded() {
+        let mut f: BloomFilter<u64> = BloomFilter::with_seeds(4096, &[7, 8, 9]).unwrap();
+        for x in 0..300 {
+            f.set(&x);
+        }
+        assert!((0..300).all(|x| f.might_contain(&x)));
+        assert!(BloomFilter::<u64>::with_seeds(0, &[1]).is_err());
+
+        // The seeded hash is specified, so its bits never change.
+        let mut f: BloomFilter<str> = BloomFilter::with_seeds(1000003, &[0, 5]).unwrap();
+        f.set("a");
+        assert_eq!(f.bits.iter().collect::<Vec<_>>(), vec![318792, 824271]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let mut f: BloomFilter<str> = BloomFilter::with_seeds(1000, &[1, 2]).unwrap();
+        for x in 0..100 {
+            f.set(&x.to_string());
+        }
+
+        let json = serde_json::to_string(&f).unwrap();
+        let de: BloomFilter<str> = serde_json::from_str(&json).unwrap();
+        assert_eq!(de.cap(), 1000);
+        for x in 0..1000 {
+            assert_eq!(
+                de.might_contain(&x.to_string()),
+                f.might_contain(&x.to_string())
+            );
+        }
+
+        let random: BloomFilter<str> = BloomFilter::with_capacity(10);
+        assert!(serde_json::to_string(&random).is_err());
+        let custom: BloomFilter<str> = BloomFilter::with_hashers([Box::new(RandomState::new())]);
+        assert!(serde_json::to_string(&custom).is_err());
+        assert!(serde_json::from_str::<BloomFilter<str>>(
+            r#"{"cap":9,"version":1,"seeds":[1],"bits":[0]}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<BloomFilter<str>>(
+            r#"{"cap":8,"version":2,"seeds":[1],"bits":[0]}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<BloomFilter<str>>(
+            r#"{"cap":8,"version":1,"seeds":[1],"bits":[0]}"#
+        )
+        .is_ok());
+    }
 }
@@ -1,14 +1,31 @@
-use std::cmp::max;
-use std::fmt::Display;
+use std::cmp::{max, Ordering};
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Display, Write};
+use std::hash::{Hash, Hasher};
+use std::iter::{FromIterator, FusedIterator};
 use std::marker::PhantomData;
 use std::mem;
 use std::ptr::NonNull;
 
+use crate::collection::error::CollectionError;
+use crate::collection::set_ops::{self, Difference, Intersection, SymmetricDifference, Union};
+
+type Link<T> = Option<NonNull<TreeNode<T>>>;
+
+/// The comparator used by the set operations of the tree.
+pub type PartialCmp<T> = fn(&T, &T) -> Ordering;
+
+/// Elements which are incomparable are treated as equal, just like the lookups
+/// of the tree do.
+fn partial_cmp<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+}
+
 struct TreeNode<T> {
     pub val: T,
-    pub left: Option<NonNull<TreeNode<T>>>,
-    pub right: Option<NonNull<TreeNode<T>>>,
-    pub parent: Option<NonNull<TreeNode<T>>>,
+    pub left: Link<T>,
+    pub right: Link<T>,
+    pub parent: Link<T>,
 }
 
 impl<T> TreeNode<T> {
@@ -20,6 +37,57 @@ impl<T> TreeNode<T> {
             parent: None,
         }
     }
+
+    /// Returns the left-most node of the subtree rooted at `node`.
+    unsafe fn leftmost(mut node: NonNull<TreeNode<T>>) -> NonNull<TreeNode<T>> {
+        while let Some(left) = (*node.as_ptr()).left {
+            node = left;
+        }
+        node
+    }
+
+    /// Returns the first node of the subtree rooted at `node` in post-order,
+    /// which is reached by going down and preferring the left child.
+    unsafe fn first_post_order(mut node: NonNull<TreeNode<T>>) -> NonNull<TreeNode<T>> {
+        loop {
+            let raw = &*node.as_ptr();
+            match (raw.left, raw.right) {
+                (Some(left), _) => node = left,
+                (None, Some(right)) => node = right,
+                (None, None) => return node,
+            }
+        }
+    }
+
+    /// Returns the in-order successor of a node.
+    unsafe fn successor(node: NonNull<TreeNode<T>>) -> Link<T> {
+        if let Some(right) = (*node.as_ptr()).right {
+            return Some(TreeNode::leftmost(right));
+        }
+
+        let mut curr = node;
+        while let Some(parent) = (*curr.as_ptr()).parent {
+            if (*parent.as_ptr()).left == Some(curr) {
+                return Some(parent);
+            }
+            curr = parent;
+        }
+        None
+    }
+
+    /// Returns the post-order successor of a node.
+    ///
+    /// Only the parent of `node` is dereferenced, so the node itself may be
+    /// freed right after its successor is known.
+    unsafe fn post_order_successor(node: NonNull<TreeNode<T>>) -> Link<T> {
+        let parent = (*node.as_ptr()).parent?;
+        match (*parent.as_ptr()).right {
+            Some(right) if (*parent.as_ptr()).left == Some(node) => {
+                Some(TreeNode::first_post_order(right))
+            }
+            _ => Some(parent),
+        }
+    }
 }
 
 pub struct BinarySearchTree<T>
@@ -27,7 +95,7 @@ where
     T: PartialOrd,
 {
     size: usize,
-    root: Option<NonNull<TreeNode<T>>>,
+    root: Link<T>,
     _marker: PhantomData<Box<TreeNode<T>>>,
 }
 
@@ -53,6 +121,74 @@ impl<T: PartialOrd> BinarySearchTree<T> {
         }
     }
 
+    /// Builds a perfectly balanced binary search tree from sorted input in
+    /// *O*(*n*) time.
+    ///
+    /// Returns an error if the input is not sorted in ascending order, which
+    /// includes input with incomparable elements such as `NaN`.
+    ///
+    /// # Examples
+    /// ```
+    /// use boost_rs::collection::bst::BinarySearchTree;
+    /// let tree = BinarySearchTree::from_sorted_iter(1..=7).unwrap();
+    /// assert_eq!(tree.size(), 7);
+    /// assert_eq!(tree.height(), 3);
+    ///
+    /// assert!(BinarySearchTree::from_sorted_iter(vec![2, 1]).is_err());
+    /// ```
+    pub fn from_sorted_iter<I>(iter: I) -> Result<Self, CollectionError>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let values: Vec<T> = iter.into_iter().collect();
+        if !values.windows(2).all(|w| w[0] <= w[1]) {
+            return Err(CollectionError::InvalidParameter(
+                "input must be sorted in ascending order".to_string(),
+            ));
+        }
+        Ok(Self::_from_sorted_vec(values))
+    }
+
+    fn _from_sorted_vec(values: Vec<T>) -> Self {
+        let nodes: Vec<_> = values
+            .into_iter()
+            .map(|val| NonNull::from(Box::leak(Box::new(TreeNode::new(val)))))
+            .collect();
+        Self {
+            size: nodes.len(),
+            root: unsafe { Self::_link_balanced(&nodes, None) },
+            _marker: PhantomData,
+        }
+    }
+
+    /// Links the sorted nodes into a perfectly balanced subtree hanging from
+    /// `parent`, and returns the root of the subtree.
+    unsafe fn _link_balanced(nodes: &[NonNull<TreeNode<T>>], parent: Link<T>) -> Link<T> {
+        if nodes.is_empty() {
+            return None;
+        }
+
+        let mid = nodes.len() / 2;
+        let node = nodes[mid];
+        (*node.as_ptr()).parent = parent;
+        (*node.as_ptr()).left = Self::_link_balanced(&nodes[..mid], Some(node));
+        (*node.as_ptr()).right = Self::_link_balanced(&nodes[mid + 1..], Some(node));
+        Some(node)
+    }
+
+    /// Unlinks all nodes from the tree and returns them in order.
+    fn _take_nodes(&mut self) -> Vec<NonNull<TreeNode<T>>> {
+        let mut nodes = Vec::with_capacity(self.size);
+        let mut curr = self._min();
+        while let Some(node) = curr {
+            nodes.push(node);
+            curr = unsafe { TreeNode::successor(node) };
+        }
+        self.root = None;
+        self.size = 0;
+        nodes
+    }
+
     /// Returns the number of elements in the binary search tree.
     /// # Examples
     /// ```
@@ -97,8 +233,7 @@ impl<T: PartialOrd> BinarySearchTree<T> {
             return None;
         }
 
-        self._min()
-            .map(|node| unsafe { self.remove(&(*node.as_ptr()).val).unwrap() })
+        self._min().map(|node| self._remove_node(node))
     }
 
     /// Insert a value into the binary search tree.
@@ -182,97 +317,45 @@ impl<T: PartialOrd> BinarySearchTree<T> {
     }
 
     /// Remove a node from the binary search tree.
-    fn _remove_node(&mut self, mut node: NonNull<TreeNode<T>>) -> T {
-        let mut removed_node = unsafe { Box::from_raw(node.as_mut()) };
-
-        // node has no children
-        if removed_node.left.is_none() && removed_node.right.is_none() {
-            // node is root
-            if removed_node.parent.is_none() {
-                self.root = None;
-            } else {
-                let parent = unsafe { &mut *removed_node.parent.unwrap().as_mut() };
-                if parent.left == Some(node) {
-                    parent.left = None;
-                } else {
-                    parent.right = None;
-                }
-            }
-        } else if removed_node.left.is_none() || removed_node.right.is_none() {
-            // node has one child, find the child
-            let mut child = if removed_node.left.is_some() {
-                removed_node.left.unwrap()
-            } else {
-                removed_node.right.unwrap()
+    fn _remove_node(&mut self, node: NonNull<TreeNode<T>>) -> T {
+        unsafe {
+            let (left, right, parent) = {
+                let raw = &*node.as_ptr();
+                (raw.left, raw.right, raw.parent)
             };
 
-            // node is root
-            if removed_node.parent.is_none() {
-                self.root = Some(child);
-                unsafe {
-                    (*child.as_ptr()).parent = None;
-                }
-            } else {
-                // node is not root
-                unsafe {
-                    let parent = &mut *removed_node.parent.unwrap().as_mut();
-                    if parent.left == Some(node) {
-                        parent.left = Some(child);
-                    } else {
-                        parent.right = Some(child);
-                    }
-
-                    child.as_mut().parent = removed_node.parent;
-                }
+            // node has two children: its successor has no left child, so swap
+            // the values and remove the successor instead
+            if let (Some(_), Some(right)) = (left, right) {
+                let successor = TreeNode::leftmost(right);
+                mem::swap(&mut (*node.as_ptr()).val, &mut (*successor.as_ptr()).val);
+                return self._remove_node(successor);
             }
-        } else {
-            // node has two children
-            let successor = self._find_successor(node);
-            let successor_node = successor.unwrap();
-            let successor_parent =
-                unsafe { &mut (*successor_node.as_ptr()).parent.unwrap().as_mut() };
-
-            // swap the values
-            mem::swap(&mut removed_node.val, &mut successor_parent.val);
-        }
 
-        removed_node.left = None;
-        removed_node.right = None;
-        removed_node.parent = None;
+            // node has at most one child, lift the child into the node's place
+            let child = left.or(right);
+            if let Some(child) = child {
+                (*child.as_ptr()).parent = parent;
+            }
+            self._replace_child(parent, node, child);
 
-        self.size -= 1;
+            self.size -= 1;
 
-        removed_node.val
+            Box::from_raw(node.as_ptr()).val
+        }
     }
 
-    /// Find the successor of a node.
-    fn _find_successor(&self, node: NonNull<TreeNode<T>>) -> Option<NonNull<TreeNode<T>>> {
-        let curr = unsafe { &*node.as_ptr() };
-        if curr.right.is_some() {
-            let mut curr = curr.right.unwrap();
-            loop {
-                unsafe {
-                    if (*curr.as_ptr()).left.is_none() {
-                        return Some(curr);
-                    } else {
-                        curr = (*curr.as_ptr()).left.unwrap();
-                    }
-                }
-            }
-        } else {
-            let mut curr = node;
-            loop {
-                unsafe {
-                    if (*curr.as_ptr()).parent.is_none() {
-                        return None;
-                    } else {
-                        let parent = (*curr.as_ptr()).parent.unwrap();
-                        if (*parent.as_ptr()).right == Some(curr) {
-                            return Some(parent);
-                        } else {
-                            curr = parent;
-                        }
-                    }
+    /// Replace the link from `parent` to `old` with `new`, where `parent` being
+    /// `None` means that `old` is the root.
+    unsafe fn _replace_child(&mut self, parent: Link<T>, old: NonNull<TreeNode<T>>, new: Link<T>) {
+        match parent {
+            None => self.root = new,
+            Some(parent) => {
+                let parent = &mut *parent.as_ptr();
+                if parent.left == Some(old) {
+                    parent.left = new;
+                } else {
+                    parent.right = new;
                 }
             }
         }
@@ -301,7 +384,7 @@ impl<T: PartialOrd> BinarySearchTree<T> {
     /// //assert!(tree._find_node(&1).is_some());
     /// //assert!(tree._find_node(&2).is_none());
     /// ```
-    fn _find_node(&self, elem: &T) -> Option<NonNull<TreeNode<T>>> {
+    fn _find_node(&self, elem: &T) -> Link<T> {
         if self.is_empty() {
             return None;
         }
@@ -339,7 +422,7 @@ impl<T: PartialOrd> BinarySearchTree<T> {
         BinarySearchTree::_height(self.root)
     }
 
-    fn _height(root: Option<NonNull<TreeNode<T>>>) -> usize {
+    fn _height(root: Link<T>) -> usize {
         if root.is_none() {
             return 0;
         }
@@ -369,18 +452,8 @@ impl<T: PartialOrd> BinarySearchTree<T> {
         self._min().map(|x| unsafe { &(*x.as_ptr()).val })
     }
 
-    fn _min(&self) -> Option<NonNull<TreeNode<T>>> {
-        self.root?;
-
-        let mut node = self.root.unwrap();
-        loop {
-            unsafe {
-                if (*node.as_ptr()).left.is_none() {
-                    return Some(node);
-                }
-                node = (*node.as_ptr()).left.unwrap();
-            }
-        }
+    fn _min(&self) -> Link<T> {
+        self.root.map(|root| unsafe { TreeNode::leftmost(root) })
     }
 
     /// Returns the maximum element of the binary search tree.
@@ -400,7 +473,7 @@ impl<T: PartialOrd> BinarySearchTree<T> {
         self._max().map(|x| unsafe { &(*x.as_ptr()).val })
     }
 
-    fn _max(&self) -> Option<NonNull<TreeNode<T>>> {
+    fn _max(&self) -> Link<T> {
         self.root?;
 
         let mut node = self.root.unwrap();
@@ -410,87 +483,427 @@ impl<T: PartialOrd> BinarySearchTree<T> {
         Some(node)
     }
 
-    pub fn iter(&self) -> Iter<T> {
+    /// Splits the tree into two at the given key. Returns everything after the
+    /// given key, including the key.
+    ///
+    /// Only the nodes on the search path of `key` are relinked, so the split
+    /// itself takes *O*(*h*) time; counting the split off elements takes *O*(*k*) time.
+    ///
+    /// # Examples
+    /// ```
+    /// use boost_rs::collection::bst::BinarySearchTree;
+    /// let mut a = BinarySearchTree::from(vec![1, 2, 3, 4, 5]);
+    /// let b = a.split_off(&3);
+    /// assert_eq!(a.iter().collect::<Vec<_>>(), vec![&1, &2]);
+    /// assert_eq!(b.iter().collect::<Vec<_>>(), vec![&3, &4, &5]);
+    /// ```
+    pub fn split_off(&mut self, key: &T) -> Self {
+        let mut left_root: Link<T> = None;
+        let mut right_root: Link<T> = None;
+
+        // The link waiting for the next node of each part, along with the owner
+        // of the link, which becomes the parent of the next node.
+        let mut left_slot: (*mut Link<T>, Link<T>) = (&mut left_root, None);
+        let mut right_slot: (*mut Link<T>, Link<T>) = (&mut right_root, None);
+
+        let mut curr = self.root.take();
+        unsafe {
+            while let Some(node) = curr {
+                let raw = node.as_ptr();
+                if (*raw).val < *key {
+                    *left_slot.0 = Some(node);
+                    (*raw).parent = left_slot.1;
+                    curr = (*raw).right;
+                    left_slot = (&mut (*raw).right, Some(node));
+                } else {
+                    *right_slot.0 = Some(node);
+                    (*raw).parent = right_slot.1;
+                    curr = (*raw).left;
+                    right_slot = (&mut (*raw).left, Some(node));
+                }
+            }
+            *left_slot.0 = None;
+            *right_slot.0 = None;
+        }
+
+        let moved = Self::_count(right_root);
+        self.root = left_root;
+        self.size -= moved;
+
+        Self {
+            size: moved,
+            root: right_root,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Counts the nodes of the subtree rooted at `root`, which must have no parent.
+    fn _count(root: Link<T>) -> usize {
+        let mut count = 0;
+        let mut curr = root.map(|root| unsafe { TreeNode::first_post_order(root) });
+        while let Some(node) = curr {
+            count += 1;
+            curr = unsafe { TreeNode::post_order_successor(node) };
+        }
+        count
+    }
+
+    /// Moves all elements from `other` into `self`, leaving `other` empty.
+    ///
+    /// The nodes of both trees are merged in *O*(*n* + *m*) time and relinked into a
+    /// perfectly balanced tree. Elements of `other` are placed after the equal
+    /// elements of `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use boost_rs::collection::bst::BinarySearchTree;
+    /// let mut a = BinarySearchTree::from(vec![1, 3, 5]);
+    /// let mut b = BinarySearchTree::from(vec![2, 4, 6]);
+    /// a.append(&mut b);
+    /// assert!(b.is_empty());
+    /// assert_eq!(a.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6]);
+    /// ```
+    pub fn append(&mut self, other: &mut Self) {
+        if other.is_empty() {
+            return;
+        }
+
+        let mut left = self._take_nodes().into_iter().peekable();
+        let mut right = other._take_nodes().into_iter().peekable();
+        let mut merged = Vec::with_capacity(left.len() + right.len());
+        loop {
+            let next = match (left.peek(), right.peek()) {
+                (Some(l), Some(r)) => unsafe {
+                    if (*r.as_ptr()).val < (*l.as_ptr()).val {
+                        right.next()
+                    } else {
+                        left.next()
+                    }
+                },
+                (Some(_), None) => left.next(),
+                (None, Some(_)) => right.next(),
+                (None, None) => break,
+            };
+            merged.extend(next);
+        }
+
+        self.size = merged.len();
+        self.root = unsafe { Self::_link_balanced(&merged, None) };
+    }
+
+    /// Returns an iterator visiting the elements in order.
+    pub fn iter(&self) -> Iter<'_, T> {
         Iter {
-            root: self.root,
+            next: self._min(),
+            len: self.size,
             _marker: PhantomData,
         }
     }
 
-    pub fn iter_mut(&mut self) -> IterMut<T> {
+    /// Returns an iterator visiting the elements in order, with mutable references.
+    ///
+    /// Changing the elements in a way that breaks their order is a logic error.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         IterMut {
-            root: self.root,
+            next: self._min(),
+            len: self.size,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator visiting the elements in pre-order: each node comes
+    /// before its left subtree, which comes before its right subtree.
+    ///
+    /// # Examples
+    /// ```
+    /// use boost_rs::collection::bst::BinarySearchTree;
+    /// let tree = BinarySearchTree::from(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.pre_order().collect::<Vec<_>>(), vec![&3, &2, &1, &5, &4]);
+    /// ```
+    pub fn pre_order(&self) -> PreOrder<'_, T> {
+        PreOrder {
+            stack: self.root.into_iter().collect(),
+            len: self.size,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator visiting the elements in post-order: each node comes
+    /// after its left subtree, which comes after its right subtree.
+    ///
+    /// # Examples
+    /// ```
+    /// use boost_rs::collection::bst::BinarySearchTree;
+    /// let tree = BinarySearchTree::from(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.post_order().collect::<Vec<_>>(), vec![&1, &2, &4, &5, &3]);
+    /// ```
+    pub fn post_order(&self) -> PostOrder<'_, T> {
+        PostOrder {
+            next: self
+                .root
+                .map(|root| unsafe { TreeNode::first_post_order(root) }),
+            len: self.size,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator visiting the elements level by level (breadth-first),
+    /// from left to right within a level.
+    ///
+    /// # Examples
+    /// ```
+    /// use boost_rs::collection::bst::BinarySearchTree;
+    /// let tree = BinarySearchTree::from(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.level_order().collect::<Vec<_>>(), vec![&3, &2, &5, &1, &4]);
+    /// ```
+    pub fn level_order(&self) -> LevelOrder<'_, T> {
+        LevelOrder {
+            queue: self.root.into_iter().collect(),
+            len: self.size,
             _marker: PhantomData,
         }
     }
+
+    /// Visits the elements representing the union, i.e., all the elements in
+    /// `self` or `other`, in ascending order.
+    ///
+    /// # Examples
+    /// ```
+    /// use boost_rs::collection::bst::BinarySearchTree;
+    /// let a = BinarySearchTree::from(vec![1, 2, 3]);
+    /// let b = BinarySearchTree::from(vec![2, 3, 4]);
+    /// assert_eq!(a.union(&b).copied().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    /// ```
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<'a, T, Iter<'a, T>, PartialCmp<T>> {
+        Union::new(self.iter(), other.iter(), partial_cmp)
+    }
+
+    /// Visits the elements representing the intersection, i.e., the elements
+    /// that are both in `self` and `other`, in ascending order.
+    ///
+    /// # Examples
+    /// ```
+    /// use boost_rs::collection::bst::BinarySearchTree;
+    /// let a = BinarySearchTree::from(vec![1, 2, 3]);
+    /// let b = BinarySearchTree::from(vec![2, 3, 4]);
+    /// assert_eq!(a.intersection(&b).copied().collect::<Vec<_>>(), vec![2, 3]);
+    /// ```
+    pub fn intersection<'a>(
+        &'a self,
+        other: &'a Self,
+    ) -> Intersection<'a, T, Iter<'a, T>, PartialCmp<T>> {
+        Intersection::new(self.iter(), other.iter(), partial_cmp)
+    }
+
+    /// Visits the elements representing the difference, i.e., the elements
+    /// that are in `self` but not in `other`, in ascending order.
+    ///
+    /// # Examples
+    /// ```
+    /// use boost_rs::collection::bst::BinarySearchTree;
+    /// let a = BinarySearchTree::from(vec![1, 2, 3]);
+    /// let b = BinarySearchTree::from(vec![2, 3, 4]);
+    /// assert_eq!(a.difference(&b).copied().collect::<Vec<_>>(), vec![1]);
+    /// ```
+    pub fn difference<'a>(
+        &'a self,
+        other: &'a Self,
+    ) -> Difference<'a, T, Iter<'a, T>, PartialCmp<T>> {
+        Difference::new(self.iter(), other.iter(), partial_cmp)
+    }
+
+    /// Visits the elements representing the symmetric difference, i.e., the
+    /// elements that are in `self` or in `other` but not in both, in ascending order.
+    ///
+    /// # Examples
+    /// ```
+    /// use boost_rs::collection::bst::BinarySearchTree;
+    /// let a = BinarySearchTree::from(vec![1, 2, 3]);
+    /// let b = BinarySearchTree::from(vec![2, 3, 4]);
+    /// assert_eq!(a.symmetric_difference(&b).copied().collect::<Vec<_>>(), vec![1, 4]);
+    /// ```
+    pub fn symmetric_difference<'a>(
+        &'a self,
+        other: &'a Self,
+    ) -> SymmetricDifference<'a, T, Iter<'a, T>, PartialCmp<T>> {
+        SymmetricDifference::new(self.iter(), other.iter(), partial_cmp)
+    }
+
+    /// Returns `true` if `self` is a subset of `other`, i.e., `other` contains
+    /// at least all the elements in `self`.
+    ///
+    /// This operation should compute in *O*(*n* + *m*) time.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.size <= other.size && set_ops::is_subset(self.iter(), other.iter(), partial_cmp)
+    }
+
+    /// Returns `true` if `self` has no elements in common with `other`.
+    ///
+    /// This operation should compute in *O*(*n* + *m*) time.
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        set_ops::is_disjoint(self.iter(), other.iter(), partial_cmp)
+    }
 }
 
 impl<T> BinarySearchTree<T>
 where
     T: PartialOrd + Display,
 {
-    pub fn traverse(&self) {
-        if self.root.is_none() {
-            println!("Empty tree");
-            return;
+    /// Exports the shape of the tree in the Graphviz DOT format, which is handy
+    /// for debugging, e.g. `dot -Tpng tree.dot -o tree.png`.
+    ///
+    /// Missing children of inner nodes are drawn as points, so left and right
+    /// links can be told apart.
+    ///
+    /// # Examples
+    /// ```
+    /// use boost_rs::collection::bst::BinarySearchTree;
+    /// let tree = BinarySearchTree::from(vec![1, 2]);
+    /// assert_eq!(
+    ///     tree.to_dot(),
+    ///     "digraph BinarySearchTree {\n    \
+    ///         node [shape=circle];\n    \
+    ///         n0 [label=\"2\"];\n    \
+    ///         n0 -> n1;\n    \
+    ///         n2 [shape=point];\n    \
+    ///         n0 -> n2;\n    \
+    ///         n1 [label=\"1\"];\n\
+    ///     }\n"
+    /// );
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph BinarySearchTree {\n");
+        dot.push_str("    node [shape=circle];\n");
+
+        // walk the tree level by level, numbering the nodes as they are discovered
+        let mut queue = VecDeque::new();
+        let mut next_id = 0;
+        if let Some(root) = self.root {
+            queue.push_back((root, next_id));
+            next_id += 1;
+        }
+        while let Some((node, id)) = queue.pop_front() {
+            let raw = unsafe { &*node.as_ptr() };
+            let label = raw
+                .val
+                .to_string()
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"");
+            let _ = writeln!(dot, "    n{} [label=\"{}\"];", id, label);
+
+            if raw.left.is_none() && raw.right.is_none() {
+                continue;
+            }
+            for child in [raw.left, raw.right] {
+                match child {
+                    Some(child) => queue.push_back((child, next_id)),
+                    None => {
+                        let _ = writeln!(dot, "    n{} [shape=point];", next_id);
+                    }
+                }
+                let _ = writeln!(dot, "    n{} -> n{};", id, next_id);
+                next_id += 1;
+            }
         }
 
-        let mut stack = Vec::new();
-        let mut node = self.root.unwrap();
-        print!("{{");
-        loop {
-            if unsafe { (*node.as_ptr()).left.is_some() } {
-                stack.push(node);
-                node = unsafe { (*node.as_ptr()).left.unwrap() };
-            } else {
-                unsafe {
-                    let raw_node = &(*node.as_ptr());
-                    print!("[");
+        dot.push_str("}\n");
+        dot
+    }
+}
 
-                    // print node
-                    print!("val: {}, ", raw_node.val);
+// The tree owns its nodes, like a `Box` would.
+unsafe impl<T: PartialOrd + Send> Send for BinarySearchTree<T> {}
 
-                    // print left child
-                    print!("left: ");
-                    if raw_node.left.is_some() {
-                        print!("{}, ", &(*raw_node.left.unwrap().as_ptr()).val);
-                    } else {
-                        print!("None, ");
-                    }
+unsafe impl<T: PartialOrd + Sync> Sync for BinarySearchTree<T> {}
 
-                    // print right child
-                    print!("right: ");
-                    if raw_node.right.is_some() {
-                        print!("{}, ", &(*raw_node.right.unwrap().as_ptr()).val);
-                    } else {
-                        print!("None, ");
-                    }
+impl<T: PartialOrd + Clone> Clone for BinarySearchTree<T> {
+    /// Clones the elements into a perfectly balanced tree, in *O*(*n*) time.
+    fn clone(&self) -> Self {
+        Self::_from_sorted_vec(self.iter().cloned().collect())
+    }
+}
 
-                    // print parent
-                    print!("parent: ");
-                    if raw_node.parent.is_some() {
-                        print!("{}", &(*raw_node.parent.unwrap().as_ptr()).val);
-                    } else {
-                        print!("None");
-                    }
+impl<T: PartialOrd + Debug> Debug for BinarySearchTree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
 
-                    print!("] ");
-                }
-                if unsafe { (*node.as_ptr()).right.is_some() } {
-                    node = unsafe { (*node.as_ptr()).right.unwrap() };
-                } else if !stack.is_empty() {
-                    node = stack.pop().unwrap();
-                } else {
-                    break;
-                }
-            }
+/// Two trees are equal if they contain equal elements in the same order,
+/// regardless of their shapes.
+impl<T: PartialOrd> PartialEq for BinarySearchTree<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: PartialOrd + Eq> Eq for BinarySearchTree<T> {}
+
+impl<T: PartialOrd + Hash> Hash for BinarySearchTree<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.size);
+        for x in self.iter() {
+            x.hash(state);
         }
-        println!("}}");
+    }
+}
+
+/// Trees are compared lexicographically by their elements in order.
+///
+/// `Ord` is deliberately not implemented: `Ord::min` and `Ord::max` take the
+/// tree by value, and would be picked over [`BinarySearchTree::min`] and
+/// [`BinarySearchTree::max`] by method calls on an owned tree.
+impl<T: PartialOrd> PartialOrd for BinarySearchTree<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: PartialOrd> FromIterator<T> for BinarySearchTree<T> {
+    /// Builds a balanced tree, see the `From<Vec<T>>` implementation.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<T: PartialOrd> Extend<T> for BinarySearchTree<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|x| self.insert(x));
+    }
+}
+
+impl<'a, T: 'a + PartialOrd + Copy> Extend<&'a T> for BinarySearchTree<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<'a, T: PartialOrd> IntoIterator for &'a BinarySearchTree<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
 impl<T: PartialOrd> From<Vec<T>> for BinarySearchTree<T> {
+    /// Builds a balanced tree by sorting the elements first in
+    /// *O*(*n* log *n*) time, the relative order of equal elements is kept.
+    ///
+    /// The balanced build needs the elements to be totally ordered. If any
+    /// two of them are incomparable, such as a `NaN` and anything else, the
+    /// elements are inserted one by one in their given order instead, as by
+    /// [`BinarySearchTree::insert`], which takes *O*(*n*²) time in the worst
+    /// case.
     fn from(s: Vec<T>) -> Self {
+        let order = _sort_indices(&s);
+        if order.windows(2).all(|w| s[w[0]] <= s[w[1]]) {
+            let mut slots: Vec<Option<T>> = s.into_iter().map(Some).collect();
+            let sorted = order.iter().map(|&i| slots[i].take().unwrap()).collect();
+            return Self::_from_sorted_vec(sorted);
+        }
+
         let mut tree = Self::new();
         for elem in s.into_iter() {
             tree.insert(elem);
@@ -499,6 +912,60 @@ impl<T: PartialOrd> From<Vec<T>> for BinarySearchTree<T> {
     }
 }
 
+/// Sorts the indices of the values stably with a bottom-up merge sort.
+///
+/// Unlike the sorts of the standard library, it never panics on a partial
+/// order: incomparable values are just left in an unspecified order.
+fn _sort_indices<T: PartialOrd>(values: &[T]) -> Vec<usize> {
+    let n = values.len();
+    let mut order: Vec<usize> = (0..n).collect();
+    let mut merged = Vec::with_capacity(n);
+    let mut width = 1;
+    while width < n {
+        merged.clear();
+        for start in (0..n).step_by(2 * width) {
+            let mid = (start + width).min(n);
+            let end = (start + 2 * width).min(n);
+            let (mut i, mut j) = (start, mid);
+            while i < mid && j < end {
+                // Ties are taken from the left, which keeps the sort stable.
+                if values[order[j]] < values[order[i]] {
+                    merged.push(order[j]);
+                    j += 1;
+                } else {
+                    merged.push(order[i]);
+                    i += 1;
+                }
+            }
+            merged.extend_from_slice(&order[i..mid]);
+            merged.extend_from_slice(&order[j..end]);
+        }
+        mem::swap(&mut order, &mut merged);
+        width *= 2;
+    }
+    order
+}
+
+impl<T> Drop for BinarySearchTree<T>
+where
+    T: PartialOrd,
+{
+    fn drop(&mut self) {
+        // free the nodes in post-order, so that children are freed before their parent
+        let mut curr = self
+            .root
+            .take()
+            .map(|root| unsafe { TreeNode::first_post_order(root) });
+        while let Some(node) = curr {
+            unsafe {
+                curr = TreeNode::post_order_successor(node);
+                drop(Box::from_raw(node.as_ptr()));
+            }
+        }
+        self.size = 0;
+    }
+}
+
 impl<T> IntoIterator for BinarySearchTree<T>
 where
     T: PartialOrd,
@@ -523,8 +990,6 @@ where
         // only need to ensure all our elements are read;
         // buffer will clean itself up afterwards.
         for _ in &mut *self {}
-
-        println!("IntoIter has been dropped!")
     }
 }
 
@@ -534,49 +999,184 @@ impl<T: PartialOrd> Iterator for IntoIter<T> {
     fn next(&mut self) -> Option<Self::Item> {
         self.tree.pop_min()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.tree.size, Some(self.tree.size))
+    }
 }
 
+impl<T: PartialOrd> ExactSizeIterator for IntoIter<T> {}
+
+impl<T: PartialOrd> FusedIterator for IntoIter<T> {}
+
 pub struct Iter<'a, T: 'a> {
-    root: Option<NonNull<TreeNode<T>>>,
+    next: Link<T>,
+    len: usize,
     _marker: PhantomData<&'a TreeNode<T>>,
 }
 
+unsafe impl<'a, T: Sync> Send for Iter<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
+
 impl<'a, T> Iterator for Iter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.root?;
-        let root = self.root.unwrap();
-        let left = unsafe { (*root.as_ptr()).left };
-        let right = unsafe { (*root.as_ptr()).right };
-        if left.is_none() {
-            self.root = right;
-            return Some(unsafe { &(*root.as_ptr()).val });
-        }
-        self.root = left;
-        self.next()
+        self.next.map(|node| unsafe {
+            self.len -= 1;
+            self.next = TreeNode::successor(node);
+            &(*node.as_ptr()).val
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
     }
 }
 
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
 pub struct IterMut<'a, T: 'a> {
-    root: Option<NonNull<TreeNode<T>>>,
+    next: Link<T>,
+    len: usize,
     _marker: PhantomData<&'a mut TreeNode<T>>,
 }
 
+unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
+
 impl<'a, T> Iterator for IterMut<'a, T> {
     type Item = &'a mut T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.root?;
-        let root = self.root.unwrap();
-        let left = unsafe { (*root.as_ptr()).left };
-        let right = unsafe { (*root.as_ptr()).right };
-        if left.is_none() {
-            self.root = right;
-            return Some(unsafe { &mut (*root.as_ptr()).val });
+        self.next.map(|node| unsafe {
+            self.len -= 1;
+            self.next = TreeNode::successor(node);
+            &mut (*node.as_ptr()).val
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<'a, T> FusedIterator for IterMut<'a, T> {}
+
+pub struct PreOrder<'a, T: 'a> {
+    stack: Vec<NonNull<TreeNode<T>>>,
+    len: usize,
+    _marker: PhantomData<&'a TreeNode<T>>,
+}
+
+unsafe impl<'a, T: Sync> Send for PreOrder<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for PreOrder<'a, T> {}
+
+impl<'a, T> Iterator for PreOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = unsafe { &*self.stack.pop()?.as_ptr() };
+        self.len -= 1;
+        self.stack.extend(node.right);
+        self.stack.extend(node.left);
+        Some(&node.val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for PreOrder<'a, T> {}
+
+impl<'a, T> FusedIterator for PreOrder<'a, T> {}
+
+pub struct PostOrder<'a, T: 'a> {
+    next: Link<T>,
+    len: usize,
+    _marker: PhantomData<&'a TreeNode<T>>,
+}
+
+unsafe impl<'a, T: Sync> Send for PostOrder<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for PostOrder<'a, T> {}
+
+impl<'a, T> Iterator for PostOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| unsafe {
+            self.len -= 1;
+            self.next = TreeNode::post_order_successor(node);
+            &(*node.as_ptr()).val
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for PostOrder<'a, T> {}
+
+impl<'a, T> FusedIterator for PostOrder<'a, T> {}
+
+pub struct LevelOrder<'a, T: 'a> {
+    queue: VecDeque<NonNull<TreeNode<T>>>,
+    len: usize,
+    _marker: PhantomData<&'a TreeNode<T>>,
+}
+
+unsafe impl<'a, T: Sync> Send for LevelOrder<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for LevelOrder<'a, T> {}
+
+impl<'a, T> Iterator for LevelOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = unsafe { &*self.queue.pop_front()?.as_ptr() };
+        self.len -= 1;
+        self.queue.extend(node.left);
+        self.queue.extend(node.right);
+        Some(&node.val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for LevelOrder<'a, T> {}
+
+impl<'a, T> FusedIterator for LevelOrder<'a, T> {}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::collection::bst::BinarySearchTree;
+
+    impl<T: PartialOrd + Serialize> Serialize for BinarySearchTree<T> {
+        /// Serializes the elements as a sequence, in ascending order.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.iter())
+        }
+    }
+
+    impl<'de, T: PartialOrd + Deserialize<'de>> Deserialize<'de> for BinarySearchTree<T> {
+        /// Deserializes a sequence of elements into a balanced tree.
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Vec::<T>::deserialize(deserializer).map(BinarySearchTree::from)
         }
-        self.root = left;
-        self.next()
     }
 }
 
@@ -617,6 +1217,24 @@ mod tests {
         assert!(tree.contains(&3));
     }
 
+    #[test]
+    fn test_remove_with_two_children() {
+        let mut tree = BinarySearchTree::new();
+        for x in [5, 3, 8, 1, 4, 7, 9, 6] {
+            tree.insert(x);
+        }
+
+        assert_eq!(tree.remove(&5), Some(5));
+        assert_eq!(tree.remove(&8), Some(8));
+        assert_eq!(tree.size(), 6);
+        assert!(!tree.contains(&5));
+        assert!(!tree.contains(&8));
+        assert_eq!(
+            tree.iter().copied().collect::<Vec<_>>(),
+            vec![1, 3, 4, 6, 7, 9]
+        );
+    }
+
     #[test]
     fn test_pop_min() {
         let mut tree = BinarySearchTree::new();
@@ -626,12 +1244,12 @@ mod tests {
         assert_eq!(tree.size(), 3);
 
         assert_eq!(tree.pop_min(), Some(1));
-        tree.traverse();
+        assert_eq!(tree.iter().copied().collect::<Vec<_>>(), vec![2, 3]);
         assert_eq!(tree.size(), 2);
         assert!(!tree.contains(&1));
 
         assert_eq!(tree.pop_min(), Some(2));
-        tree.traverse();
+        assert_eq!(tree.iter().copied().collect::<Vec<_>>(), vec![3]);
         assert_eq!(tree.size(), 1);
         assert!(!tree.contains(&2));
 
@@ -651,12 +1269,12 @@ mod tests {
         assert_eq!(tree.size(), 3);
 
         assert_eq!(tree.pop_min(), Some(1));
-        tree.traverse();
+        assert_eq!(tree.iter().copied().collect::<Vec<_>>(), vec![2, 3]);
         assert_eq!(tree.size(), 2);
         assert!(!tree.contains(&1));
 
         assert_eq!(tree.pop_min(), Some(2));
-        tree.traverse();
+        assert_eq!(tree.iter().copied().collect::<Vec<_>>(), vec![3]);
         assert_eq!(tree.size(), 1);
         assert!(!tree.contains(&2));
 
@@ -666,4 +1284,260 @@ mod tests {
 
         assert_eq!(tree.pop_min(), None);
     }
+
+    #[test]
+    fn test_iter() {
+        let mut tree = BinarySearchTree::new();
+        for x in [4, 2, 6, 1, 3, 5, 7] {
+            tree.insert(x);
+        }
+        assert_eq!(
+            tree.iter().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5, 6, 7]
+        );
+
+        tree.iter_mut().for_each(|x| *x *= 10);
+        assert_eq!(
+            tree.iter().copied().collect::<Vec<_>>(),
+            vec![10, 20, 30, 40, 50, 60, 70]
+        );
+        assert_eq!(
+            tree.into_iter().collect::<Vec<_>>(),
+            vec![10, 20, 30, 40, 50, 60, 70]
+        );
+
+        // Dropping a partly consumed iterator drops the rest of the elements.
+        let tree: BinarySearchTree<String> =
+            ["b", "a", "c"].iter().map(|s| s.to_string()).collect();
+        let mut iter = tree.into_iter();
+        assert_eq!(iter.next().as_deref(), Some("a"));
+        drop(iter);
+    }
+
+    #[test]
+    fn test_traversal_orders() {
+        let mut tree = BinarySearchTree::new();
+        for x in [4, 2, 6, 1, 3, 7] {
+            tree.insert(x);
+        }
+
+        assert_eq!(
+            tree.pre_order().copied().collect::<Vec<_>>(),
+            vec![4, 2, 1, 3, 6, 7]
+        );
+        assert_eq!(
+            tree.post_order().copied().collect::<Vec<_>>(),
+            vec![1, 3, 2, 7, 6, 4]
+        );
+        assert_eq!(
+            tree.level_order().copied().collect::<Vec<_>>(),
+            vec![4, 2, 6, 1, 3, 7]
+        );
+
+        let empty = BinarySearchTree::<i32>::new();
+        assert_eq!(empty.pre_order().count(), 0);
+        assert_eq!(empty.post_order().count(), 0);
+        assert_eq!(empty.level_order().count(), 0);
+    }
+
+    #[test]
+    fn test_from_sorted_iter() {
+        let tree = BinarySearchTree::from_sorted_iter(0..1023).unwrap();
+        assert_eq!(tree.size(), 1023);
+        assert_eq!(tree.height(), 10);
+        assert!(tree.iter().copied().eq(0..1023));
+
+        assert!(BinarySearchTree::from_sorted_iter(vec![1, 3, 2]).is_err());
+        assert!(
+            BinarySearchTree::from_sorted_iter(vec![4.0, 5.0, f64::NAN, 1.0, 2.0, 3.0, 6.0])
+                .is_err()
+        );
+        assert!(BinarySearchTree::from_sorted_iter(vec![1.0, f64::NAN]).is_err());
+    }
+
+    #[test]
+    fn test_from_vec() {
+        let tree = BinarySearchTree::from(vec![5, 1, 4, 2, 3, 3]);
+        assert_eq!(tree.size(), 6);
+        assert_eq!(tree.height(), 3);
+        assert_eq!(
+            tree.iter().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn test_from_vec_incomparable() {
+        // Balanced when totally ordered, which floats without NaN are.
+        let tree = BinarySearchTree::from((0..127).rev().map(f64::from).collect::<Vec<_>>());
+        assert_eq!(tree.height(), 7);
+
+        // Otherwise the elements are inserted one by one in the given order,
+        // so sorted input gives a degenerate tree.
+        let mut values: Vec<f64> = (0..100).map(f64::from).collect();
+        values.push(f64::NAN);
+        let tree = BinarySearchTree::from(values);
+        assert_eq!(tree.size(), 101);
+        assert_eq!(tree.height(), 101);
+        let mut expected = BinarySearchTree::new();
+        (0..100).for_each(|x| expected.insert(f64::from(x)));
+        expected.insert(f64::NAN);
+        assert!(tree
+            .pre_order()
+            .zip(expected.pre_order())
+            .all(|(a, b)| a.to_bits() == b.to_bits()));
+    }
+
+    #[test]
+    fn test_split_off() {
+        let mut tree = BinarySearchTree::new();
+        for x in [50, 30, 70, 20, 40, 60, 80, 35, 45, 65] {
+            tree.insert(x);
+        }
+
+        let right = tree.split_off(&45);
+        assert_eq!(tree.size(), 4);
+        assert_eq!(right.size(), 6);
+        assert_eq!(
+            tree.iter().copied().collect::<Vec<_>>(),
+            vec![20, 30, 35, 40]
+        );
+        assert_eq!(
+            right.iter().copied().collect::<Vec<_>>(),
+            vec![45, 50, 60, 65, 70, 80]
+        );
+        assert_eq!(right.post_order().count() + tree.post_order().count(), 10);
+
+        let mut tree = BinarySearchTree::from(vec![1, 2, 3]);
+        assert!(tree.split_off(&10).is_empty());
+        let all = tree.split_off(&0);
+        assert!(tree.is_empty());
+        assert_eq!(all.size(), 3);
+    }
+
+    #[test]
+    fn test_append() {
+        let mut a = BinarySearchTree::new();
+        let mut b = BinarySearchTree::new();
+        for x in 0..100 {
+            if x % 3 == 0 {
+                a.insert(x);
+            } else {
+                b.insert(x);
+            }
+        }
+
+        a.append(&mut b);
+        assert!(b.is_empty());
+        assert_eq!(a.size(), 100);
+        assert_eq!(a.height(), 7);
+        assert!(a.iter().copied().eq(0..100));
+
+        b.append(&mut a);
+        assert!(a.is_empty());
+        assert_eq!(b.size(), 100);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let tree = BinarySearchTree::from(vec![1, 2, 3]);
+        let dot = tree.to_dot();
+        assert!(dot.starts_with("digraph BinarySearchTree {"));
+        assert!(dot.contains("n0 [label=\"2\"];"));
+        assert!(dot.contains("n0 -> n1;"));
+        assert!(dot.contains("n0 -> n2;"));
+        assert!(!dot.contains("shape=point"));
+
+        let empty = BinarySearchTree::<i32>::new();
+        assert_eq!(
+            empty.to_dot(),
+            "digraph BinarySearchTree {\n    node [shape=circle];\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = BinarySearchTree::from(vec![1, 3, 5, 7, 9]);
+        let b = BinarySearchTree::from(vec![3, 4, 5, 6]);
+
+        assert_eq!(
+            a.union(&b).copied().collect::<Vec<_>>(),
+            vec![1, 3, 4, 5, 6, 7, 9]
+        );
+        assert_eq!(a.intersection(&b).copied().collect::<Vec<_>>(), vec![3, 5]);
+        assert_eq!(a.difference(&b).copied().collect::<Vec<_>>(), vec![1, 7, 9]);
+        assert_eq!(
+            a.symmetric_difference(&b).copied().collect::<Vec<_>>(),
+            vec![1, 4, 6, 7, 9]
+        );
+
+        assert!(!a.is_subset(&b));
+        assert!(BinarySearchTree::from(vec![3, 9]).is_subset(&a));
+        assert!(BinarySearchTree::new().is_subset(&a));
+        assert!(!a.is_disjoint(&b));
+        assert!(a.is_disjoint(&BinarySearchTree::from(vec![2, 4, 6])));
+    }
+
+    #[test]
+    fn test_std_traits() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash_of<T: Hash>(x: &T) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            x.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let a: BinarySearchTree<i32> = vec![3, 1, 2, 2].into_iter().collect();
+        let mut b = BinarySearchTree::new();
+        b.extend(&[1, 2, 2, 3]);
+        assert_eq!(a, b);
+        assert_ne!(a.height(), b.height());
+        assert_eq!(hash_of(&a), hash_of(&b));
+        assert_eq!(format!("{:?}", a), "[1, 2, 2, 3]");
+
+        let c = b.clone();
+        assert_eq!(c, b);
+        assert_eq!(c.height(), 3);
+
+        b.insert(0);
+        assert!(b < a);
+        assert_eq!(b.iter().len(), 5);
+        assert_eq!(b.pre_order().len(), 5);
+        assert_eq!(b.level_order().len(), 5);
+        assert_eq!((&b).into_iter().count(), 5);
+        assert_eq!(b.into_iter().len(), 5);
+
+        let mut it = a.iter();
+        it.by_ref().for_each(drop);
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn test_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<BinarySearchTree<String>>();
+        assert_send_sync::<Iter<'_, String>>();
+
+        let tree: BinarySearchTree<i32> = (0..100).collect();
+        let sum = std::thread::scope(|s| s.spawn(|| tree.iter().sum::<i32>()).join().unwrap());
+        assert_eq!(sum, 4950);
+        let tree = std::thread::spawn(move || tree).join().unwrap();
+        assert_eq!(tree.size(), 100);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let tree: BinarySearchTree<i32> = (0..15).rev().collect();
+        let json = serde_json::to_string(&tree).unwrap();
+        assert_eq!(
+            json,
+            serde_json::to_string(&(0..15).collect::<Vec<_>>()).unwrap()
+        );
+
+        let de: BinarySearchTree<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(de, tree);
+        assert_eq!(de.height(), 4);
+    }
 }
@@ -2,12 +2,12 @@
 
 use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
 use std::hash::{BuildHasher, Hash};
-use std::ptr::NonNull;
+use std::mem;
 
 use crate::collection::cache::Cache;
-use crate::collection::linkedlist::{LinkedList, Node};
+use crate::collection::linkedlist::{LinkedList, NodeHandle};
 
 const DEFAULT_CAPACITY: usize = 1024;
 
@@ -31,21 +31,11 @@ impl<K: Eq + Hash + Clone, V> LruEntry<K, V> {
 }
 
 pub struct LruCache<K: Eq + Hash + Clone, V, S: BuildHasher = RandomState> {
-    map: HashMap<K, NonNull<Node<LruEntry<K, V>>>, S>,
+    map: HashMap<K, NodeHandle, S>,
     cache: LinkedList<LruEntry<K, V>>,
     cap: usize,
 }
 
-impl<K: Eq + Hash + Clone + Debug, V: Debug, S: BuildHasher> LruCache<K, V, S> {
-    pub fn traverse(&self) {
-        print!("{{ ");
-        for entry in self.cache.iter() {
-            print!("({:?}, {:?}) -> ", &entry.key, &entry.value);
-        }
-        println!("None }}");
-    }
-}
-
 impl<K: Eq + Hash + Clone, V> LruCache<K, V, RandomState> {
     pub fn with_capacity(cap: usize) -> Self {
         LruCache {
@@ -84,30 +74,31 @@ impl<K: Eq + Hash + Clone, V> Default for LruCache<K, V, RandomState> {
     }
 }
 
+/// Formats the entries as a map, from the most to the least recently used.
+impl<K: Eq + Hash + Clone + Debug, V: Debug, S: BuildHasher> Debug for LruCache<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.cache.iter().map(|entry| (&entry.key, &entry.value)))
+            .finish()
+    }
+}
+
 impl<K: Eq + Hash + Clone, V, S: BuildHasher> Cache<K, V> for LruCache<K, V, S> {
     fn get(&mut self, key: &K) -> Option<&V> {
-        let node = self.map.get(key)?;
-
-        let val = unsafe { &node.as_ref().val().value };
+        let node = *self.map.get(key)?;
 
-        self.cache.move_raw_node_to_head(*node);
+        self.cache.move_to_front(node).ok()?;
 
-        Some(val)
+        self.cache.peek_front().map(|entry| &entry.value)
     }
 
     fn put(&mut self, key: K, value: V) -> Option<V> {
-        let new_key = key.clone();
-        let new_node = LruEntry::new(key, value);
-        let new_node = Box::new(Node::new(new_node));
-        let new_node = NonNull::new(Box::into_raw(new_node)).unwrap();
-
-        match self.map.get(&new_key) {
-            Some(val) => unsafe {
-                let removed = self.cache.remove_by_val(val.as_ref().val())?;
-                self.cache._push_front_raw(new_node);
-                self.map.insert(new_key, new_node);
-                Some(removed.value)
-            },
+        match self.map.get(&key) {
+            Some(&node) => {
+                self.cache.move_to_front(node).ok()?;
+                let entry = self.cache.peek_front_mut()?;
+                Some(mem::replace(&mut entry.value, value))
+            }
             None => {
                 // Not found
                 let mut val = None;
@@ -118,7 +109,8 @@ impl<K: Eq + Hash + Clone, V, S: BuildHasher> Cache<K, V> for LruCache<K, V, S>
                         val = Some(entry.value);
                     }
                 }
-                self.cache._push_front_raw(new_node);
+                let new_key = key.clone();
+                let new_node = self.cache.push_front_handle(LruEntry::new(key, value));
                 self.map.insert(new_key, new_node);
                 val
             }
@@ -130,6 +122,90 @@ impl<K: Eq + Hash + Clone, V, S: BuildHasher> Cache<K, V> for LruCache<K, V, S>
     }
 }
 
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use std::collections::HashMap;
+    use std::hash::{BuildHasher, Hash};
+
+    use serde::de::Error;
+    use serde::ser::SerializeStruct;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::collection::cache::lru::{LruCache, LruEntry};
+    use crate::collection::error::CollectionError;
+    use crate::collection::linkedlist::LinkedList;
+
+    /// The entries of the cache, from the most to the least recently used.
+    struct Entries<'a, K: Eq + Hash + Clone, V>(&'a LinkedList<LruEntry<K, V>>);
+
+    impl<'a, K, V> Serialize for Entries<'a, K, V>
+    where
+        K: Eq + Hash + Clone + Serialize,
+        V: Serialize,
+    {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.0.iter().map(|entry| (&entry.key, &entry.value)))
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename = "LruCache")]
+    struct LruCacheRepr<K, V> {
+        capacity: usize,
+        entries: Vec<(K, V)>,
+    }
+
+    impl<K, V, S> Serialize for LruCache<K, V, S>
+    where
+        K: Eq + Hash + Clone + Serialize,
+        V: Serialize,
+        S: BuildHasher,
+    {
+        /// Serializes the capacity, and the entries as a sequence of key-value
+        /// pairs from the most to the least recently used.
+        fn serialize<Se: Serializer>(&self, serializer: Se) -> Result<Se::Ok, Se::Error> {
+            let mut state = serializer.serialize_struct("LruCache", 2)?;
+            state.serialize_field("capacity", &self.cap)?;
+            state.serialize_field("entries", &Entries(&self.cache))?;
+            state.end()
+        }
+    }
+
+    impl<'de, K, V, S> Deserialize<'de> for LruCache<K, V, S>
+    where
+        K: Eq + Hash + Clone + Deserialize<'de>,
+        V: Deserialize<'de>,
+        S: BuildHasher + Default,
+    {
+        /// Deserializes a cache with the same capacity and order of recency.
+        ///
+        /// Repeated keys and more entries than the capacity are rejected.
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let repr = LruCacheRepr::<K, V>::deserialize(deserializer)?;
+            if repr.entries.len() > repr.capacity {
+                return Err(D::Error::custom(CollectionError::CapacityExceeded {
+                    capacity: repr.capacity,
+                }));
+            }
+
+            let mut map = HashMap::with_capacity_and_hasher(repr.entries.len(), S::default());
+            let mut cache = LinkedList::new();
+            for (key, value) in repr.entries {
+                if map.contains_key(&key) {
+                    return Err(D::Error::custom(CollectionError::DuplicateKey));
+                }
+                let node = cache.push_back_handle(LruEntry::new(key.clone(), value));
+                map.insert(key, node);
+            }
+            Ok(LruCache {
+                map,
+                cache,
+                cap: repr.capacity,
+            })
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::hash_map::RandomState;
@@ -151,19 +227,33 @@ mod tests {
         l.put("2".to_string(), 2);
         l.put("3".to_string(), 3);
         l.put("4".to_string(), 4);
-        l.traverse();
+        assert_eq!(format!("{:?}", l), r#"{"4": 4, "3": 3, "2": 2, "1": 1}"#);
 
         assert_eq!(l.get(&"1".to_string()), Some(&1));
         assert_eq!(l.get(&"2".to_string()), Some(&2));
         assert_eq!(l.get(&"3".to_string()), Some(&3));
         assert_eq!(l.get(&"4".to_string()), Some(&4));
         assert_eq!(l.get(&"5".to_string()), None);
-        l.traverse();
+        assert_eq!(format!("{:?}", l), r#"{"4": 4, "3": 3, "2": 2, "1": 1}"#);
 
         l.put("5".to_string(), 5);
         assert_eq!(l.get(&"5".to_string()), Some(&5));
         assert_eq!(l.get(&"1".to_string()), None); // Cache cleaned
-        l.traverse();
+        assert_eq!(format!("{:?}", l), r#"{"5": 5, "4": 4, "3": 3, "2": 2}"#);
+    }
+
+    #[test]
+    fn test_put_existing() {
+        let mut l = LruCache::with_capacity(2);
+        assert_eq!(l.put("1".to_string(), 1), None);
+        assert_eq!(l.put("2".to_string(), 2), None);
+        assert_eq!(l.put("1".to_string(), 10), Some(1));
+
+        // "1" was refreshed by the update, so "2" is evicted.
+        assert_eq!(l.put("3".to_string(), 3), Some(2));
+        assert_eq!(l.get(&"2".to_string()), None);
+        assert_eq!(l.get(&"1".to_string()), Some(&10));
+        assert_eq!(l.get(&"3".to_string()), Some(&3));
     }
 
     #[test]
@@ -172,13 +262,44 @@ mod tests {
         l.put("1".to_string(), 1);
         l.put("2".to_string(), 2);
         l.put("3".to_string(), 3);
-        l.traverse();
+        assert_eq!(format!("{:?}", l), r#"{"3": 3, "2": 2, "1": 1}"#);
         l.put("4".to_string(), 4);
         l.put("5".to_string(), 5);
-        l.traverse();
+        assert_eq!(format!("{:?}", l), r#"{"5": 5, "4": 4, "3": 3, "2": 2}"#);
         l.put("6".to_string(), 6);
         l.put("7".to_string(), 7);
         l.put("8".to_string(), 8);
-        l.traverse();
+        assert_eq!(format!("{:?}", l), r#"{"8": 8, "7": 7, "6": 6, "5": 5}"#);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let mut l = LruCache::with_capacity(3);
+        l.put("1".to_string(), 1);
+        l.put("2".to_string(), 2);
+        l.put("3".to_string(), 3);
+        l.get(&"1".to_string());
+
+        let json = serde_json::to_string(&l).unwrap();
+        assert_eq!(
+            json,
+            r#"{"capacity":3,"entries":[["1",1],["3",3],["2",2]]}"#
+        );
+
+        // The least recently used entry is still evicted first.
+        let mut de: LruCache<String, i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(de.capacity(), 3);
+        assert_eq!(de.put("4".to_string(), 4), Some(2));
+        assert_eq!(de.get(&"3".to_string()), Some(&3));
+
+        assert!(serde_json::from_str::<LruCache<String, i32>>(
+            r#"{"capacity":1,"entries":[["1",1],["2",2]]}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<LruCache<String, i32>>(
+            r#"{"capacity":2,"entries":[["1",1],["1",2]]}"#
+        )
+        .is_err());
     }
 }
@@ -0,0 +1,375 @@
+//! Disjoint sets (union-find) with path compression and union by rank.
+//!
+//! Both optimizations together make every operation run in *O*(α(*n*))
+//! amortized time, where α is the inverse Ackermann function, which is less
+//! than 5 for any practical *n*.
+//!
+//! Wikipedia:
+//!  - https://en.wikipedia.org/wiki/Disjoint-set_data_structure
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::collection::error::CollectionError;
+
+/// Disjoint sets of the elements `0..n`.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::disjoint_set::DisjointSet;
+///
+/// let mut s = DisjointSet::with_length(5);
+/// s.union(0, 1).unwrap();
+/// s.union(3, 4).unwrap();
+///
+/// assert!(s.connected(0, 1).unwrap());
+/// assert!(!s.connected(1, 3).unwrap());
+/// assert_eq!(s.set_size(4).unwrap(), 2);
+/// assert_eq!(s.sets().collect::<Vec<_>>(), vec![vec![0, 1], vec![2], vec![3, 4]]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    // An upper bound of the height of the tree of every root.
+    rank: Vec<u8>,
+    // The size of the set of every root.
+    size: Vec<usize>,
+    set_count: usize,
+}
+
+impl DisjointSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates `n` singleton sets of the elements `0..n`.
+    pub fn with_length(n: usize) -> Self {
+        DisjointSet {
+            parent: (0..n).collect(),
+            rank: vec![0; n],
+            size: vec![1; n],
+            set_count: n,
+        }
+    }
+
+    /// Returns the number of elements.
+    #[inline]
+    pub fn length(&self) -> usize {
+        self.parent.len()
+    }
+
+    /// Returns `true` if there are no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Returns the number of disjoint sets.
+    #[inline]
+    pub fn set_count(&self) -> usize {
+        self.set_count
+    }
+
+    /// Adds a new element in a singleton set, and returns the element.
+    pub fn make_set(&mut self) -> usize {
+        let x = self.parent.len();
+        self.parent.push(x);
+        self.rank.push(0);
+        self.size.push(1);
+        self.set_count += 1;
+        x
+    }
+
+    /// Returns the representative of the set of `x`.
+    ///
+    /// The path from `x` to the representative is compressed on the way.
+    pub fn find(&mut self, x: usize) -> Result<usize, CollectionError> {
+        self._check(x)?;
+        let root = self._root(x);
+        let mut x = x;
+        while self.parent[x] != root {
+            x = std::mem::replace(&mut self.parent[x], root);
+        }
+        Ok(root)
+    }
+
+    /// Merges the sets of `x` and `y`, and returns `false` if they are in the
+    /// same set already.
+    pub fn union(&mut self, x: usize, y: usize) -> Result<bool, CollectionError> {
+        let (x, y) = (self.find(x)?, self.find(y)?);
+        if x == y {
+            return Ok(false);
+        }
+        // Attach the lower tree under the higher one.
+        let (root, child) = if self.rank[x] < self.rank[y] {
+            (y, x)
+        } else {
+            (x, y)
+        };
+        self.parent[child] = root;
+        self.size[root] += self.size[child];
+        if self.rank[root] == self.rank[child] {
+            self.rank[root] += 1;
+        }
+        self.set_count -= 1;
+        Ok(true)
+    }
+
+    /// Returns `true` if `x` and `y` are in the same set.
+    pub fn connected(&mut self, x: usize, y: usize) -> Result<bool, CollectionError> {
+        Ok(self.find(x)? == self.find(y)?)
+    }
+
+    /// Returns the size of the set of `x`.
+    pub fn set_size(&mut self, x: usize) -> Result<usize, CollectionError> {
+        let root = self.find(x)?;
+        Ok(self.size[root])
+    }
+
+    /// Gets an iterator over the sets, every set is sorted and the sets are
+    /// ordered by their least elements.
+    pub fn sets(&self) -> impl Iterator<Item = Vec<usize>> {
+        let mut group_of_root = vec![usize::MAX; self.parent.len()];
+        let mut groups: Vec<Vec<usize>> = Vec::with_capacity(self.set_count);
+        for x in 0..self.parent.len() {
+            let root = self._root(x);
+            if group_of_root[root] == usize::MAX {
+                group_of_root[root] = groups.len();
+                groups.push(Vec::with_capacity(self.size[root]));
+            }
+            groups[group_of_root[root]].push(x);
+        }
+        groups.into_iter()
+    }
+
+    /// Returns the root of `x`, without compressing the path.
+    fn _root(&self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            x = self.parent[x];
+        }
+        x
+    }
+
+    fn _check(&self, x: usize) -> Result<(), CollectionError> {
+        if x >= self.parent.len() {
+            return Err(CollectionError::IndexOutOfRange {
+                index: x,
+                len: self.parent.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Disjoint sets of arbitrary keys.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::disjoint_set::KeyedDisjointSet;
+///
+/// // Cluster the records which share an email.
+/// let mut records = KeyedDisjointSet::new();
+/// for id in ["alice", "alice2", "bob", "carol"] {
+///     records.make_set(id);
+/// }
+/// records.union(&"alice", &"alice2").unwrap();
+///
+/// assert!(records.connected(&"alice2", &"alice").unwrap());
+/// assert_eq!(records.set_count(), 3);
+/// assert!(records.union(&"alice", &"dave").is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct KeyedDisjointSet<K> {
+    keys: Vec<K>,
+    indices: HashMap<K, usize>,
+    set: DisjointSet,
+}
+
+impl<K: Hash + Eq + Clone> Default for KeyedDisjointSet<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone> KeyedDisjointSet<K> {
+    pub fn new() -> Self {
+        KeyedDisjointSet {
+            keys: Vec::new(),
+            indices: HashMap::new(),
+            set: DisjointSet::new(),
+        }
+    }
+
+    /// Returns the number of keys.
+    #[inline]
+    pub fn length(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if there are no keys.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the number of disjoint sets.
+    #[inline]
+    pub fn set_count(&self) -> usize {
+        self.set.set_count()
+    }
+
+    /// Returns `true` if the key is in some set.
+    pub fn contains(&self, key: &K) -> bool {
+        self.indices.contains_key(key)
+    }
+
+    /// Adds the key in a singleton set, and returns `false` if the key is in
+    /// some set already.
+    pub fn make_set(&mut self, key: K) -> bool {
+        if self.indices.contains_key(&key) {
+            return false;
+        }
+        let i = self.set.make_set();
+        self.indices.insert(key.clone(), i);
+        self.keys.push(key);
+        true
+    }
+
+    /// Returns the representative key of the set of `key`.
+    pub fn find(&mut self, key: &K) -> Result<&K, CollectionError> {
+        let i = self._index(key)?;
+        let root = self.set.find(i)?;
+        Ok(&self.keys[root])
+    }
+
+    /// Merges the sets of the keys, and returns `false` if they are in the
+    /// same set already.
+    pub fn union(&mut self, x: &K, y: &K) -> Result<bool, CollectionError> {
+        let (x, y) = (self._index(x)?, self._index(y)?);
+        self.set.union(x, y)
+    }
+
+    /// Returns `true` if the keys are in the same set.
+    pub fn connected(&mut self, x: &K, y: &K) -> Result<bool, CollectionError> {
+        let (x, y) = (self._index(x)?, self._index(y)?);
+        self.set.connected(x, y)
+    }
+
+    /// Returns the size of the set of `key`.
+    pub fn set_size(&mut self, key: &K) -> Result<usize, CollectionError> {
+        let i = self._index(key)?;
+        self.set.set_size(i)
+    }
+
+    /// Gets an iterator over the sets, the keys of every set and the sets
+    /// themselves are in the order the keys were added.
+    pub fn sets(&self) -> impl Iterator<Item = Vec<&K>> {
+        self.set
+            .sets()
+            .map(move |set| set.into_iter().map(|i| &self.keys[i]).collect())
+    }
+
+    fn _index(&self, key: &K) -> Result<usize, CollectionError> {
+        self.indices
+            .get(key)
+            .copied()
+            .ok_or(CollectionError::KeyNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collection::disjoint_set::{DisjointSet, KeyedDisjointSet};
+    use crate::collection::error::CollectionError;
+
+    #[test]
+    fn test_union_find() {
+        let mut s = DisjointSet::with_length(6);
+        assert_eq!(s.set_count(), 6);
+        assert!(s.union(0, 1).unwrap());
+        assert!(s.union(2, 3).unwrap());
+        assert!(s.union(1, 3).unwrap());
+        assert!(!s.union(0, 2).unwrap());
+        assert_eq!(s.set_count(), 3);
+
+        assert!(s.connected(0, 3).unwrap());
+        assert!(!s.connected(0, 4).unwrap());
+        assert_eq!(s.set_size(2).unwrap(), 4);
+        assert_eq!(s.set_size(5).unwrap(), 1);
+        assert_eq!(s.find(3).unwrap(), s.find(0).unwrap());
+
+        let x = s.make_set();
+        assert_eq!(x, 6);
+        s.union(x, 5).unwrap();
+        assert_eq!(
+            s.sets().collect::<Vec<_>>(),
+            vec![vec![0, 1, 2, 3], vec![4], vec![5, 6]]
+        );
+        assert_eq!(s.length(), 7);
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let mut s = DisjointSet::with_length(2);
+        assert!(matches!(
+            s.find(2),
+            Err(CollectionError::IndexOutOfRange { index: 2, len: 2 })
+        ));
+        assert!(s.union(0, 5).is_err());
+        assert!(s.connected(9, 0).is_err());
+        assert_eq!(s.set_count(), 2);
+    }
+
+    #[test]
+    fn test_path_compression() {
+        let mut s = DisjointSet::with_length(1000);
+        for i in 1..1000 {
+            s.union(i - 1, i).unwrap();
+        }
+        // Union by rank keeps the trees shallow.
+        assert!(s.rank.iter().all(|&r| r <= 10));
+
+        let root = s.find(999).unwrap();
+        assert_eq!(s.parent[999], root);
+        assert_eq!(s.set_size(0).unwrap(), 1000);
+        assert_eq!(s.set_count(), 1);
+    }
+
+    #[test]
+    fn test_keyed() {
+        let mut s = KeyedDisjointSet::new();
+        for k in ["a", "b", "c", "d", "e"] {
+            assert!(s.make_set(k.to_string()));
+        }
+        assert!(!s.make_set("a".to_string()));
+
+        let key = |k: &str| k.to_string();
+        s.union(&key("a"), &key("c")).unwrap();
+        s.union(&key("e"), &key("c")).unwrap();
+        assert!(s.connected(&key("a"), &key("e")).unwrap());
+        assert!(!s.connected(&key("a"), &key("b")).unwrap());
+        assert_eq!(s.set_size(&key("e")).unwrap(), 3);
+        let rep = s.find(&key("e")).unwrap().clone();
+        assert!(["a", "c", "e"].contains(&rep.as_str()));
+
+        assert!(matches!(
+            s.union(&key("a"), &key("x")),
+            Err(CollectionError::KeyNotFound)
+        ));
+        assert!(s.find(&key("x")).is_err());
+
+        let sets: Vec<Vec<&String>> = s.sets().collect();
+        assert_eq!(
+            sets,
+            vec![
+                vec![&key("a"), &key("c"), &key("e")],
+                vec![&key("b")],
+                vec![&key("d")],
+            ]
+        );
+        assert_eq!(s.set_count(), 3);
+        assert_eq!(s.length(), 5);
+    }
+}
@@ -2,12 +2,21 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum CollectionError {
-    #[error("index out of range")]
-    IndexOutOfRange,
+    #[error("index out of range: the len is {len} but the index is {index}")]
+    IndexOutOfRange { index: usize, len: usize },
 
-    #[error("invalid parameter")]
+    #[error("invalid parameter: {0}")]
     InvalidParameter(String),
 
+    #[error("incompatible parameters: {0}")]
+    IncompatibleParameters(String),
+
     #[error("key already exists")]
     DuplicateKey,
+
+    #[error("key not found")]
+    KeyNotFound,
+
+    #[error("capacity exceeded: the capacity is {capacity}")]
+    CapacityExceeded { capacity: usize },
 }
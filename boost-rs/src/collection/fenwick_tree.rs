@@ -0,0 +1,183 @@
+//! A Fenwick tree (binary indexed tree) for prefix sums.
+//!
+//! Both point updates and prefix sums compute in *O*(log *n*) time, with only
+//! `n` values of storage.
+//!
+//! Wikipedia:
+//!  - https://en.wikipedia.org/wiki/Fenwick_tree
+
+use std::iter::FromIterator;
+use std::ops::RangeBounds;
+
+use crate::collection::_index_range;
+use crate::collection::error::CollectionError;
+use crate::types::ops::NumOps;
+
+/// A Fenwick tree over the values `0..n`, where `T::default()` is the zero.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::fenwick_tree::FenwickTree;
+///
+/// let mut t = FenwickTree::from(vec![3u32, 1, 4, 1, 5]);
+/// assert_eq!(t.prefix_sum(3).unwrap(), 8);
+///
+/// t.add(1, 10).unwrap();
+/// assert_eq!(t.range_sum(1..=3).unwrap(), 16);
+/// assert_eq!(t.get(1).unwrap(), 11);
+/// assert!(t.prefix_sum(6).is_err());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FenwickTree<T> {
+    // The node `i` (1-based) holds the sum of the values in
+    // `i - lowbit(i)..i`.
+    tree: Vec<T>,
+}
+
+impl<T: NumOps + Copy + Default> FenwickTree<T> {
+    /// Creates a tree of `n` zeros.
+    pub fn new(n: usize) -> Self {
+        FenwickTree {
+            tree: vec![T::default(); n],
+        }
+    }
+
+    /// Returns the number of values.
+    #[inline]
+    pub fn length(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Returns `true` if there are no values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Adds `delta` to the value at `index`.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn add(&mut self, index: usize, delta: T) -> Result<(), CollectionError> {
+        if index >= self.tree.len() {
+            return Err(CollectionError::IndexOutOfRange {
+                index,
+                len: self.tree.len(),
+            });
+        }
+        let mut i = index + 1;
+        while i <= self.tree.len() {
+            self.tree[i - 1] = self.tree[i - 1] + delta;
+            i += _lowbit(i);
+        }
+        Ok(())
+    }
+
+    /// Returns the sum of the values in `0..end`.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn prefix_sum(&self, end: usize) -> Result<T, CollectionError> {
+        if end > self.tree.len() {
+            return Err(CollectionError::IndexOutOfRange {
+                index: end,
+                len: self.tree.len(),
+            });
+        }
+        Ok(self._prefix_sum(end))
+    }
+
+    /// Returns the sum of the values in `range`.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn range_sum<R: RangeBounds<usize>>(&self, range: R) -> Result<T, CollectionError> {
+        let range = _index_range(range, self.tree.len())?;
+        Ok(self._prefix_sum(range.end) - self._prefix_sum(range.start))
+    }
+
+    /// Returns the value at `index`.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn get(&self, index: usize) -> Result<T, CollectionError> {
+        self.range_sum(index..=index)
+    }
+
+    fn _prefix_sum(&self, mut end: usize) -> T {
+        let mut sum = T::default();
+        while end > 0 {
+            sum = sum + self.tree[end - 1];
+            end -= _lowbit(end);
+        }
+        sum
+    }
+}
+
+#[inline]
+fn _lowbit(i: usize) -> usize {
+    i & i.wrapping_neg()
+}
+
+impl<T: NumOps + Copy + Default> From<Vec<T>> for FenwickTree<T> {
+    /// Builds a tree in *O*(*n*) time.
+    fn from(mut tree: Vec<T>) -> Self {
+        for i in 1..=tree.len() {
+            let parent = i + _lowbit(i);
+            if parent <= tree.len() {
+                tree[parent - 1] = tree[parent - 1] + tree[i - 1];
+            }
+        }
+        FenwickTree { tree }
+    }
+}
+
+impl<T: NumOps + Copy + Default> FromIterator<T> for FenwickTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collection::error::CollectionError;
+    use crate::collection::fenwick_tree::FenwickTree;
+
+    #[test]
+    fn test_prefix_sum() {
+        let mut t: FenwickTree<i64> = FenwickTree::new(8);
+        assert_eq!(t.prefix_sum(8).unwrap(), 0);
+        for i in 0..8 {
+            t.add(i, i as i64 - 3).unwrap();
+        }
+        assert_eq!(t.prefix_sum(0).unwrap(), 0);
+        assert_eq!(t.prefix_sum(4).unwrap(), -6);
+        assert_eq!(t.range_sum(4..).unwrap(), 10);
+        assert_eq!(t.range_sum(2..2).unwrap(), 0);
+        assert_eq!(t.get(7).unwrap(), 4);
+
+        assert!(matches!(
+            t.add(8, 1),
+            Err(CollectionError::IndexOutOfRange { index: 8, len: 8 })
+        ));
+        assert!(t.prefix_sum(9).is_err());
+        assert!(t.range_sum(..=8).is_err());
+        assert!(t.get(8).is_err());
+        assert_eq!(t.length(), 8);
+    }
+
+    #[test]
+    fn test_build() {
+        let v: Vec<u16> = (0..100).map(|x| (x * 37) % 101).collect();
+        let built = FenwickTree::from(v.clone());
+        let mut added = FenwickTree::new(v.len());
+        for (i, &x) in v.iter().enumerate() {
+            added.add(i, x).unwrap();
+        }
+        assert_eq!(built.tree, added.tree);
+
+        let collected: FenwickTree<u16> = v.iter().copied().collect();
+        for end in 0..=v.len() {
+            let sum: u16 = v[..end].iter().sum();
+            assert_eq!(collected.prefix_sum(end).unwrap(), sum);
+        }
+        assert!(FenwickTree::<u8>::new(0).is_empty());
+    }
+}
@@ -0,0 +1,538 @@
+//! Consistent hashing, mapping keys to nodes so that few keys move when the
+//! nodes change.
+//!
+//! - [`HashRing`] places every node at many points of a ring of hashes, and
+//!   maps a key to the first node clockwise from its hash.
+//! - [`jump_hash`] maps a key to one of `n` numbered buckets with no memory at
+//!   all, when the buckets only grow or shrink at the end.
+//! - [`rendezvous`] maps a key to the node with the highest hash of the key
+//!   and the node, in *O*(*n*) time for `n` nodes.
+//!
+//! Wikipedia:
+//!  - https://en.wikipedia.org/wiki/Consistent_hashing
+//!  - https://en.wikipedia.org/wiki/Rendezvous_hashing
+
+use std::cmp::Reverse;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+
+use crate::collection::error::CollectionError;
+
+/// The number of virtual nodes for a unit of weight, by default.
+const DEFAULT_VIRTUAL_NODES: usize = 160;
+
+/// The default hasher of [`HashRing`], which is not randomly seeded, so
+/// the processes running the same build agree on the ring.
+///
+/// The algorithm of `DefaultHasher` is unspecified and may change between
+/// Rust releases, so the ring is only the same for the same build. Processes
+/// built otherwise should share a hasher of a specified algorithm through
+/// [`HashRing::with_hasher`].
+pub type RingState = BuildHasherDefault<DefaultHasher>;
+
+/// A consistent hashing ring with virtual nodes and weighted members.
+///
+/// A node of weight `w` is placed at `w * virtual_nodes` points of the ring.
+/// When a node is added, only the keys between its new points and the
+/// preceding points move, and all of them move to the new node.
+///
+/// The points are kept in a sorted vector, so a lookup is a binary search.
+/// Points of equal hashes are ordered by the hashes of their nodes, so rings
+/// of the same nodes map the keys alike, whatever order the nodes are added
+/// in.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::hash_ring::HashRing;
+///
+/// let mut ring = HashRing::new();
+/// ring.add_node("pool-a").unwrap();
+/// ring.add_node("pool-b").unwrap();
+/// ring.add_weighted_node("pool-c", 2).unwrap();
+///
+/// let owner = *ring.get(&"user:42").unwrap();
+/// let replicas = ring.get_n(&"user:42", 2);
+/// assert_eq!(*replicas[0], owner);
+/// assert_ne!(replicas[0], replicas[1]);
+///
+/// // Removing another node doesn't move the key.
+/// let other = ["pool-a", "pool-b", "pool-c"].iter().copied().find(|n| *n != owner).unwrap();
+/// ring.remove_node(&other).unwrap();
+/// assert_eq!(ring.get(&"user:42"), Some(&owner));
+/// ```
+pub struct HashRing<N, S = RingState> {
+    // The points of the virtual nodes and the hashes of their nodes, sorted
+    // by the points then by the hashes of the nodes.
+    ring: Vec<(u64, u64, N)>,
+    weights: HashMap<N, usize>,
+    virtual_nodes: usize,
+    hasher: S,
+}
+
+impl<N: Hash + Eq + Clone> HashRing<N, RingState> {
+    /// Creates an empty ring with 160 virtual nodes for a unit of weight.
+    pub fn new() -> Self {
+        Self::with_hasher(RingState::default())
+    }
+
+    /// Creates an empty ring with `virtual_nodes` for a unit of weight.
+    ///
+    /// Returns [`CollectionError::InvalidParameter`] if `virtual_nodes` is 0.
+    pub fn with_virtual_nodes(virtual_nodes: usize) -> Result<Self, CollectionError> {
+        Self::with_virtual_nodes_and_hasher(virtual_nodes, RingState::default())
+    }
+}
+
+impl<N: Hash + Eq + Clone> Default for HashRing<N, RingState> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Hash + Eq + Clone, S: BuildHasher> HashRing<N, S> {
+    /// Creates an empty ring which hashes with `hasher`.
+    pub fn with_hasher(hasher: S) -> Self {
+        HashRing {
+            ring: Vec::new(),
+            weights: HashMap::new(),
+            virtual_nodes: DEFAULT_VIRTUAL_NODES,
+            hasher,
+        }
+    }
+
+    /// Creates an empty ring with `virtual_nodes` for a unit of weight, which
+    /// hashes with `hasher`.
+    ///
+    /// Returns [`CollectionError::InvalidParameter`] if `virtual_nodes` is 0.
+    pub fn with_virtual_nodes_and_hasher(
+        virtual_nodes: usize,
+        hasher: S,
+    ) -> Result<Self, CollectionError> {
+        if virtual_nodes == 0 {
+            return Err(CollectionError::InvalidParameter(
+                "the number of virtual nodes must be positive".to_string(),
+            ));
+        }
+        Ok(HashRing {
+            virtual_nodes,
+            ..Self::with_hasher(hasher)
+        })
+    }
+
+    /// Returns the number of nodes.
+    #[inline]
+    pub fn length(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Returns `true` if there are no nodes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    /// Returns the number of virtual nodes for a unit of weight.
+    #[inline]
+    pub fn virtual_nodes(&self) -> usize {
+        self.virtual_nodes
+    }
+
+    /// Returns `true` if the ring contains the node.
+    pub fn contains_node(&self, node: &N) -> bool {
+        self.weights.contains_key(node)
+    }
+
+    /// Returns the weight of the node.
+    pub fn weight(&self, node: &N) -> Option<usize> {
+        self.weights.get(node).copied()
+    }
+
+    /// Gets an iterator over the nodes and their weights, in arbitrary order.
+    pub fn nodes(&self) -> impl Iterator<Item = (&N, usize)> + '_ {
+        self.weights.iter().map(|(n, &w)| (n, w))
+    }
+
+    /// Adds a node of weight 1.
+    ///
+    /// Returns [`CollectionError::DuplicateKey`] if the ring contains the node
+    /// already.
+    pub fn add_node(&mut self, node: N) -> Result<(), CollectionError> {
+        self.add_weighted_node(node, 1)
+    }
+
+    /// Adds a node, which gets about `weight` times as many keys as a node of
+    /// weight 1.
+    ///
+    /// Returns [`CollectionError::DuplicateKey`] if the ring contains the node
+    /// already, or [`CollectionError::InvalidParameter`] if `weight` is 0 or
+    /// the number of its points overflows.
+    ///
+    /// This operation should compute in *O*(*m* log *m*) time, where *m* is
+    /// the number of the points on the ring.
+    pub fn add_weighted_node(&mut self, node: N, weight: usize) -> Result<(), CollectionError> {
+        if weight == 0 {
+            return Err(CollectionError::InvalidParameter(
+                "the weight of a node must be positive".to_string(),
+            ));
+        }
+        let points = weight.checked_mul(self.virtual_nodes).ok_or_else(|| {
+            CollectionError::InvalidParameter(format!(
+                "the weight {} times {} virtual nodes overflows",
+                weight, self.virtual_nodes
+            ))
+        })?;
+        if self.weights.contains_key(&node) {
+            return Err(CollectionError::DuplicateKey);
+        }
+        let id = self._hash(&node);
+        for i in 0..points {
+            // A fixed-width index hashes the same on every target.
+            let point = self._hash(&(&node, i as u64));
+            self.ring.push((point, id, node.clone()));
+        }
+        self.ring.sort_by_key(|&(point, id, _)| (point, id));
+        self.weights.insert(node, weight);
+        Ok(())
+    }
+
+    /// Removes a node, and returns its weight.
+    ///
+    /// Returns [`CollectionError::KeyNotFound`] if the ring doesn't contain
+    /// the node.
+    ///
+    /// This operation should compute in *O*(*m*) time, where *m* is the
+    /// number of the points on the ring.
+    pub fn remove_node(&mut self, node: &N) -> Result<usize, CollectionError> {
+        let weight = self
+            .weights
+            .remove(node)
+            .ok_or(CollectionError::KeyNotFound)?;
+        self.ring.retain(|(_, _, n)| n != node);
+        Ok(weight)
+    }
+
+    /// Returns the node of the key, or `None` if the ring is empty.
+    ///
+    /// This operation should compute in *O*(log *m*) time, where *m* is the
+    /// number of the points on the ring.
+    pub fn get<K: Hash + ?Sized>(&self, key: &K) -> Option<&N> {
+        if self.ring.is_empty() {
+            return None;
+        }
+        Some(&self.ring[self._first_point(key)].2)
+    }
+
+    /// Returns up to `n` distinct nodes of the key for its replicas, starting
+    /// with the node of [`HashRing::get`], then the next distinct nodes
+    /// clockwise.
+    pub fn get_n<K: Hash + ?Sized>(&self, key: &K, n: usize) -> Vec<&N> {
+        let n = n.min(self.weights.len());
+        let mut nodes: Vec<&N> = Vec::with_capacity(n);
+        if n == 0 {
+            return nodes;
+        }
+        let start = self._first_point(key);
+        let (after, before) = self.ring.split_at(start);
+        for (_, _, node) in before.iter().chain(after) {
+            if !nodes.contains(&node) {
+                nodes.push(node);
+                if nodes.len() == n {
+                    break;
+                }
+            }
+        }
+        nodes
+    }
+
+    /// Returns the index of the first point at or after the hash of the key,
+    /// wrapping around to 0.
+    fn _first_point<K: Hash + ?Sized>(&self, key: &K) -> usize {
+        let hash = self._hash(key);
+        let i = self.ring.partition_point(|&(point, _, _)| point < hash);
+        if i == self.ring.len() {
+            0
+        } else {
+            i
+        }
+    }
+
+    fn _hash<K: Hash + ?Sized>(&self, key: &K) -> u64 {
+        self.hasher.hash_one(key)
+    }
+}
+
+impl<N: Debug, S> Debug for HashRing<N, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HashRing")
+            .field("nodes", &self.weights)
+            .field("virtual_nodes", &self.virtual_nodes)
+            .finish()
+    }
+}
+
+impl<N: Clone, S: Clone> Clone for HashRing<N, S> {
+    fn clone(&self) -> Self {
+        HashRing {
+            ring: self.ring.clone(),
+            weights: self.weights.clone(),
+            virtual_nodes: self.virtual_nodes,
+            hasher: self.hasher.clone(),
+        }
+    }
+}
+
+/// Maps a key to one of the buckets `0..buckets` by the jump consistent hash
+/// of Lamping and Veach.
+///
+/// When the buckets grow from `n` to `n + 1`, only about `1 / (n + 1)` of the
+/// keys move, all of them to the bucket `n`. Removing a bucket other than the
+/// last one isn't supported, use [`HashRing`] or [`rendezvous`] for that.
+///
+/// Returns [`CollectionError::InvalidParameter`] if `buckets` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::hash_ring::jump_hash;
+///
+/// let before = jump_hash(0xdead_beef, 10).unwrap();
+/// let after = jump_hash(0xdead_beef, 11).unwrap();
+/// assert!(after == before || after == 10);
+/// assert!(jump_hash(1, 0).is_err());
+/// ```
+pub fn jump_hash(key: u64, buckets: u32) -> Result<u32, CollectionError> {
+    if buckets == 0 {
+        return Err(CollectionError::InvalidParameter(
+            "the number of buckets must be positive".to_string(),
+        ));
+    }
+    let (mut key, mut b, mut j) = (key, -1i64, 0i64);
+    while j < buckets as i64 {
+        b = j;
+        key = key.wrapping_mul(2862933555777941757).wrapping_add(1);
+        j = ((b + 1) as f64 * ((1u64 << 31) as f64 / ((key >> 33) + 1) as f64)) as i64;
+    }
+    Ok(b as u32)
+}
+
+/// Returns the node of the key by rendezvous (highest random weight) hashing,
+/// or `None` if there are no nodes.
+///
+/// Every node is scored by the hash of the key and the node, and the node
+/// with the highest score wins. Removing a node only moves its own keys, to
+/// wherever they score second.
+///
+/// The scores come from `DefaultHasher`, so they are only the same for the
+/// same build, see [`RingState`].
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::hash_ring::rendezvous;
+///
+/// let pools = ["pool-a", "pool-b", "pool-c"];
+/// let owner = rendezvous(&"user:42", &pools).unwrap();
+///
+/// let rest: Vec<_> = pools.iter().copied().filter(|p| p != owner).collect();
+/// assert_eq!(rendezvous(&"user:42", &rest[..1]), Some(&rest[0]));
+/// assert_eq!(rendezvous(&"user:42", &pools[..0]), None);
+/// ```
+pub fn rendezvous<'a, K: Hash + ?Sized, N: Hash>(key: &K, nodes: &'a [N]) -> Option<&'a N> {
+    nodes.iter().max_by_key(|node| _score(key, *node))
+}
+
+/// Returns up to `n` nodes of the key for its replicas by rendezvous hashing,
+/// from the highest score to the lowest.
+pub fn rendezvous_n<'a, K: Hash + ?Sized, N: Hash>(
+    key: &K,
+    nodes: &'a [N],
+    n: usize,
+) -> Vec<&'a N> {
+    let mut scored: Vec<(u64, &N)> = nodes.iter().map(|node| (_score(key, node), node)).collect();
+    scored.sort_unstable_by_key(|&(score, _)| Reverse(score));
+    scored.into_iter().take(n).map(|(_, node)| node).collect()
+}
+
+fn _score<K: Hash + ?Sized, N: Hash>(key: &K, node: &N) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    node.hash(&mut hasher);
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::hash::{BuildHasherDefault, Hasher};
+
+    use crate::collection::error::CollectionError;
+    use crate::collection::hash_ring::{jump_hash, rendezvous, rendezvous_n, HashRing};
+
+    #[test]
+    fn test_add_and_remove() {
+        let mut ring = HashRing::with_virtual_nodes(10).unwrap();
+        assert_eq!(ring.get(&1), None);
+        assert!(ring.get_n(&1, 3).is_empty());
+
+        ring.add_node("a").unwrap();
+        ring.add_weighted_node("b", 3).unwrap();
+        assert!(matches!(
+            ring.add_node("a"),
+            Err(CollectionError::DuplicateKey)
+        ));
+        assert!(matches!(
+            ring.add_weighted_node("c", 0),
+            Err(CollectionError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            ring.add_weighted_node("c", usize::MAX),
+            Err(CollectionError::InvalidParameter(_))
+        ));
+        assert_eq!(ring.ring.len(), 40);
+        assert_eq!(ring.length(), 2);
+        assert_eq!(ring.weight(&"b"), Some(3));
+        assert!(ring.contains_node(&"a"));
+
+        let mut replicas = ring.get_n(&"key", 5);
+        replicas.sort();
+        assert_eq!(replicas, vec![&"a", &"b"]);
+
+        assert_eq!(ring.remove_node(&"b").unwrap(), 3);
+        assert!(matches!(
+            ring.remove_node(&"b"),
+            Err(CollectionError::KeyNotFound)
+        ));
+        assert_eq!(ring.ring.len(), 10);
+        assert_eq!(ring.get(&"key"), Some(&"a"));
+        assert!(HashRing::<u8>::with_virtual_nodes(0).is_err());
+    }
+
+    #[test]
+    fn test_minimal_remapping() {
+        let mut ring = HashRing::new();
+        for node in 0..8u32 {
+            ring.add_node(node).unwrap();
+        }
+        let before: Vec<u32> = (0..10_000u32).map(|k| *ring.get(&k).unwrap()).collect();
+
+        ring.add_node(8).unwrap();
+        let mut moved = 0;
+        for (k, old) in before.iter().enumerate() {
+            let new = *ring.get(&(k as u32)).unwrap();
+            if new != *old {
+                assert_eq!(new, 8);
+                moved += 1;
+            }
+        }
+        // About 1 / 9 of the keys move.
+        assert!((700..1600).contains(&moved), "{} keys moved", moved);
+
+        ring.remove_node(&8).unwrap();
+        let after: Vec<u32> = (0..10_000u32).map(|k| *ring.get(&k).unwrap()).collect();
+        assert_eq!(before, after);
+    }
+
+    /// Hashes a `u64` to itself, so the points `(node, i)` of all nodes are
+    /// at `i`, and other values by FNV-1a.
+    #[derive(Clone, Default)]
+    struct CollidingHasher {
+        bytes: u64,
+        last: Option<u64>,
+    }
+
+    impl Hasher for CollidingHasher {
+        fn finish(&self) -> u64 {
+            self.last.unwrap_or(self.bytes)
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for &b in bytes {
+                self.bytes = (self.bytes ^ b as u64).wrapping_mul(0x100_0000_01b3);
+            }
+        }
+
+        fn write_u64(&mut self, i: u64) {
+            self.last = Some(i);
+        }
+    }
+
+    #[test]
+    fn test_colliding_points() {
+        let state = BuildHasherDefault::<CollidingHasher>::default();
+        let ring = |nodes: &[&'static str]| {
+            let mut ring = HashRing::with_virtual_nodes_and_hasher(4, state.clone()).unwrap();
+            for node in nodes {
+                ring.add_node(*node).unwrap();
+            }
+            ring
+        };
+        let (a, b) = (ring(&["x", "y", "z"]), ring(&["z", "x", "y"]));
+        for key in 0..5u64 {
+            assert_eq!(a.get(&key), b.get(&key));
+            assert_eq!(a.get_n(&key, 3), b.get_n(&key, 3));
+        }
+    }
+
+    #[test]
+    fn test_weights() {
+        let mut ring = HashRing::new();
+        ring.add_weighted_node("small", 1).unwrap();
+        ring.add_weighted_node("large", 3).unwrap();
+        let mut counts = HashMap::new();
+        for k in 0..20_000u32 {
+            *counts.entry(*ring.get(&k).unwrap()).or_insert(0) += 1;
+        }
+        let share = counts["large"] as f64 / 20_000.0;
+        assert!((0.65..0.85).contains(&share), "share {}", share);
+
+        for k in 0..100u32 {
+            let replicas = ring.get_n(&k, 2);
+            assert_eq!(replicas[0], ring.get(&k).unwrap());
+            assert_ne!(replicas[0], replicas[1]);
+        }
+    }
+
+    #[test]
+    fn test_jump_hash() {
+        for key in 0..2000u64 {
+            let key = key.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+            let mut prev = jump_hash(key, 1).unwrap();
+            assert_eq!(prev, 0);
+            for buckets in 2..50 {
+                let b = jump_hash(key, buckets).unwrap();
+                assert!(b == prev || b == buckets - 1);
+                prev = b;
+            }
+        }
+        assert!(matches!(
+            jump_hash(7, 0),
+            Err(CollectionError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_rendezvous() {
+        let nodes: Vec<String> = (0..6).map(|i| format!("node-{}", i)).collect();
+        let mut counts = vec![0; nodes.len()];
+        for k in 0..6000u32 {
+            let owner = rendezvous(&k, &nodes).unwrap();
+            counts[nodes.iter().position(|n| n == owner).unwrap()] += 1;
+
+            let top = rendezvous_n(&k, &nodes, 3);
+            assert_eq!(top.len(), 3);
+            assert_eq!(top[0], owner);
+            // Without the owner, the key moves to the second choice.
+            let rest: Vec<String> = nodes.iter().filter(|n| *n != owner).cloned().collect();
+            assert_eq!(rendezvous(&k, &rest), Some(top[1]));
+        }
+        assert!(
+            counts.iter().all(|&c| (800..1200).contains(&c)),
+            "{:?}",
+            counts
+        );
+        assert_eq!(rendezvous_n(&0, &nodes, 10).len(), 6);
+    }
+}
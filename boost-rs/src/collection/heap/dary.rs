@@ -0,0 +1,359 @@
+//! A d-ary heap with a custom comparator.
+
+use std::cmp::Ordering;
+use std::fmt::{self, Debug};
+use std::iter::FromIterator;
+use std::slice;
+use std::sync::Arc;
+
+use crate::collection::heap::HeapCmp;
+
+/// A binary max-heap with a custom comparator.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::heap::BinaryHeap;
+///
+/// // Order the tasks by their length.
+/// let mut tasks = BinaryHeap::with_comparator(|a: &&str, b: &&str| a.len().cmp(&b.len()));
+/// tasks.push("write");
+/// tasks.push("review");
+/// tasks.push("fix");
+///
+/// assert_eq!(tasks.pop(), Some("review"));
+/// assert_eq!(tasks.peek(), Some(&"write"));
+/// ```
+pub type BinaryHeap<T> = DaryHeap<T, 2>;
+
+/// A max-heap where every node has `D` children, with a custom comparator.
+///
+/// `D` must be at least 2, which is checked at compile time.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::heap::DaryHeap;
+///
+/// let mut heap: DaryHeap<i32, 4> = DaryHeap::new_min();
+/// heap.extend(vec![5, 1, 8, 3, 2]);
+///
+/// assert_eq!(heap.pop(), Some(1));
+/// assert_eq!(heap.into_sorted_vec(), vec![8, 5, 3, 2]);
+/// ```
+pub struct DaryHeap<T, const D: usize> {
+    data: Vec<T>,
+    cmp: HeapCmp<T>,
+}
+
+impl<T: Ord + 'static, const D: usize> DaryHeap<T, D> {
+    /// Creates an empty max-heap, ordered by `Ord`.
+    pub fn new() -> Self {
+        Self::with_comparator(|x: &T, y: &T| x.cmp(y))
+    }
+
+    /// Creates an empty min-heap, ordered by `Ord`.
+    pub fn new_min() -> Self {
+        Self::with_comparator(|x: &T, y: &T| y.cmp(x))
+    }
+}
+
+impl<T: Ord + 'static, const D: usize> Default for DaryHeap<T, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const D: usize> DaryHeap<T, D> {
+    const ARITY_CHECK: () = assert!(D >= 2, "the arity of a heap must be at least 2");
+
+    /// Creates an empty heap, whose greatest element by `cmp` is on the top.
+    pub fn with_comparator<F>(cmp: F) -> Self
+    where
+        F: Fn(&T, &T) -> Ordering + Send + Sync + 'static,
+    {
+        Self::from_vec_with_comparator(Vec::new(), cmp)
+    }
+
+    /// Builds a heap from the elements of `vec`.
+    ///
+    /// This operation should compute in *O*(*n*) time.
+    pub fn from_vec_with_comparator<F>(vec: Vec<T>, cmp: F) -> Self
+    where
+        F: Fn(&T, &T) -> Ordering + Send + Sync + 'static,
+    {
+        let () = Self::ARITY_CHECK;
+        let mut heap = DaryHeap {
+            data: vec,
+            cmp: Arc::new(cmp),
+        };
+        heap._heapify();
+        heap
+    }
+
+    /// Returns the number of elements in the heap.
+    #[inline]
+    pub fn length(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the heap contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Clears the heap, removing all elements.
+    pub fn clear(&mut self) {
+        self.data.clear()
+    }
+
+    /// Returns the greatest element of the heap.
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    /// Pushes an element into the heap.
+    ///
+    /// This operation should compute in *O*(log_d *n*) time.
+    pub fn push(&mut self, elem: T) {
+        self.data.push(elem);
+        self._sift_up(self.data.len() - 1);
+    }
+
+    /// Removes the greatest element from the heap and returns it.
+    ///
+    /// This operation should compute in *O*(*d* log_d *n*) time.
+    pub fn pop(&mut self) -> Option<T> {
+        let last = self.data.pop()?;
+        if self.data.is_empty() {
+            return Some(last);
+        }
+        let top = std::mem::replace(&mut self.data[0], last);
+        self._sift_down(0, self.data.len());
+        Some(top)
+    }
+
+    /// Gets an iterator over the elements of the heap, in arbitrary order.
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    /// Consumes the heap and returns its elements, in arbitrary order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    /// Consumes the heap and returns its elements in ascending order by the
+    /// heap's comparator, so the top of the heap comes last.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use boost_rs::collection::heap::BinaryHeap;
+    ///
+    /// let max: BinaryHeap<i32> = vec![3, 8, 2, 5].into_iter().collect();
+    /// assert_eq!(max.into_sorted_vec(), vec![2, 3, 5, 8]);
+    ///
+    /// let mut min: BinaryHeap<i32> = BinaryHeap::new_min();
+    /// min.extend(vec![3, 8, 2, 5]);
+    /// assert_eq!(min.into_sorted_vec(), vec![8, 5, 3, 2]);
+    /// ```
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        for end in (1..self.data.len()).rev() {
+            self.data.swap(0, end);
+            self._sift_down(0, end);
+        }
+        self.data
+    }
+
+    fn _heapify(&mut self) {
+        let len = self.data.len();
+        if len > 1 {
+            for i in (0..=(len - 2) / D).rev() {
+                self._sift_down(i, len);
+            }
+        }
+    }
+
+    #[inline]
+    fn _is_greater(&self, i: usize, j: usize) -> bool {
+        (self.cmp)(&self.data[i], &self.data[j]) == Ordering::Greater
+    }
+
+    /// Moves the element at `i` up until its parent is not less than it.
+    fn _sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / D;
+            if !self._is_greater(i, parent) {
+                break;
+            }
+            self.data.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    /// Moves the element at `i` down until it is not less than its children,
+    /// only the elements before `end` belong to the heap.
+    fn _sift_down(&mut self, mut i: usize, end: usize) {
+        loop {
+            let first = D * i + 1;
+            if first >= end {
+                break;
+            }
+            let greatest = (first + 1..(first + D).min(end)).fold(first, |g, c| {
+                if self._is_greater(c, g) {
+                    c
+                } else {
+                    g
+                }
+            });
+            if !self._is_greater(greatest, i) {
+                break;
+            }
+            self.data.swap(i, greatest);
+            i = greatest;
+        }
+    }
+}
+
+impl<T: Clone, const D: usize> Clone for DaryHeap<T, D> {
+    fn clone(&self) -> Self {
+        DaryHeap {
+            data: self.data.clone(),
+            cmp: Arc::clone(&self.cmp),
+        }
+    }
+}
+
+impl<T: Debug, const D: usize> Debug for DaryHeap<T, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.data.iter()).finish()
+    }
+}
+
+impl<T, const D: usize> Extend<T> for DaryHeap<T, D> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+impl<T: Ord + 'static, const D: usize> FromIterator<T> for DaryHeap<T, D> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<T: Ord + 'static, const D: usize> From<Vec<T>> for DaryHeap<T, D> {
+    /// Builds a max-heap in *O*(*n*) time.
+    fn from(vec: Vec<T>) -> Self {
+        Self::from_vec_with_comparator(vec, |x: &T, y: &T| x.cmp(y))
+    }
+}
+
+impl<T, const D: usize> IntoIterator for DaryHeap<T, D> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    /// Consumes the heap into an iterator over its elements, in arbitrary
+    /// order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'a, T, const D: usize> IntoIterator for &'a DaryHeap<T, D> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collection::heap::{BinaryHeap, DaryHeap};
+
+    fn assert_heap<T, const D: usize>(heap: &DaryHeap<T, D>) {
+        for i in 1..heap.data.len() {
+            assert!(!heap._is_greater(i, (i - 1) / D), "broken at {}", i);
+        }
+    }
+
+    #[test]
+    fn test_push_and_pop() {
+        let mut heap = BinaryHeap::new();
+        for x in [5, 3, 9, 1, 9, 4] {
+            heap.push(x);
+            assert_heap(&heap);
+        }
+        assert_eq!(heap.length(), 6);
+        assert_eq!(heap.peek(), Some(&9));
+
+        let mut popped = Vec::new();
+        while let Some(x) = heap.pop() {
+            assert_heap(&heap);
+            popped.push(x);
+        }
+        assert_eq!(popped, vec![9, 9, 5, 4, 3, 1]);
+        assert!(heap.is_empty());
+        assert_eq!(heap.peek(), None);
+    }
+
+    #[test]
+    fn test_comparator() {
+        let mut heap =
+            BinaryHeap::with_comparator(|x: &(i32, &str), y: &(i32, &str)| y.0.cmp(&x.0));
+        heap.extend(vec![(3, "c"), (1, "a"), (2, "b")]);
+        assert_eq!(heap.pop(), Some((1, "a")));
+
+        let min: DaryHeap<i32, 3> = DaryHeap::new_min();
+        let mut min2 = min.clone();
+        min2.extend(vec![4, 2, 6]);
+        assert_eq!(min2.peek(), Some(&2));
+        assert!(min.is_empty());
+    }
+
+    #[test]
+    fn test_build_and_sort() {
+        let v: Vec<i32> = (0..100).map(|x| (x * 37) % 101).collect();
+        let mut sorted = v.clone();
+        sorted.sort_unstable();
+
+        let heap: BinaryHeap<i32> = v.iter().copied().collect();
+        assert_heap(&heap);
+        assert_eq!(heap.into_sorted_vec(), sorted);
+
+        let heap: DaryHeap<i32, 5> = DaryHeap::from(v);
+        assert_heap(&heap);
+        assert_eq!(heap.length(), 100);
+        assert_eq!(heap.iter().count(), 100);
+        assert_eq!(heap.clone().into_sorted_vec(), sorted);
+        let mut all = heap.into_vec();
+        all.sort_unstable();
+        assert_eq!(all, sorted);
+    }
+
+    #[test]
+    fn test_random_consistency() {
+        use crate::collection::test_util::random_ops;
+        use rand::Rng;
+
+        let mut heap: DaryHeap<u32, 4> = DaryHeap::new_min();
+        let mut std_heap = std::collections::BinaryHeap::new();
+        random_ops(5000, |rng, _| {
+            if rng.gen_bool(0.6) {
+                let x = rng.gen_range(0..1000);
+                heap.push(x);
+                std_heap.push(std::cmp::Reverse(x));
+            } else {
+                assert_eq!(heap.pop(), std_heap.pop().map(|r| r.0));
+            }
+            assert_eq!(heap.peek(), std_heap.peek().map(|r| &r.0));
+        });
+        assert_heap(&heap);
+    }
+}
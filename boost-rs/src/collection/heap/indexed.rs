@@ -0,0 +1,353 @@
+//! An indexed priority queue, whose priorities can be changed by key.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::hash::Hash;
+use std::mem;
+use std::sync::Arc;
+
+use crate::collection::error::CollectionError;
+use crate::collection::heap::HeapCmp;
+
+/// A priority queue of keys, where the priority of every key can be changed,
+/// or the key removed, in *O*(log *n*) time.
+///
+/// It is a binary max-heap of `(key, priority)` entries with respect to the
+/// comparator of the priorities, plus a map from every key to its position in
+/// the heap.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::heap::IndexedPriorityQueue;
+///
+/// // Tentative distances of a shortest path search.
+/// let mut dist = IndexedPriorityQueue::new_min();
+/// dist.push("a", 7).unwrap();
+/// dist.push("b", 3).unwrap();
+/// dist.push("c", 5).unwrap();
+///
+/// // A shorter path to "a" is found.
+/// assert_eq!(dist.change_priority(&"a", 1).unwrap(), 7);
+///
+/// assert_eq!(dist.pop(), Some(("a", 1)));
+/// assert_eq!(dist.remove(&"b"), Some(3));
+/// assert_eq!(dist.pop(), Some(("c", 5)));
+/// ```
+pub struct IndexedPriorityQueue<K, P> {
+    heap: Vec<(K, P)>,
+    positions: HashMap<K, usize>,
+    cmp: HeapCmp<P>,
+}
+
+impl<K: Hash + Eq + Clone, P: Ord + 'static> IndexedPriorityQueue<K, P> {
+    /// Creates an empty queue, the greatest priority by `Ord` comes first.
+    pub fn new() -> Self {
+        Self::with_comparator(|x: &P, y: &P| x.cmp(y))
+    }
+
+    /// Creates an empty queue, the least priority by `Ord` comes first.
+    pub fn new_min() -> Self {
+        Self::with_comparator(|x: &P, y: &P| y.cmp(x))
+    }
+}
+
+impl<K: Hash + Eq + Clone, P: Ord + 'static> Default for IndexedPriorityQueue<K, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone, P> IndexedPriorityQueue<K, P> {
+    /// Creates an empty queue, the greatest priority by `cmp` comes first.
+    pub fn with_comparator<F>(cmp: F) -> Self
+    where
+        F: Fn(&P, &P) -> Ordering + Send + Sync + 'static,
+    {
+        IndexedPriorityQueue {
+            heap: Vec::new(),
+            positions: HashMap::new(),
+            cmp: Arc::new(cmp),
+        }
+    }
+
+    /// Returns the number of keys in the queue.
+    #[inline]
+    pub fn length(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns `true` if the queue contains no keys.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Clears the queue, removing all keys.
+    pub fn clear(&mut self) {
+        self.heap.clear();
+        self.positions.clear();
+    }
+
+    /// Returns `true` if the queue contains the key.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    /// Returns the priority of the key.
+    pub fn priority(&self, key: &K) -> Option<&P> {
+        self.positions.get(key).map(|&i| &self.heap[i].1)
+    }
+
+    /// Returns the key with the greatest priority, and its priority.
+    pub fn peek(&self) -> Option<(&K, &P)> {
+        self.heap.first().map(|(k, p)| (k, p))
+    }
+
+    /// Pushes a key with its priority into the queue.
+    ///
+    /// Returns [`CollectionError::DuplicateKey`] if the queue already contains
+    /// the key, use [`IndexedPriorityQueue::change_priority`] for it instead.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn push(&mut self, key: K, priority: P) -> Result<(), CollectionError> {
+        if self.positions.contains_key(&key) {
+            return Err(CollectionError::DuplicateKey);
+        }
+        let i = self.heap.len();
+        self.positions.insert(key.clone(), i);
+        self.heap.push((key, priority));
+        self._sift_up(i);
+        Ok(())
+    }
+
+    /// Removes the key with the greatest priority from the queue, and returns
+    /// it with its priority.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn pop(&mut self) -> Option<(K, P)> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        Some(self._remove_at(0))
+    }
+
+    /// Changes the priority of the key, and returns the old priority.
+    ///
+    /// Returns [`CollectionError::KeyNotFound`] if the queue doesn't contain
+    /// the key.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn change_priority(&mut self, key: &K, priority: P) -> Result<P, CollectionError> {
+        let i = *self
+            .positions
+            .get(key)
+            .ok_or(CollectionError::KeyNotFound)?;
+        let old = mem::replace(&mut self.heap[i].1, priority);
+        match (self.cmp)(&self.heap[i].1, &old) {
+            Ordering::Greater => self._sift_up(i),
+            Ordering::Less => self._sift_down(i),
+            Ordering::Equal => {}
+        }
+        Ok(old)
+    }
+
+    /// Removes the key from the queue, and returns its priority.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn remove(&mut self, key: &K) -> Option<P> {
+        let i = *self.positions.get(key)?;
+        Some(self._remove_at(i).1)
+    }
+
+    /// Gets an iterator over the keys and their priorities, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &P)> + '_ {
+        self.heap.iter().map(|(k, p)| (k, p))
+    }
+
+    /// Removes the entry at `i` of the heap, and restores the heap.
+    fn _remove_at(&mut self, i: usize) -> (K, P) {
+        let entry = self.heap.swap_remove(i);
+        self.positions.remove(&entry.0);
+        if i < self.heap.len() {
+            *self.positions.get_mut(&self.heap[i].0).unwrap() = i;
+            self._sift_up(i);
+            self._sift_down(i);
+        }
+        entry
+    }
+
+    #[inline]
+    fn _is_greater(&self, i: usize, j: usize) -> bool {
+        (self.cmp)(&self.heap[i].1, &self.heap[j].1) == Ordering::Greater
+    }
+
+    /// Swaps two entries of the heap, and updates their positions.
+    fn _swap(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+        *self.positions.get_mut(&self.heap[i].0).unwrap() = i;
+        *self.positions.get_mut(&self.heap[j].0).unwrap() = j;
+    }
+
+    fn _sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if !self._is_greater(i, parent) {
+                break;
+            }
+            self._swap(i, parent);
+            i = parent;
+        }
+    }
+
+    fn _sift_down(&mut self, mut i: usize) {
+        let len = self.heap.len();
+        loop {
+            let left = 2 * i + 1;
+            if left >= len {
+                break;
+            }
+            let right = left + 1;
+            let greater = if right < len && self._is_greater(right, left) {
+                right
+            } else {
+                left
+            };
+            if !self._is_greater(greater, i) {
+                break;
+            }
+            self._swap(i, greater);
+            i = greater;
+        }
+    }
+}
+
+impl<K: Clone, P: Clone> Clone for IndexedPriorityQueue<K, P> {
+    fn clone(&self) -> Self {
+        IndexedPriorityQueue {
+            heap: self.heap.clone(),
+            positions: self.positions.clone(),
+            cmp: Arc::clone(&self.cmp),
+        }
+    }
+}
+
+impl<K: Debug, P: Debug> Debug for IndexedPriorityQueue<K, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.heap.iter().map(|(k, p)| (k, p)))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::collection::error::CollectionError;
+    use crate::collection::heap::IndexedPriorityQueue;
+
+    fn assert_heap<P: Ord>(q: &IndexedPriorityQueue<u32, P>) {
+        for i in 1..q.heap.len() {
+            assert!(!q._is_greater(i, (i - 1) / 2), "broken at {}", i);
+        }
+        for (i, (k, _)) in q.heap.iter().enumerate() {
+            assert_eq!(q.positions[k], i);
+        }
+        assert_eq!(q.positions.len(), q.heap.len());
+    }
+
+    #[test]
+    fn test_push_and_pop() {
+        let mut q = IndexedPriorityQueue::new();
+        q.push(1, 10).unwrap();
+        q.push(2, 30).unwrap();
+        q.push(3, 20).unwrap();
+        assert!(matches!(q.push(1, 0), Err(CollectionError::DuplicateKey)));
+        assert_heap(&q);
+
+        assert_eq!(q.length(), 3);
+        assert_eq!(q.peek(), Some((&2, &30)));
+        assert_eq!(q.priority(&3), Some(&20));
+        assert_eq!(q.pop(), Some((2, 30)));
+        assert_eq!(q.pop(), Some((3, 20)));
+        assert!(!q.contains_key(&3));
+        assert_eq!(q.pop(), Some((1, 10)));
+        assert_eq!(q.pop(), None);
+        assert!(q.is_empty());
+    }
+
+    #[test]
+    fn test_change_priority_and_remove() {
+        let mut q = IndexedPriorityQueue::new_min();
+        for k in 0..10u32 {
+            q.push(k, k * 10).unwrap();
+        }
+
+        assert_eq!(q.change_priority(&9, 5).unwrap(), 90);
+        assert_heap(&q);
+        assert_eq!(q.change_priority(&0, 100).unwrap(), 0);
+        assert_heap(&q);
+        assert!(matches!(
+            q.change_priority(&42, 1),
+            Err(CollectionError::KeyNotFound)
+        ));
+
+        assert_eq!(q.remove(&1), Some(10));
+        assert_eq!(q.remove(&1), None);
+        assert_heap(&q);
+
+        assert_eq!(q.pop(), Some((9, 5)));
+        assert_eq!(q.pop(), Some((2, 20)));
+        let rest: Vec<_> = std::iter::from_fn(|| q.pop()).map(|(k, _)| k).collect();
+        assert_eq!(rest, vec![3, 4, 5, 6, 7, 8, 0]);
+    }
+
+    #[test]
+    fn test_random_consistency() {
+        use crate::collection::test_util::random_ops;
+        use rand::Rng;
+
+        // The priorities contain the keys, so that there are no ties.
+        let mut q = IndexedPriorityQueue::new_min();
+        let mut s: BTreeSet<(u32, u32)> = BTreeSet::new();
+        let mut prio = vec![None; 200];
+        random_ops(5000, |rng, _| {
+            let k = rng.gen_range(0..200u32);
+            let p = (rng.gen_range(0..1000u32), k);
+            match (rng.gen_range(0..4), prio[k as usize]) {
+                (0, Some(old)) => {
+                    assert_eq!(q.change_priority(&k, p).unwrap(), old);
+                    s.remove(&old);
+                    s.insert(p);
+                    prio[k as usize] = Some(p);
+                }
+                (1, old) => {
+                    assert_eq!(q.remove(&k), old);
+                    if let Some(old) = old {
+                        s.remove(&old);
+                    }
+                    prio[k as usize] = None;
+                }
+                (2, _) => {
+                    let top = s.iter().next().copied();
+                    if let Some(top) = top {
+                        s.remove(&top);
+                        prio[top.1 as usize] = None;
+                    }
+                    assert_eq!(q.pop(), top.map(|p| (p.1, p)));
+                }
+                (_, None) => {
+                    q.push(k, p).unwrap();
+                    s.insert(p);
+                    prio[k as usize] = Some(p);
+                }
+                (_, Some(_)) => assert!(q.push(k, p).is_err()),
+            }
+            assert_eq!(q.peek().map(|(_, p)| p), s.iter().next());
+        });
+        assert_heap(&q);
+        assert_eq!(q.length(), s.len());
+    }
+}
@@ -0,0 +1,28 @@
+//! Priority queues based on implicit heaps.
+//!
+//! - [`DaryHeap`] is a heap where every node has `D` children, and
+//!   [`BinaryHeap`] is the common case of `D = 2`. A larger arity makes the
+//!   heap shallower, so `push` gets cheaper while `pop` compares more children
+//!   on each level.
+//! - [`IndexedPriorityQueue`] keeps a priority for every key, which can be
+//!   changed or removed in *O*(log *n*) time. This is what Dijkstra-style
+//!   algorithms need, and what `std::collections::BinaryHeap` can't do.
+//!
+//! All the heaps are max-heaps with respect to their comparator, just like
+//! `std::collections::BinaryHeap`. Use `new_min` or a reversed comparator for
+//! a min-heap.
+//!
+//! Wikipedia:
+//!  - https://en.wikipedia.org/wiki/D-ary_heap
+
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+pub use self::dary::{BinaryHeap, DaryHeap};
+pub use self::indexed::IndexedPriorityQueue;
+
+pub mod dary;
+pub mod indexed;
+
+/// The comparator of a heap, which is shared between clones of the heap.
+pub(crate) type HeapCmp<T> = Arc<dyn Fn(&T, &T) -> Ordering + Send + Sync>;
@@ -0,0 +1,635 @@
+//! An interval tree, finding the intervals which overlap a point or a range.
+//!
+//! The tree is a binary search tree of half-open intervals ordered by their
+//! starts then their ends, kept balanced as an AVL tree. Every node is
+//! augmented with the greatest end in its subtree, so that the subtrees
+//! without any overlapping interval are skipped by the queries.
+//!
+//! It doesn't reuse [`BinarySearchTree`](crate::collection::bst::BinarySearchTree),
+//! which is not balanced, so its height and with it every operation is *O*(*n*)
+//! in the worst case, and which has no way to keep a value such as the
+//! greatest end up to date in its nodes.
+//!
+//! A query visits the nodes on the paths from the root to the *m* intervals
+//! found, and to the borders of the query, which takes
+//! *O*(log *n* + *m* log(*n* / *m*)) time. This is *O*(log *n* + *m*) if the
+//! intervals found are next to each other in start order, but not when they
+//! are scattered among intervals ending before the query: the tree is ordered
+//! by the starts only, so it can't skip those intervals as a whole. Meeting
+//! *O*(log *n* + *m*) for every query takes a structure ordered by both ends,
+//! such as a priority search tree.
+//!
+//! Wikipedia:
+//!  - https://en.wikipedia.org/wiki/Interval_tree#Augmented_tree
+
+use std::cmp::{max, Ordering};
+use std::fmt::{self, Debug};
+use std::iter::{FromIterator, FusedIterator};
+use std::ops::{Bound, Range};
+
+use crate::collection::error::CollectionError;
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+#[derive(Clone)]
+struct Node<K, V> {
+    range: Range<K>,
+    value: V,
+    // The greatest end of the intervals in the subtree.
+    max_end: K,
+    height: u8,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+impl<K: Ord + Clone, V> Node<K, V> {
+    fn new(range: Range<K>, value: V) -> Box<Self> {
+        Box::new(Node {
+            max_end: range.end.clone(),
+            range,
+            value,
+            height: 1,
+            left: None,
+            right: None,
+        })
+    }
+
+    /// Recomputes the height and the greatest end from the children.
+    fn update(&mut self) {
+        self.height = 1 + max(_height(&self.left), _height(&self.right));
+        let mut max_end = &self.range.end;
+        for child in [&self.left, &self.right].iter().copied().flatten() {
+            max_end = max(max_end, &child.max_end);
+        }
+        self.max_end = max_end.clone();
+    }
+}
+
+fn _height<K, V>(link: &Link<K, V>) -> u8 {
+    link.as_ref().map_or(0, |n| n.height)
+}
+
+/// Orders the intervals by their starts, then by their ends.
+fn _cmp<K: Ord>(a: &Range<K>, b: &Range<K>) -> Ordering {
+    a.start.cmp(&b.start).then_with(|| a.end.cmp(&b.end))
+}
+
+/// A map from half-open intervals `start..end` to values, which finds the
+/// intervals overlapping a point or a range in *O*(log *n* + *m* log(*n* / *m*))
+/// time, where *m* is the number of the intervals found.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::interval_tree::IntervalTree;
+///
+/// // The meetings of a day, in minutes.
+/// let mut meetings = IntervalTree::new();
+/// meetings.insert(540..600, "standup").unwrap();
+/// meetings.insert(570..660, "review").unwrap();
+/// meetings.insert(780..840, "planning").unwrap();
+///
+/// let at_ten: Vec<_> = meetings.query_point(&580).map(|(_, v)| *v).collect();
+/// assert_eq!(at_ten, vec!["standup", "review"]);
+///
+/// let afternoon: Vec<_> = meetings.query_overlap(720..1080).map(|(_, v)| *v).collect();
+/// assert_eq!(afternoon, vec!["planning"]);
+///
+/// assert!(meetings.insert(600..600, "empty").is_err());
+/// ```
+#[derive(Clone)]
+pub struct IntervalTree<K, V> {
+    root: Link<K, V>,
+    length: usize,
+}
+
+impl<K: Ord + Clone, V> Default for IntervalTree<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + Clone, V> IntervalTree<K, V> {
+    pub fn new() -> Self {
+        IntervalTree {
+            root: None,
+            length: 0,
+        }
+    }
+
+    /// Returns the number of intervals in the tree.
+    #[inline]
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if the tree contains no intervals.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Clears the tree, removing all intervals.
+    pub fn clear(&mut self) {
+        self.root = None;
+        self.length = 0;
+    }
+
+    /// Inserts an interval with its value, and returns the old value of the
+    /// interval.
+    ///
+    /// Returns [`CollectionError::InvalidParameter`] if the interval is empty.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn insert(&mut self, range: Range<K>, value: V) -> Result<Option<V>, CollectionError> {
+        if range.start >= range.end {
+            return Err(CollectionError::InvalidParameter(
+                "the interval is empty".to_string(),
+            ));
+        }
+        let mut old = None;
+        self.root = Some(Self::_insert(self.root.take(), range, value, &mut old));
+        if old.is_none() {
+            self.length += 1;
+        }
+        Ok(old)
+    }
+
+    /// Removes an interval from the tree, and returns its value.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn remove(&mut self, range: &Range<K>) -> Option<V> {
+        let mut removed = None;
+        self.root = Self::_remove(self.root.take(), range, &mut removed);
+        if removed.is_some() {
+            self.length -= 1;
+        }
+        removed
+    }
+
+    /// Returns a reference to the value of the interval.
+    pub fn get(&self, range: &Range<K>) -> Option<&V> {
+        let mut curr = self.root.as_deref();
+        while let Some(node) = curr {
+            curr = match _cmp(range, &node.range) {
+                Ordering::Less => node.left.as_deref(),
+                Ordering::Greater => node.right.as_deref(),
+                Ordering::Equal => return Some(&node.value),
+            };
+        }
+        None
+    }
+
+    /// Returns `true` if the tree contains the interval.
+    pub fn contains(&self, range: &Range<K>) -> bool {
+        self.get(range).is_some()
+    }
+
+    /// Gets an iterator over the intervals containing `point`, in start order.
+    ///
+    /// Iterating to the end should compute in
+    /// *O*(log *n* + *m* log(*n* / *m*)) time, where *m* is the number of the
+    /// intervals found.
+    pub fn query_point(&self, point: &K) -> Overlap<'_, K, V> {
+        Overlap::new(
+            self.root.as_deref(),
+            point.clone(),
+            Bound::Included(point.clone()),
+        )
+    }
+
+    /// Gets an iterator over the intervals overlapping `range`, in start
+    /// order.
+    ///
+    /// An empty range overlaps no intervals.
+    ///
+    /// Iterating to the end should compute in
+    /// *O*(log *n* + *m* log(*n* / *m*)) time, where *m* is the number of the
+    /// intervals found.
+    pub fn query_overlap(&self, range: Range<K>) -> Overlap<'_, K, V> {
+        if range.start >= range.end {
+            return Overlap::new(None, range.start, Bound::Excluded(range.end));
+        }
+        Overlap::new(
+            self.root.as_deref(),
+            range.start,
+            Bound::Excluded(range.end),
+        )
+    }
+
+    /// Gets an iterator over the intervals and their values, in start order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let mut iter = Iter {
+            stack: Vec::new(),
+            remaining: self.length,
+        };
+        iter._push_left(self.root.as_deref());
+        iter
+    }
+
+    fn _insert(
+        link: Link<K, V>,
+        range: Range<K>,
+        value: V,
+        old: &mut Option<V>,
+    ) -> Box<Node<K, V>> {
+        let mut node = match link {
+            None => return Node::new(range, value),
+            Some(node) => node,
+        };
+        match _cmp(&range, &node.range) {
+            Ordering::Less => node.left = Some(Self::_insert(node.left.take(), range, value, old)),
+            Ordering::Greater => {
+                node.right = Some(Self::_insert(node.right.take(), range, value, old))
+            }
+            Ordering::Equal => {
+                *old = Some(std::mem::replace(&mut node.value, value));
+                return node;
+            }
+        }
+        Self::_rebalance(node)
+    }
+
+    fn _remove(link: Link<K, V>, range: &Range<K>, removed: &mut Option<V>) -> Link<K, V> {
+        let mut node = link?;
+        match _cmp(range, &node.range) {
+            Ordering::Less => node.left = Self::_remove(node.left.take(), range, removed),
+            Ordering::Greater => node.right = Self::_remove(node.right.take(), range, removed),
+            Ordering::Equal => {
+                let Node {
+                    value, left, right, ..
+                } = *node;
+                *removed = Some(value);
+                let right = match right {
+                    None => return left,
+                    Some(right) => right,
+                };
+                // Lift the successor into the place of the node.
+                let (rest, mut successor) = Self::_remove_min(right);
+                successor.left = left;
+                successor.right = rest;
+                return Some(Self::_rebalance(successor));
+            }
+        }
+        Some(Self::_rebalance(node))
+    }
+
+    /// Detaches the least node of the subtree, and returns the rest of the
+    /// subtree with the node.
+    fn _remove_min(mut node: Box<Node<K, V>>) -> (Link<K, V>, Box<Node<K, V>>) {
+        match node.left.take() {
+            None => (node.right.take(), node),
+            Some(left) => {
+                let (rest, min) = Self::_remove_min(left);
+                node.left = rest;
+                (Some(Self::_rebalance(node)), min)
+            }
+        }
+    }
+
+    /// Restores the balance of a node whose subtrees are balanced and differ
+    /// in height by at most 2.
+    fn _rebalance(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+        let (lh, rh) = (_height(&node.left), _height(&node.right));
+        if lh > rh + 1 {
+            let left = node.left.as_ref().unwrap();
+            if _height(&left.left) < _height(&left.right) {
+                node.left = Some(Self::_rotate_left(node.left.take().unwrap()));
+            }
+            return Self::_rotate_right(node);
+        }
+        if rh > lh + 1 {
+            let right = node.right.as_ref().unwrap();
+            if _height(&right.right) < _height(&right.left) {
+                node.right = Some(Self::_rotate_right(node.right.take().unwrap()));
+            }
+            return Self::_rotate_left(node);
+        }
+        node.update();
+        node
+    }
+
+    fn _rotate_left(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+        let mut right = node.right.take().unwrap();
+        node.right = right.left.take();
+        node.update();
+        right.left = Some(node);
+        right.update();
+        right
+    }
+
+    fn _rotate_right(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+        let mut left = node.left.take().unwrap();
+        node.left = left.right.take();
+        node.update();
+        left.right = Some(node);
+        left.update();
+        left
+    }
+}
+
+impl<K: Ord + Clone + Debug, V: Debug> Debug for IntervalTree<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Ord + Clone, V> Extend<(Range<K>, V)> for IntervalTree<K, V> {
+    /// Inserts the intervals, the empty intervals are ignored.
+    fn extend<I: IntoIterator<Item = (Range<K>, V)>>(&mut self, iter: I) {
+        for (range, value) in iter {
+            let _ = self.insert(range, value);
+        }
+    }
+}
+
+impl<K: Ord + Clone, V> FromIterator<(Range<K>, V)> for IntervalTree<K, V> {
+    fn from_iter<I: IntoIterator<Item = (Range<K>, V)>>(iter: I) -> Self {
+        let mut tree = Self::new();
+        tree.extend(iter);
+        tree
+    }
+}
+
+impl<'a, K: Ord + Clone, V> IntoIterator for &'a IntervalTree<K, V> {
+    type Item = (&'a Range<K>, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the intervals of an [`IntervalTree`], in start order.
+pub struct Iter<'a, K, V> {
+    stack: Vec<&'a Node<K, V>>,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    fn _push_left(&mut self, mut link: Option<&'a Node<K, V>>) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = node.left.as_deref();
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a Range<K>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self._push_left(node.right.as_deref());
+        self.remaining -= 1;
+        Some((&node.range, &node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+impl<'a, K, V> FusedIterator for Iter<'a, K, V> {}
+
+/// An iterator over the intervals of an [`IntervalTree`] which overlap a
+/// query, in start order.
+pub struct Overlap<'a, K, V> {
+    stack: Vec<&'a Node<K, V>>,
+    // The overlapping intervals end after `lo`, and start before `hi`.
+    lo: K,
+    hi: Bound<K>,
+}
+
+impl<'a, K: Ord, V> Overlap<'a, K, V> {
+    fn new(root: Option<&'a Node<K, V>>, lo: K, hi: Bound<K>) -> Self {
+        let mut iter = Overlap {
+            stack: Vec::new(),
+            lo,
+            hi,
+        };
+        iter._push_left(root);
+        iter
+    }
+
+    fn _starts_before_hi(&self, node: &Node<K, V>) -> bool {
+        match &self.hi {
+            Bound::Included(hi) => node.range.start <= *hi,
+            Bound::Excluded(hi) => node.range.start < *hi,
+            Bound::Unbounded => true,
+        }
+    }
+
+    /// Pushes the left spine of the subtree, skipping the subtrees ending
+    /// before `lo`, and the nodes starting after `hi` with their right
+    /// subtrees.
+    fn _push_left(&mut self, mut link: Option<&'a Node<K, V>>) {
+        while let Some(node) = link {
+            if node.max_end <= self.lo {
+                break;
+            }
+            if self._starts_before_hi(node) {
+                self.stack.push(node);
+            }
+            link = node.left.as_deref();
+        }
+    }
+}
+
+impl<'a, K: Ord, V> Iterator for Overlap<'a, K, V> {
+    type Item = (&'a Range<K>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.stack.pop() {
+            self._push_left(node.right.as_deref());
+            if node.range.end > self.lo {
+                return Some((&node.range, &node.value));
+            }
+        }
+        None
+    }
+}
+
+impl<'a, K: Ord, V> FusedIterator for Overlap<'a, K, V> {}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use std::ops::Range;
+
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::collection::interval_tree::IntervalTree;
+
+    impl<K: Ord + Clone + Serialize, V: Serialize> Serialize for IntervalTree<K, V> {
+        /// Serializes the entries as a sequence of interval-value pairs, in
+        /// start order.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.iter())
+        }
+    }
+
+    impl<'de, K, V> Deserialize<'de> for IntervalTree<K, V>
+    where
+        K: Ord + Clone + Deserialize<'de>,
+        V: Deserialize<'de>,
+    {
+        /// Deserializes a sequence of interval-value pairs, the last value of a
+        /// repeated interval wins.
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let mut tree = IntervalTree::new();
+            for (range, value) in Vec::<(Range<K>, V)>::deserialize(deserializer)? {
+                tree.insert(range, value).map_err(D::Error::custom)?;
+            }
+            Ok(tree)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use crate::collection::error::CollectionError;
+    use crate::collection::interval_tree::{IntervalTree, Link};
+
+    /// Checks the order, the balance and the augmentation of the subtree,
+    /// and returns its height.
+    fn assert_tree<V>(link: &Link<i32, V>) -> u8 {
+        let node = match link {
+            None => return 0,
+            Some(node) => node,
+        };
+        let (lh, rh) = (assert_tree(&node.left), assert_tree(&node.right));
+        assert!(lh.abs_diff(rh) <= 1);
+        assert_eq!(node.height, 1 + lh.max(rh));
+        let mut max_end = node.range.end;
+        if let Some(left) = &node.left {
+            assert!((left.range.start, left.range.end) < (node.range.start, node.range.end));
+            max_end = max_end.max(left.max_end);
+        }
+        if let Some(right) = &node.right {
+            assert!((right.range.start, right.range.end) > (node.range.start, node.range.end));
+            max_end = max_end.max(right.max_end);
+        }
+        assert_eq!(node.max_end, max_end);
+        node.height
+    }
+
+    fn ranges<'a, V: 'a>(it: impl Iterator<Item = (&'a Range<i32>, &'a V)>) -> Vec<Range<i32>> {
+        it.map(|(r, _)| r.clone()).collect()
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut t = IntervalTree::new();
+        assert_eq!(t.insert(5..10, 1).unwrap(), None);
+        assert_eq!(t.insert(1..3, 2).unwrap(), None);
+        assert_eq!(t.insert(5..8, 3).unwrap(), None);
+        assert_eq!(t.insert(5..10, 4).unwrap(), Some(1));
+        assert!(matches!(
+            t.insert(3..3, 0),
+            Err(CollectionError::InvalidParameter(_))
+        ));
+        assert_eq!(t.length(), 3);
+        assert_eq!(t.get(&(5..10)), Some(&4));
+        assert!(!t.contains(&(5..9)));
+        assert_eq!(ranges(t.iter()), vec![1..3, 5..8, 5..10]);
+
+        assert_eq!(t.remove(&(5..8)), Some(3));
+        assert_eq!(t.remove(&(5..8)), None);
+        assert_tree(&t.root);
+        assert_eq!(ranges(t.iter()), vec![1..3, 5..10]);
+        assert_eq!(format!("{:?}", t), "{1..3: 2, 5..10: 4}");
+
+        t.clear();
+        assert!(t.is_empty());
+        assert_eq!(t.iter().next(), None);
+    }
+
+    #[test]
+    fn test_queries() {
+        let t: IntervalTree<i32, u32> =
+            vec![(0..5, 0), (3..8, 1), (6..7, 2), (10..20, 3), (12..13, 4)]
+                .into_iter()
+                .collect();
+        assert_eq!(ranges(t.query_point(&3)), vec![0..5, 3..8]);
+        assert_eq!(ranges(t.query_point(&5)), vec![3..8]);
+        assert_eq!(ranges(t.query_point(&12)), vec![10..20, 12..13]);
+        assert_eq!(ranges(t.query_point(&9)), vec![]);
+        assert_eq!(ranges(t.query_point(&20)), vec![]);
+
+        assert_eq!(ranges(t.query_overlap(5..10)), vec![3..8, 6..7]);
+        assert_eq!(
+            ranges(t.query_overlap(4..11)),
+            vec![0..5, 3..8, 6..7, 10..20]
+        );
+        assert_eq!(ranges(t.query_overlap(8..10)), vec![]);
+        assert_eq!(ranges(t.query_overlap(6..6)), vec![]);
+        assert_eq!(ranges(t.query_overlap(-10..100)).len(), 5);
+    }
+
+    #[test]
+    fn test_random_consistency() {
+        use crate::collection::test_util::random_ops;
+        use rand::Rng;
+
+        let mut t = IntervalTree::new();
+        let mut v: Vec<(Range<i32>, usize)> = Vec::new();
+        random_ops(3000, |rng, i| {
+            let start = rng.gen_range(0..200);
+            let range = start..start + rng.gen_range(1..30);
+            if rng.gen_bool(0.6) {
+                let old = v
+                    .iter()
+                    .position(|(r, _)| *r == range)
+                    .map(|j| v.remove(j).1);
+                assert_eq!(t.insert(range.clone(), i).unwrap(), old);
+                v.push((range, i));
+            } else {
+                let old = v
+                    .iter()
+                    .position(|(r, _)| *r == range)
+                    .map(|j| v.remove(j).1);
+                assert_eq!(t.remove(&range), old);
+            }
+            if i % 100 == 0 {
+                assert_tree(&t.root);
+            }
+
+            let (lo, hi) = (rng.gen_range(0..230), rng.gen_range(0..230));
+            let mut expected: Vec<Range<i32>> = v
+                .iter()
+                .map(|(r, _)| r.clone())
+                .filter(|r| lo < hi && r.start < hi && lo < r.end)
+                .collect();
+            expected.sort_by_key(|r| (r.start, r.end));
+            assert_eq!(ranges(t.query_overlap(lo..hi)), expected);
+            expected.retain(|r| r.contains(&lo));
+            if lo < hi {
+                assert_eq!(ranges(t.query_point(&lo)), expected);
+            }
+        });
+        assert_eq!(t.length(), v.len());
+        // The height of an AVL tree is less than 1.45 log2(n + 2).
+        assert!(assert_tree(&t.root) as f64 <= 1.45 * ((v.len() + 2) as f64).log2());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let t: IntervalTree<i32, &str> = vec![(4..6, "b"), (1..9, "a")].into_iter().collect();
+        let json = serde_json::to_string(&t).unwrap();
+        assert_eq!(
+            json,
+            r#"[[{"start":1,"end":9},"a"],[{"start":4,"end":6},"b"]]"#
+        );
+        let back: IntervalTree<i32, String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.get(&(4..6)).map(String::as_str), Some("b"));
+        assert!(
+            serde_json::from_str::<IntervalTree<i32, u8>>(r#"[[{"start":2,"end":1},0]]"#).is_err()
+        );
+    }
+}
@@ -1,14 +1,25 @@
-use std::fmt::Debug;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::hash::{BuildHasherDefault, Hash, Hasher};
+use std::iter::{FromIterator, FusedIterator};
 use std::marker::PhantomData;
 use std::mem;
+use std::ops::{Index, IndexMut};
 use std::ptr::NonNull;
+use std::sync::atomic::{self, AtomicU64};
 
 use crate::collection::error::CollectionError;
 
+/// The source of unique stamps for the nodes which are given a [`NodeHandle`].
+static NEXT_STAMP: AtomicU64 = AtomicU64::new(1);
+
 pub(crate) struct Node<T> {
     val: T,
     next: Option<NonNull<Node<T>>>,
     prev: Option<NonNull<Node<T>>>,
+    // The stamp of the handle to this node, or 0 if no handle was given out.
+    stamp: u64,
 }
 
 impl<T> Node<T> {
@@ -17,22 +28,81 @@ impl<T> Node<T> {
             val,
             prev: None,
             next: None,
+            stamp: 0,
         }
     }
 
-    pub fn val(&self) -> &T {
-        &self.val
-    }
-
     fn into_val(self) -> T {
         self.val
     }
 }
 
+/// An opaque handle to an element of a [`LinkedList`], which allows to access,
+/// move and remove the element in *O*(1) time.
+///
+/// A handle is only valid for the list owning its element: it is rejected by
+/// any other list, and by the owning list once the element has been removed.
+///
+/// Every list keeps the handles of its elements, which follow the elements
+/// when the list is split or spliced. Once a handle has been given out,
+/// splitting the list takes the length of the shorter part, and splicing the
+/// lesser number of handles of the two lists.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::linkedlist::LinkedList;
+/// let mut list = LinkedList::new();
+/// let a = list.push_front_handle("a");
+/// let b = list.push_front_handle("b");
+///
+/// assert_eq!(list.peek_front(), Some(&"b"));
+/// list.move_to_front(a).unwrap();
+/// assert_eq!(list.peek_front(), Some(&"a"));
+///
+/// assert_eq!(list.remove(b), Some("b"));
+/// assert_eq!(list.get(b), None);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NodeHandle {
+    stamp: u64,
+}
+
+/// The nodes which have been given a handle, by stamp.
+type Handles<T> = HashMap<u64, NonNull<Node<T>>, BuildHasherDefault<StampHasher>>;
+
+/// A hasher for the stamps, which are unique and sequential, so a single
+/// multiplication spreads them well enough.
+#[derive(Default)]
+struct StampHasher(u64);
+
+impl Hasher for StampHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.write_u64(self.0 ^ byte as u64);
+        }
+    }
+
+    #[inline]
+    fn write_u64(&mut self, stamp: u64) {
+        self.0 = stamp.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    }
+}
+
+/// A detached chain of nodes: the first one, the last one and their number.
+type Chain<T> = (NonNull<Node<T>>, NonNull<Node<T>>, usize);
+
 pub struct LinkedList<T> {
     length: usize,
     head: Option<NonNull<Node<T>>>,
     tail: Option<NonNull<Node<T>>>,
+    // The nodes of this list which have been given a handle, by stamp.
+    handles: Handles<T>,
     _marker: PhantomData<Box<Node<T>>>,
 }
 
@@ -48,6 +118,7 @@ impl<T> LinkedList<T> {
             length: 0,
             head: None,
             tail: None,
+            handles: Handles::default(),
             _marker: PhantomData,
         }
     }
@@ -64,7 +135,7 @@ impl<T> LinkedList<T> {
         self._push_front_raw(node);
     }
 
-    pub(crate) fn _push_front_raw(&mut self, mut node: NonNull<Node<T>>) {
+    fn _push_front_raw(&mut self, mut node: NonNull<Node<T>>) {
         unsafe {
             node.as_mut().next = self.head;
             node.as_mut().prev = None;
@@ -107,6 +178,7 @@ impl<T> LinkedList<T> {
 
             unsafe {
                 let node = Box::from_raw(node.as_ptr());
+                self._unregister(&node);
 
                 self.head = node.next;
 
@@ -129,6 +201,7 @@ impl<T> LinkedList<T> {
 
             unsafe {
                 let node = Box::from_raw(node.as_ptr());
+                self._unregister(&node);
 
                 self.tail = node.prev;
 
@@ -184,58 +257,60 @@ impl<T> LinkedList<T> {
         unsafe { self.tail.as_mut().map(|node| &mut node.as_mut().val) }
     }
 
-    pub fn get_by_idx(&self, idx: usize) -> Result<Option<&T>, CollectionError> {
-        let len = self.length;
-
-        if idx >= len {
-            return Err(CollectionError::IndexOutOfRange);
-        }
+    /// Provides a reference to the element at the given index, or `None` if
+    /// the index is out of range.
+    ///
+    /// This operation should compute in *O*(*min(idx, n - idx)*) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use boost_rs::collection::linkedlist::LinkedList;
+    /// let list: LinkedList<_> = vec![1, 2, 3].into_iter().collect();
+    /// assert_eq!(list.get_at(1), Some(&2));
+    /// assert_eq!(list.get_at(3), None);
+    /// ```
+    pub fn get_at(&self, idx: usize) -> Option<&T> {
+        let node = self._get_by_idx_mut(idx).ok()??;
+        unsafe { Some(&(*node.as_ptr()).val) }
+    }
 
-        // Iterate towards the node at the given index, either from the start or the end,
-        // depending on which would be faster.
-        let offset_from_end = len - idx - 1;
-        let mut cur;
-        if idx <= offset_from_end {
-            // Head to Tail
-            cur = self.head;
-            for _ in 0..idx {
-                match cur.take() {
-                    None => {
-                        cur = self.head;
-                    }
-                    Some(current) => unsafe {
-                        cur = current.as_ref().next;
-                    },
-                }
-            }
-        } else {
-            // Tail to Head
-            cur = self.tail;
-            for _ in 0..offset_from_end {
-                match cur.take() {
-                    None => {
-                        cur = self.tail;
-                    }
-                    Some(current) => unsafe {
-                        cur = current.as_ref().prev;
-                    },
-                }
-            }
-        }
+    /// Provides a mutable reference to the element at the given index, or
+    /// `None` if the index is out of range.
+    ///
+    /// This operation should compute in *O*(*min(idx, n - idx)*) time.
+    pub fn get_at_mut(&mut self, idx: usize) -> Option<&mut T> {
+        let node = self._get_by_idx_mut(idx).ok()??;
+        unsafe { Some(&mut (*node.as_ptr()).val) }
+    }
 
-        unsafe { Ok(cur.as_ref().map(|node| &node.as_ref().val)) }
+    /// The `Ok` value is never `None`: use [`LinkedList::get_at`] instead,
+    /// and `get_at(idx).ok_or(..)` where an error is needed.
+    #[deprecated(
+        since = "0.0.10",
+        note = "use `get_at`, which returns `None` if the index is out of range"
+    )]
+    pub fn get_by_idx(&self, idx: usize) -> Result<Option<&T>, CollectionError> {
+        self._check_index(idx)?;
+        Ok(self.get_at(idx))
     }
 
-    pub fn get_by_idx_mut(&self, idx: usize) -> Result<Option<&mut T>, CollectionError> {
-        let mut cur = self._get_by_idx_mut(idx)?;
-        unsafe { Ok(cur.as_mut().map(|node| &mut node.as_mut().val)) }
+    /// The `Ok` value is never `None`: use [`LinkedList::get_at_mut`] instead,
+    /// and `get_at_mut(idx).ok_or(..)` where an error is needed.
+    #[deprecated(
+        since = "0.0.10",
+        note = "use `get_at_mut`, which returns `None` if the index is out of range"
+    )]
+    pub fn get_by_idx_mut(&mut self, idx: usize) -> Result<Option<&mut T>, CollectionError> {
+        self._check_index(idx)?;
+        Ok(self.get_at_mut(idx))
     }
 
     pub fn insert_by_idx(&mut self, idx: usize, data: T) -> Result<(), CollectionError> {
         let len = self.length;
 
         if idx > len {
-            return Err(CollectionError::IndexOutOfRange);
+            return Err(CollectionError::IndexOutOfRange { index: idx, len });
         }
 
         if idx == 0 {
@@ -275,9 +350,7 @@ impl<T> LinkedList<T> {
     pub fn remove_by_idx(&mut self, idx: usize) -> Result<T, CollectionError> {
         let len = self.length;
 
-        if idx >= len {
-            return Err(CollectionError::IndexOutOfRange);
-        }
+        self._check_index(idx)?;
 
         if idx == 0 {
             return Ok(self.pop_front().unwrap());
@@ -289,10 +362,7 @@ impl<T> LinkedList<T> {
 
         self.unlink_node(cur);
 
-        unsafe {
-            let unlinked_node = Box::from_raw(cur.as_ptr());
-            Ok(unlinked_node.val)
-        }
+        Ok(self._free_node(cur))
     }
 
     /// Returns `true` if the `LinkedList` contains an element equal to the given value.
@@ -323,6 +393,160 @@ impl<T> LinkedList<T> {
         *self = Self::new();
     }
 
+    /// Moves all elements from `other` to the end of the list, leaving
+    /// `other` empty.
+    ///
+    /// This operation should compute in *O*(1) time, plus the lesser number
+    /// of handles given out by the two lists.
+    pub fn append(&mut self, other: &mut Self) {
+        if let Some((head, tail, length)) = other._detach_all() {
+            let prev = self.tail;
+            self._splice_nodes(prev, None, head, tail, length);
+            self._adopt_handles(other);
+        }
+    }
+
+    /// Splits the list into two at the given index. Returns everything after
+    /// the given index, including the index.
+    ///
+    /// This operation should compute in *O*(*n*) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use boost_rs::collection::linkedlist::LinkedList;
+    /// let mut list = LinkedList::new();
+    /// (1..=5).for_each(|x| list.push_back(x));
+    ///
+    /// let tail = list.split_off(2).unwrap();
+    /// assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2]);
+    /// assert_eq!(tail.iter().copied().collect::<Vec<_>>(), vec![3, 4, 5]);
+    /// ```
+    pub fn split_off(&mut self, idx: usize) -> Result<Self, CollectionError> {
+        if idx > self.length {
+            return Err(CollectionError::IndexOutOfRange {
+                index: idx,
+                len: self.length,
+            });
+        }
+        if idx == 0 {
+            return Ok(mem::take(self));
+        }
+
+        let split_node = self._get_by_idx_mut(idx - 1)?;
+        Ok(self._split_off_after_node(split_node, idx))
+    }
+
+    /// Reverses the order of the elements in place.
+    ///
+    /// This operation should compute in *O*(*n*) time.
+    pub fn reverse(&mut self) {
+        let mut cur = self.head;
+        while let Some(node) = cur {
+            unsafe {
+                let node = &mut *node.as_ptr();
+                mem::swap(&mut node.next, &mut node.prev);
+                cur = node.prev;
+            }
+        }
+        mem::swap(&mut self.head, &mut self.tail);
+    }
+
+    /// Sorts the list with a comparator function.
+    ///
+    /// The sort is stable, and relinks the nodes without moving the elements,
+    /// so handles stay valid. It is a merge sort, which computes in
+    /// *O*(*n* log *n*) time.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        if self.length < 2 {
+            return;
+        }
+
+        unsafe {
+            let head = Self::_merge_sort(self.head.unwrap(), self.length, &mut compare);
+
+            // Only the `next` links are kept up to date while sorting.
+            let mut prev = None;
+            let mut cur = Some(head);
+            while let Some(node) = cur {
+                (*node.as_ptr()).prev = prev;
+                prev = cur;
+                cur = node.as_ref().next;
+            }
+            self.head = Some(head);
+            self.tail = prev;
+        }
+    }
+
+    /// Removes consecutive elements for which `same_bucket` returns `true`,
+    /// keeping the first one of them.
+    ///
+    /// `same_bucket` is given the element to remove first, and the previous
+    /// kept element second, the same as [`Vec::dedup_by`].
+    ///
+    /// This operation should compute in *O*(*n*) time.
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        let mut kept = match self.head {
+            Some(head) => head,
+            None => return,
+        };
+
+        while let Some(node) = unsafe { kept.as_ref().next } {
+            let duplicate =
+                unsafe { same_bucket(&mut (*node.as_ptr()).val, &mut (*kept.as_ptr()).val) };
+            if duplicate {
+                self.unlink_node(node);
+                self._free_node(node);
+            } else {
+                kept = node;
+            }
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// This operation should compute in *O*(*n*) time.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.drain_filter(|x| !f(x)).for_each(drop);
+    }
+
+    /// Creates an iterator which uses a closure to determine if an element
+    /// should be removed.
+    ///
+    /// If the closure returns `true`, the element is removed and yielded. The
+    /// elements left when the iterator is dropped are still filtered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use boost_rs::collection::linkedlist::LinkedList;
+    /// let mut list = LinkedList::new();
+    /// (1..=6).for_each(|x| list.push_back(x));
+    ///
+    /// let evens: Vec<_> = list.drain_filter(|x| *x % 2 == 0).collect();
+    /// assert_eq!(evens, vec![2, 4, 6]);
+    /// assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 3, 5]);
+    /// ```
+    pub fn drain_filter<F>(&mut self, filter: F) -> DrainFilter<'_, T, F>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        DrainFilter {
+            it: self.head,
+            list: self,
+            pred: filter,
+        }
+    }
+
     pub fn iter(&self) -> Iter<'_, T> {
         Iter {
             head: self.head,
@@ -341,12 +565,82 @@ impl<T> LinkedList<T> {
         }
     }
 
+    /// Provides a cursor at the front element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        Cursor {
+            index: 0,
+            current: self.head,
+            list: self,
+        }
+    }
+
+    /// Provides a cursor at the back element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    pub fn cursor_back(&self) -> Cursor<'_, T> {
+        Cursor {
+            index: self.length.saturating_sub(1),
+            current: self.tail,
+            list: self,
+        }
+    }
+
+    /// Provides a cursor with editing operations at the front element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use boost_rs::collection::linkedlist::LinkedList;
+    /// let mut list = LinkedList::new();
+    /// list.push_back(1);
+    /// list.push_back(3);
+    ///
+    /// let mut cursor = list.cursor_front_mut();
+    /// cursor.insert_after(2);
+    /// cursor.move_next();
+    /// assert_eq!(cursor.current(), Some(&mut 2));
+    /// assert_eq!(cursor.remove_current(), Some(2));
+    /// assert_eq!(cursor.current(), Some(&mut 3));
+    /// ```
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: 0,
+            current: self.head,
+            list: self,
+        }
+    }
+
+    /// Provides a cursor with editing operations at the back element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: self.length.saturating_sub(1),
+            current: self.tail,
+            list: self,
+        }
+    }
+
+    /// Returns an error unless `idx` is the index of an element.
+    #[inline]
+    fn _check_index(&self, idx: usize) -> Result<(), CollectionError> {
+        if idx >= self.length {
+            return Err(CollectionError::IndexOutOfRange {
+                index: idx,
+                len: self.length,
+            });
+        }
+        Ok(())
+    }
+
     fn _get_by_idx_mut(&self, idx: usize) -> Result<Option<NonNull<Node<T>>>, CollectionError> {
         let len = self.length;
 
-        if idx >= len {
-            return Err(CollectionError::IndexOutOfRange);
-        }
+        self._check_index(idx)?;
 
         // Iterate towards the node at the given index, either from the start or the end,
         // depending on which would be faster.
@@ -383,12 +677,159 @@ impl<T> LinkedList<T> {
         Ok(cur)
     }
 
-    /// Move the raw node pointer to the head of the list.
+    /// Adds the given element to the front of the list, and returns a handle
+    /// to it.
     ///
-    /// Warning: this will not check that the provided node belongs to the current list.
-    pub(crate) fn move_raw_node_to_head(&mut self, node: NonNull<Node<T>>) {
+    /// This operation should compute in *O*(1) time.
+    pub fn push_front_handle(&mut self, val: T) -> NodeHandle {
+        let node = self._new_handle_node(val);
+        self._push_front_raw(node);
+        NodeHandle {
+            stamp: unsafe { node.as_ref().stamp },
+        }
+    }
+
+    /// Adds the given element to the back of the list, and returns a handle
+    /// to it.
+    ///
+    /// This operation should compute in *O*(1) time.
+    pub fn push_back_handle(&mut self, val: T) -> NodeHandle {
+        let node = self._new_handle_node(val);
+        let prev = self.tail;
+        self._splice_nodes(prev, None, node, node, 1);
+        NodeHandle {
+            stamp: unsafe { node.as_ref().stamp },
+        }
+    }
+
+    /// Returns `true` if the handle refers to an element of this list.
+    pub fn contains_handle(&self, handle: NodeHandle) -> bool {
+        self.handles.contains_key(&handle.stamp)
+    }
+
+    /// Provides a reference to the element of the handle, or `None` if the
+    /// handle is not valid for this list.
+    ///
+    /// This operation should compute in *O*(1) time.
+    pub fn get(&self, handle: NodeHandle) -> Option<&T> {
+        let node = self.handles.get(&handle.stamp)?;
+        unsafe { Some(&(*node.as_ptr()).val) }
+    }
+
+    /// Provides a mutable reference to the element of the handle, or `None`
+    /// if the handle is not valid for this list.
+    ///
+    /// This operation should compute in *O*(1) time.
+    pub fn get_mut(&mut self, handle: NodeHandle) -> Option<&mut T> {
+        let node = self.handles.get(&handle.stamp)?;
+        unsafe { Some(&mut (*node.as_ptr()).val) }
+    }
+
+    /// Removes the element of the handle and returns it, or `None` if the
+    /// handle is not valid for this list.
+    ///
+    /// This operation should compute in *O*(1) time.
+    pub fn remove(&mut self, handle: NodeHandle) -> Option<T> {
+        let node = *self.handles.get(&handle.stamp)?;
+        self.unlink_node(node);
+        Some(self._free_node(node))
+    }
+
+    /// Moves the element of the handle to the front of the list. Returns
+    /// [`CollectionError::KeyNotFound`] if the handle is not valid for this list.
+    ///
+    /// This operation should compute in *O*(1) time.
+    pub fn move_to_front(&mut self, handle: NodeHandle) -> Result<(), CollectionError> {
+        let node = self._handle_node(handle)?;
         self.unlink_node(node);
         self._push_front_raw(node);
+        Ok(())
+    }
+
+    /// Moves the element of the handle to the back of the list. Returns
+    /// [`CollectionError::KeyNotFound`] if the handle is not valid for this list.
+    ///
+    /// This operation should compute in *O*(1) time.
+    pub fn move_to_back(&mut self, handle: NodeHandle) -> Result<(), CollectionError> {
+        let node = self._handle_node(handle)?;
+        self.unlink_node(node);
+        let prev = self.tail;
+        self._splice_nodes(prev, None, node, node, 1);
+        Ok(())
+    }
+
+    /// Returns the node of the handle, if it is valid for this list.
+    #[inline]
+    fn _handle_node(&self, handle: NodeHandle) -> Result<NonNull<Node<T>>, CollectionError> {
+        self.handles
+            .get(&handle.stamp)
+            .copied()
+            .ok_or(CollectionError::KeyNotFound)
+    }
+
+    /// Allocates a detached node with a fresh stamp, and registers it.
+    fn _new_handle_node(&mut self, val: T) -> NonNull<Node<T>> {
+        let mut node = Box::new(Node::new(val));
+        node.stamp = NEXT_STAMP.fetch_add(1, atomic::Ordering::Relaxed);
+        let stamp = node.stamp;
+        let node = NonNull::new(Box::into_raw(node)).unwrap();
+        self.handles.insert(stamp, node);
+        node
+    }
+
+    /// Forgets the handle of the node, if it has one.
+    #[inline]
+    fn _unregister(&mut self, node: &Node<T>) {
+        if node.stamp != 0 {
+            self.handles.remove(&node.stamp);
+        }
+    }
+
+    /// Frees an unlinked node and returns its element.
+    #[inline]
+    fn _free_node(&mut self, node: NonNull<Node<T>>) -> T {
+        let node = unsafe { Box::from_raw(node.as_ptr()) };
+        self._unregister(&node);
+        node.into_val()
+    }
+
+    /// Moves the handles of the nodes of `other`, which have just been taken
+    /// out of this list, over to `other`.
+    ///
+    /// This walks the shorter of the two lists, unless no handle was given
+    /// out at all.
+    fn _move_handles_to(&mut self, other: &mut Self) {
+        if self.handles.is_empty() {
+            return;
+        }
+        if other.length <= self.length {
+            Self::_move_handles_of(other.head, &mut self.handles, &mut other.handles);
+        } else {
+            // All the handles go to `other`, and those of the nodes left in
+            // this list come back.
+            mem::swap(&mut self.handles, &mut other.handles);
+            Self::_move_handles_of(self.head, &mut other.handles, &mut self.handles);
+        }
+    }
+
+    /// Moves the handles of the chain of nodes starting at `head` from `from`
+    /// to `to`.
+    fn _move_handles_of(
+        head: Option<NonNull<Node<T>>>,
+        from: &mut Handles<T>,
+        to: &mut Handles<T>,
+    ) {
+        let mut cur = head;
+        while let Some(node) = cur {
+            unsafe {
+                let stamp = node.as_ref().stamp;
+                if stamp != 0 {
+                    from.remove(&stamp);
+                    to.insert(stamp, node);
+                }
+                cur = node.as_ref().next;
+            }
+        }
     }
 
     /// Unlinks the specified node from the current list.
@@ -416,37 +857,226 @@ impl<T> LinkedList<T> {
 
         self.length -= 1;
     }
-}
-
-impl<T: Eq> LinkedList<T> {
-    /// Remove the element that equals to the given val and returns the
-    ///
-    /// element with ownership.
-    ///
-    /// There maybe be more than one element, the remove order will NOT be guaranteed!
-    ///
-    /// This operation should compute in *O*(*n*) time.
-    pub fn remove_by_val(&mut self, val: &T) -> Option<T> {
-        if self.length() == 0 {
-            return None;
-        }
-
-        if self.peek_front()? == val {
-            return self.pop_front();
-        } else if self.peek_back()? == val {
-            return self.pop_back();
-        }
 
-        let cur = self._get_raw_by_val(val)?;
-        self.unlink_node(cur);
+    /// Links the chain of nodes from `splice_start` to `splice_end` between
+    /// `prev` and `next`, which must be adjacent in the current list.
+    #[inline]
+    fn _splice_nodes(
+        &mut self,
+        prev: Option<NonNull<Node<T>>>,
+        next: Option<NonNull<Node<T>>>,
+        splice_start: NonNull<Node<T>>,
+        splice_end: NonNull<Node<T>>,
+        splice_length: usize,
+    ) {
         unsafe {
-            let unlinked_node = Box::from_raw(cur.as_ptr());
-            Some(unlinked_node.val)
+            match prev {
+                Some(prev) => (*prev.as_ptr()).next = Some(splice_start),
+                None => self.head = Some(splice_start),
+            }
+            match next {
+                Some(next) => (*next.as_ptr()).prev = Some(splice_end),
+                None => self.tail = Some(splice_end),
+            }
+            (*splice_start.as_ptr()).prev = prev;
+            (*splice_end.as_ptr()).next = next;
         }
+
+        self.length += splice_length;
+    }
+
+    /// Takes all the nodes out of the list, returning the first one, the last
+    /// one and their number, or `None` if the list is empty.
+    ///
+    /// The handles of the nodes are left in the list, see `_adopt_handles`.
+    #[inline]
+    fn _detach_all(&mut self) -> Option<Chain<T>> {
+        let head = self.head.take()?;
+        let tail = self.tail.take()?;
+        let length = mem::replace(&mut self.length, 0);
+        Some((head, tail, length))
+    }
+
+    /// Sorts the chain of `length` nodes starting at `head` by their `next`
+    /// links, and returns the new first node.
+    unsafe fn _merge_sort<F>(
+        head: NonNull<Node<T>>,
+        length: usize,
+        compare: &mut F,
+    ) -> NonNull<Node<T>>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        if length == 1 {
+            (*head.as_ptr()).next = None;
+            return head;
+        }
+
+        let half = length / 2;
+        let mut mid = head;
+        for _ in 0..half {
+            mid = mid.as_ref().next.unwrap();
+        }
+        // Sort the second half first, as sorting the first one cuts it off.
+        let mut right = Some(Self::_merge_sort(mid, length - half, compare));
+        let mut left = Some(Self::_merge_sort(head, half, compare));
+
+        let mut merged: Option<NonNull<Node<T>>> = None;
+        let mut last: Option<NonNull<Node<T>>> = None;
+        loop {
+            let next = match (left, right) {
+                (Some(l), Some(r)) => {
+                    // Ties are taken from the left, which keeps the sort stable.
+                    if compare(&l.as_ref().val, &r.as_ref().val) == Ordering::Greater {
+                        right = r.as_ref().next;
+                        r
+                    } else {
+                        left = l.as_ref().next;
+                        l
+                    }
+                }
+                (Some(rest), None) | (None, Some(rest)) => {
+                    match last {
+                        Some(last) => (*last.as_ptr()).next = Some(rest),
+                        None => merged = Some(rest),
+                    }
+                    break;
+                }
+                (None, None) => break,
+            };
+            match last {
+                Some(last) => (*last.as_ptr()).next = Some(next),
+                None => merged = Some(next),
+            }
+            last = Some(next);
+        }
+        merged.unwrap()
+    }
+
+    /// Takes over the handles of `other`, whose nodes have been detached and
+    /// linked into this list.
+    ///
+    /// The smaller map is moved into the larger one, so this computes in the
+    /// lesser number of handles.
+    #[inline]
+    fn _adopt_handles(&mut self, other: &mut Self) {
+        if other.handles.len() > self.handles.len() {
+            mem::swap(&mut self.handles, &mut other.handles);
+        }
+        if !other.handles.is_empty() {
+            self.handles.extend(other.handles.drain());
+        }
+    }
+
+    /// Splits the list after `split_node`, which is at the index `at - 1`,
+    /// and returns the second part.
+    ///
+    /// The whole list is split off if `split_node` is `None`.
+    fn _split_off_after_node(&mut self, split_node: Option<NonNull<Node<T>>>, at: usize) -> Self {
+        let split_node = match split_node {
+            Some(node) => node,
+            None => return mem::take(self),
+        };
+
+        unsafe {
+            let second_head = (*split_node.as_ptr()).next.take();
+            let second_tail = match second_head {
+                Some(head) => {
+                    (*head.as_ptr()).prev = None;
+                    self.tail
+                }
+                None => None,
+            };
+            let mut second = LinkedList {
+                length: self.length - at,
+                head: second_head,
+                tail: second_tail,
+                handles: Handles::default(),
+                _marker: PhantomData,
+            };
+
+            self.tail = Some(split_node);
+            self.length = at;
+            self._move_handles_to(&mut second);
+            second
+        }
+    }
+
+    /// Splits the list before `split_node`, which is at the index `at`, and
+    /// returns the first part.
+    ///
+    /// The whole list is split off if `split_node` is `None`.
+    fn _split_off_before_node(&mut self, split_node: Option<NonNull<Node<T>>>, at: usize) -> Self {
+        let split_node = match split_node {
+            Some(node) => node,
+            None => return mem::take(self),
+        };
+
+        unsafe {
+            let first_tail = (*split_node.as_ptr()).prev.take();
+            let first_head = match first_tail {
+                Some(tail) => {
+                    (*tail.as_ptr()).next = None;
+                    self.head
+                }
+                None => None,
+            };
+            let mut first = LinkedList {
+                length: at,
+                head: first_head,
+                tail: first_tail,
+                handles: Handles::default(),
+                _marker: PhantomData,
+            };
+
+            self.head = Some(split_node);
+            self.length -= at;
+            self._move_handles_to(&mut first);
+            first
+        }
+    }
+}
+
+impl<T: Ord> LinkedList<T> {
+    /// Sorts the list, see [`LinkedList::sort_by`].
+    pub fn sort(&mut self) {
+        self.sort_by(|a, b| a.cmp(b))
+    }
+}
+
+impl<T: PartialEq> LinkedList<T> {
+    /// Removes consecutive repeated elements, see [`LinkedList::dedup_by`].
+    pub fn dedup(&mut self) {
+        self.dedup_by(|a, b| a == b)
+    }
+}
+
+impl<T: Eq> LinkedList<T> {
+    /// Remove the element that equals to the given val and returns the
+    ///
+    /// element with ownership.
+    ///
+    /// There maybe be more than one element, the remove order will NOT be guaranteed!
+    ///
+    /// This operation should compute in *O*(*n*) time.
+    pub fn remove_by_val(&mut self, val: &T) -> Option<T> {
+        if self.length() == 0 {
+            return None;
+        }
+
+        if self.peek_front()? == val {
+            return self.pop_front();
+        } else if self.peek_back()? == val {
+            return self.pop_back();
+        }
+
+        let cur = self._get_raw_by_val(val)?;
+        self.unlink_node(cur);
+        Some(self._free_node(cur))
     }
 
     #[inline]
-    pub(crate) fn _get_raw_by_val(&self, val: &T) -> Option<NonNull<Node<T>>> {
+    fn _get_raw_by_val(&self, val: &T) -> Option<NonNull<Node<T>>> {
         if self.length == 0 {
             return None;
         }
@@ -465,13 +1095,110 @@ impl<T: Eq> LinkedList<T> {
     }
 }
 
-impl<T: Debug> LinkedList<T> {
-    pub fn traverse(&self) {
-        print!("{{ ");
-        for (idx, x) in self.iter().enumerate() {
-            print!(" [{}: {:?}] ", idx, *x)
+// The list owns its nodes, like a `Box` would.
+unsafe impl<T: Send> Send for LinkedList<T> {}
+
+unsafe impl<T: Sync> Sync for LinkedList<T> {}
+
+impl<T: Clone> Clone for LinkedList<T> {
+    /// Clones the elements of the list. Handles are not carried over, as
+    /// they are only valid for the list they were given out by.
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: Debug> Debug for LinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for LinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.length == other.length && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for LinkedList<T> {}
+
+impl<T: Hash> Hash for LinkedList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.length);
+        for x in self.iter() {
+            x.hash(state);
         }
-        println!(" }}");
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for LinkedList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for LinkedList<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|x| self.push_back(x));
+    }
+}
+
+impl<'a, T: 'a + Copy> Extend<&'a T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+/// Handles give *O*(1) access to the elements of the list.
+impl<T> Index<NodeHandle> for LinkedList<T> {
+    type Output = T;
+
+    /// # Panics
+    ///
+    /// Panics if the handle is not valid for this list.
+    fn index(&self, handle: NodeHandle) -> &T {
+        self.get(handle).expect("invalid node handle")
+    }
+}
+
+impl<T> IndexMut<NodeHandle> for LinkedList<T> {
+    /// # Panics
+    ///
+    /// Panics if the handle is not valid for this list.
+    fn index_mut(&mut self, handle: NodeHandle) -> &mut T {
+        self.get_mut(handle).expect("invalid node handle")
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
     }
 }
 
@@ -541,6 +1268,10 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
     }
 }
 
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
 pub struct Iter<'a, T: 'a> {
     head: Option<NonNull<Node<T>>>,
     tail: Option<NonNull<Node<T>>>,
@@ -548,6 +1279,16 @@ pub struct Iter<'a, T: 'a> {
     _marker: PhantomData<&'a Node<T>>,
 }
 
+unsafe impl<'a, T: Sync> Send for Iter<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter { ..*self }
+    }
+}
+
 impl<'a, T> Iterator for Iter<'a, T> {
     type Item = &'a T;
 
@@ -598,6 +1339,10 @@ impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
     }
 }
 
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
 pub struct IterMut<'a, T: 'a> {
     head: Option<NonNull<Node<T>>>,
     tail: Option<NonNull<Node<T>>>,
@@ -605,6 +1350,10 @@ pub struct IterMut<'a, T: 'a> {
     _marker: PhantomData<&'a mut Node<T>>,
 }
 
+unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
+
 impl<'a, T> Iterator for IterMut<'a, T> {
     type Item = &'a mut T;
 
@@ -656,8 +1405,401 @@ impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
     }
 }
 
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<'a, T> FusedIterator for IterMut<'a, T> {}
+
+/// An iterator produced by calling `drain_filter` on a `LinkedList`.
+pub struct DrainFilter<'a, T: 'a, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    list: &'a mut LinkedList<T>,
+    it: Option<NonNull<Node<T>>>,
+    pred: F,
+}
+
+impl<'a, T, F> Iterator for DrainFilter<'a, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while let Some(node) = self.it {
+            unsafe {
+                self.it = node.as_ref().next;
+                if (self.pred)(&mut (*node.as_ptr()).val) {
+                    self.list.unlink_node(node);
+                    return Some(self.list._free_node(node));
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.list.length))
+    }
+}
+
+impl<'a, T, F> Drop for DrainFilter<'a, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+/// A cursor over a `LinkedList`.
+///
+/// A cursor points to an element of the list, or to a "ghost" non-element
+/// which sits between the back and the front of the list. Moving the cursor
+/// computes in *O*(1) time, and wraps around through the ghost.
+pub struct Cursor<'a, T: 'a> {
+    index: usize,
+    current: Option<NonNull<Node<T>>>,
+    list: &'a LinkedList<T>,
+}
+
+unsafe impl<'a, T: Sync> Send for Cursor<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for Cursor<'a, T> {}
+
+impl<'a, T> Clone for Cursor<'a, T> {
+    fn clone(&self) -> Self {
+        Cursor {
+            index: self.index,
+            current: self.current,
+            list: self.list,
+        }
+    }
+}
+
+impl<'a, T> Cursor<'a, T> {
+    /// Returns the index of the current element, or `None` if the cursor is
+    /// pointing to the ghost.
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    /// Moves the cursor to the next element, the front element if the cursor
+    /// is pointing to the ghost, or the ghost if it is at the back element.
+    pub fn move_next(&mut self) {
+        match self.current.take() {
+            None => {
+                self.current = self.list.head;
+                self.index = 0;
+            }
+            Some(current) => unsafe {
+                self.current = current.as_ref().next;
+                self.index += 1;
+            },
+        }
+    }
+
+    /// Moves the cursor to the previous element, the back element if the
+    /// cursor is pointing to the ghost, or the ghost if it is at the front element.
+    pub fn move_prev(&mut self) {
+        match self.current.take() {
+            None => {
+                self.current = self.list.tail;
+                self.index = self.list.length.saturating_sub(1);
+            }
+            Some(current) => unsafe {
+                self.current = current.as_ref().prev;
+                self.index = self.index.checked_sub(1).unwrap_or(self.list.length);
+            },
+        }
+    }
+
+    /// Returns a reference to the current element, or `None` if the cursor is
+    /// pointing to the ghost.
+    pub fn current(&self) -> Option<&'a T> {
+        unsafe { self.current.map(|node| &(*node.as_ptr()).val) }
+    }
+
+    /// Returns a reference to the next element, which is the front element if
+    /// the cursor is pointing to the ghost.
+    pub fn peek_next(&self) -> Option<&'a T> {
+        unsafe {
+            let next = match self.current {
+                None => self.list.head,
+                Some(current) => current.as_ref().next,
+            };
+            next.map(|node| &(*node.as_ptr()).val)
+        }
+    }
+
+    /// Returns a reference to the previous element, which is the back element
+    /// if the cursor is pointing to the ghost.
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        unsafe {
+            let prev = match self.current {
+                None => self.list.tail,
+                Some(current) => current.as_ref().prev,
+            };
+            prev.map(|node| &(*node.as_ptr()).val)
+        }
+    }
+}
+
+/// A cursor over a `LinkedList` with editing operations.
+///
+/// Besides moving around like a [`Cursor`], it can insert, remove, split and
+/// splice the list at its position in *O*(1) time, as long as no
+/// [`NodeHandle`] has been given out.
+pub struct CursorMut<'a, T: 'a> {
+    index: usize,
+    current: Option<NonNull<Node<T>>>,
+    list: &'a mut LinkedList<T>,
+}
+
+unsafe impl<'a, T: Send> Send for CursorMut<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for CursorMut<'a, T> {}
+
+impl<'a, T> CursorMut<'a, T> {
+    /// Returns the index of the current element, or `None` if the cursor is
+    /// pointing to the ghost.
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    /// Moves the cursor to the next element, the front element if the cursor
+    /// is pointing to the ghost, or the ghost if it is at the back element.
+    pub fn move_next(&mut self) {
+        match self.current.take() {
+            None => {
+                self.current = self.list.head;
+                self.index = 0;
+            }
+            Some(current) => unsafe {
+                self.current = current.as_ref().next;
+                self.index += 1;
+            },
+        }
+    }
+
+    /// Moves the cursor to the previous element, the back element if the
+    /// cursor is pointing to the ghost, or the ghost if it is at the front element.
+    pub fn move_prev(&mut self) {
+        match self.current.take() {
+            None => {
+                self.current = self.list.tail;
+                self.index = self.list.length.saturating_sub(1);
+            }
+            Some(current) => unsafe {
+                self.current = current.as_ref().prev;
+                self.index = self.index.checked_sub(1).unwrap_or(self.list.length);
+            },
+        }
+    }
+
+    /// Returns a mutable reference to the current element, or `None` if the
+    /// cursor is pointing to the ghost.
+    pub fn current(&mut self) -> Option<&mut T> {
+        unsafe { self.current.map(|node| &mut (*node.as_ptr()).val) }
+    }
+
+    /// Returns a mutable reference to the next element, which is the front
+    /// element if the cursor is pointing to the ghost.
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        unsafe {
+            let next = match self.current {
+                None => self.list.head,
+                Some(current) => current.as_ref().next,
+            };
+            next.map(|node| &mut (*node.as_ptr()).val)
+        }
+    }
+
+    /// Returns a mutable reference to the previous element, which is the back
+    /// element if the cursor is pointing to the ghost.
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        unsafe {
+            let prev = match self.current {
+                None => self.list.tail,
+                Some(current) => current.as_ref().prev,
+            };
+            prev.map(|node| &mut (*node.as_ptr()).val)
+        }
+    }
+
+    /// Returns a read-only cursor pointing to the current element.
+    pub fn as_cursor(&self) -> Cursor<'_, T> {
+        Cursor {
+            index: self.index,
+            current: self.current,
+            list: self.list,
+        }
+    }
+
+    /// Inserts a new element after the current one. If the cursor is pointing
+    /// to the ghost, the element is inserted at the front of the list.
+    ///
+    /// This operation should compute in *O*(1) time.
+    pub fn insert_after(&mut self, item: T) {
+        let node = NonNull::new(Box::into_raw(Box::new(Node::new(item)))).unwrap();
+        let (prev, next) = match self.current {
+            None => (None, self.list.head),
+            Some(current) => (Some(current), unsafe { current.as_ref().next }),
+        };
+        self.list._splice_nodes(prev, next, node, node, 1);
+        if self.current.is_none() {
+            // The ghost stays at the index `length`.
+            self.index += 1;
+        }
+    }
+
+    /// Inserts a new element before the current one. If the cursor is
+    /// pointing to the ghost, the element is inserted at the back of the list.
+    ///
+    /// This operation should compute in *O*(1) time.
+    pub fn insert_before(&mut self, item: T) {
+        let node = NonNull::new(Box::into_raw(Box::new(Node::new(item)))).unwrap();
+        let (prev, next) = match self.current {
+            None => (self.list.tail, None),
+            Some(current) => (unsafe { current.as_ref().prev }, Some(current)),
+        };
+        self.list._splice_nodes(prev, next, node, node, 1);
+        self.index += 1;
+    }
+
+    /// Removes the current element and returns it, moving the cursor to the
+    /// next element. Nothing is removed if the cursor is pointing to the ghost.
+    ///
+    /// This operation should compute in *O*(1) time.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let unlinked = self.current?;
+        self.current = unsafe { unlinked.as_ref().next };
+        self.list.unlink_node(unlinked);
+        Some(self.list._free_node(unlinked))
+    }
+
+    /// Splits the list into two after the current element, and returns
+    /// everything after it. If the cursor is pointing to the ghost, the whole
+    /// list is returned.
+    ///
+    /// This operation should compute in *O*(1) time if no handle has been
+    /// given out. Otherwise the handles have to follow their elements, which
+    /// takes the length of the shorter part.
+    pub fn split_after(&mut self) -> LinkedList<T> {
+        let split_off_idx = match self.current {
+            None => {
+                self.index = 0;
+                0
+            }
+            Some(_) => self.index + 1,
+        };
+        self.list._split_off_after_node(self.current, split_off_idx)
+    }
+
+    /// Splits the list into two before the current element, and returns
+    /// everything before it. If the cursor is pointing to the ghost, the whole
+    /// list is returned.
+    ///
+    /// This operation should compute in *O*(1) time if no handle has been
+    /// given out. Otherwise the handles have to follow their elements, which
+    /// takes the length of the shorter part.
+    pub fn split_before(&mut self) -> LinkedList<T> {
+        let split_off_idx = mem::replace(&mut self.index, 0);
+        self.list
+            ._split_off_before_node(self.current, split_off_idx)
+    }
+
+    /// Inserts the elements of `list` after the current element. If the cursor
+    /// is pointing to the ghost, they are inserted at the front of the list.
+    ///
+    /// This operation should compute in *O*(1) time, plus the lesser number
+    /// of handles given out by the two lists.
+    pub fn splice_after(&mut self, mut list: LinkedList<T>) {
+        let (splice_start, splice_end, splice_length) = match list._detach_all() {
+            Some(nodes) => nodes,
+            None => return,
+        };
+        let (prev, next) = match self.current {
+            None => (None, self.list.head),
+            Some(current) => (Some(current), unsafe { current.as_ref().next }),
+        };
+        self.list
+            ._splice_nodes(prev, next, splice_start, splice_end, splice_length);
+        self.list._adopt_handles(&mut list);
+        if self.current.is_none() {
+            self.index += splice_length;
+        }
+    }
+
+    /// Inserts the elements of `list` before the current element. If the
+    /// cursor is pointing to the ghost, they are inserted at the back of the list.
+    ///
+    /// This operation should compute in *O*(1) time, plus the lesser number
+    /// of handles given out by the two lists.
+    pub fn splice_before(&mut self, mut list: LinkedList<T>) {
+        let (splice_start, splice_end, splice_length) = match list._detach_all() {
+            Some(nodes) => nodes,
+            None => return,
+        };
+        let (prev, next) = match self.current {
+            None => (self.list.tail, None),
+            Some(current) => (unsafe { current.as_ref().prev }, Some(current)),
+        };
+        self.list
+            ._splice_nodes(prev, next, splice_start, splice_end, splice_length);
+        self.list._adopt_handles(&mut list);
+        self.index += splice_length;
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use std::fmt;
+    use std::marker::PhantomData;
+
+    use serde::de::{SeqAccess, Visitor};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::collection::linkedlist::LinkedList;
+
+    impl<T: Serialize> Serialize for LinkedList<T> {
+        /// Serializes the elements as a sequence, from front to back. Node
+        /// handles are not serialized.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.iter())
+        }
+    }
+
+    impl<'de, T: Deserialize<'de>> Deserialize<'de> for LinkedList<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct SeqVisitor<T>(PhantomData<T>);
+
+            impl<'de, T: Deserialize<'de>> Visitor<'de> for SeqVisitor<T> {
+                type Value = LinkedList<T>;
+
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("a sequence")
+                }
+
+                fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                    let mut list = LinkedList::new();
+                    while let Some(x) = seq.next_element()? {
+                        list.push_back(x);
+                    }
+                    Ok(list)
+                }
+            }
+
+            deserializer.deserialize_seq(SeqVisitor(PhantomData))
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use std::mem;
+
+    use crate::collection::error::CollectionError;
     use crate::collection::linkedlist::LinkedList;
 
     #[test]
@@ -668,13 +1810,13 @@ mod test {
         let mut list = _new_list_i32();
 
         assert_eq!(list.length, 5);
-        list.traverse();
+        assert_eq!(format!("{:?}", list), "[-1, 123, 456, 789, 2147483647]");
 
         assert_eq!(list.pop_front(), Some(-1));
         assert_eq!(list.pop_back(), Some(i32::MAX));
 
         assert_eq!(list.length, 3);
-        list.traverse();
+        assert_eq!(format!("{:?}", list), "[123, 456, 789]");
     }
 
     #[test]
@@ -699,37 +1841,71 @@ mod test {
         assert_eq!(list.peek_front(), Some(&String::from("abc ")));
         assert_eq!(list.peek_back(), Some(&String::from("hij ")));
         assert_eq!(list.length, 3);
-
-        list.traverse();
+        assert_eq!(format!("{:?}", list), r#"["abc ", "def", "hij "]"#);
     }
 
     #[test]
+    #[allow(deprecated)]
     fn test_get_idx() {
-        let list = _new_list_i32();
+        let mut list = _new_list_i32();
 
         assert_eq!(list.get_by_idx(2).unwrap(), Some(&456));
         assert_eq!(list.get_by_idx(3).unwrap(), Some(&789));
 
-        print!("before change: ");
-        list.traverse();
         let cur = list.get_by_idx_mut(2).unwrap().unwrap();
         assert_eq!(cur, &mut 456);
 
         *cur <<= 1;
-        print!("after change: ");
-        list.traverse();
+        assert_eq!(format!("{:?}", list), "[-1, 123, 912, 789, 2147483647]");
 
         assert_eq!(list.get_by_idx(2).unwrap(), Some(&(456 << 1)));
     }
 
     #[test]
+    #[allow(deprecated)]
     fn test_get_idx_err() {
-        let list = _new_list_i32();
+        let mut list = _new_list_i32();
 
         assert!(list.get_by_idx(99).is_err());
         assert!(list.get_by_idx_mut(99).is_err());
     }
 
+    #[test]
+    fn test_get_at() {
+        let mut list = _new_list_i32();
+
+        assert_eq!(list.get_at(0), Some(&-1));
+        assert_eq!(list.get_at(3), Some(&789));
+        assert_eq!(list.get_at(5), None);
+
+        *list.get_at_mut(4).unwrap() = 0;
+        assert_eq!(list.peek_back(), Some(&0));
+        assert_eq!(list.get_at_mut(5), None);
+    }
+
+    #[test]
+    fn test_index_error_context() {
+        let mut list = _new_list_i32();
+
+        let err = list.remove_by_idx(7).unwrap_err();
+        assert!(matches!(
+            err,
+            CollectionError::IndexOutOfRange { index: 7, len: 5 }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "index out of range: the len is 5 but the index is 7"
+        );
+        assert!(matches!(
+            list.insert_by_idx(6, 0),
+            Err(CollectionError::IndexOutOfRange { index: 6, len: 5 })
+        ));
+        assert!(matches!(
+            list.split_off(6),
+            Err(CollectionError::IndexOutOfRange { index: 6, len: 5 })
+        ));
+    }
+
     #[test]
     fn test_insert_idx() {
         let mut list = LinkedList::new();
@@ -739,10 +1915,10 @@ mod test {
         list.push_back(String::from("3"));
 
         list.insert_by_idx(1, String::from("99")).unwrap();
-        list.traverse();
+        assert_eq!(format!("{:?}", list), r#"["1", "99", "2", "3"]"#);
 
-        assert_eq!(list.get_by_idx(0).unwrap(), Some(&String::from("1")));
-        assert_eq!(list.get_by_idx(1).unwrap(), Some(&String::from("99")));
+        assert_eq!(list.get_at(0), Some(&String::from("1")));
+        assert_eq!(list.get_at(1), Some(&String::from("99")));
     }
 
     #[test]
@@ -761,12 +1937,12 @@ mod test {
         list.push_back(String::from("3"));
 
         let removed = list.remove_by_idx(1).unwrap();
-        list.traverse();
+        assert_eq!(format!("{:?}", list), r#"["1", "3"]"#);
 
         assert_eq!(removed, String::from("2"));
 
-        assert_eq!(list.get_by_idx(0).unwrap(), Some(&String::from("1")));
-        assert_eq!(list.get_by_idx(1).unwrap(), Some(&String::from("3")));
+        assert_eq!(list.get_at(0), Some(&String::from("1")));
+        assert_eq!(list.get_at(1), Some(&String::from("3")));
     }
 
     #[test]
@@ -778,30 +1954,111 @@ mod test {
         assert_eq!(l.remove_by_val(&removed), Some(removed));
         assert_eq!(len - 1, l.length());
         assert!(!l.contains(&"abc".to_string()));
-        l.traverse();
+        assert_eq!(format!("{:?}", l), r#"["def", "hij"]"#);
 
         let removed = "abc".to_string();
         assert_eq!(l.remove_by_val(&removed), None);
         assert_eq!(len - 1, l.length());
         assert!(!l.contains(&"abc".to_string()));
-        l.traverse();
+        assert_eq!(format!("{:?}", l), r#"["def", "hij"]"#);
     }
 
     #[test]
-    fn test_move_raw_node_to_head() {
+    fn test_move_to_front() {
         let mut l = LinkedList::new();
         l.push_back("1".to_string());
-        l.push_back("2".to_string());
+        let node = l.push_back_handle("2".to_string());
         l.push_back("3".to_string());
-        l.traverse();
+        assert_eq!(format!("{:?}", l), r#"["1", "2", "3"]"#);
         assert_eq!(l.length(), 3);
         assert_eq!(l.peek_front().unwrap(), &"1".to_string());
 
-        let node = l._get_raw_by_val(&"2".to_string()).unwrap();
-        l.move_raw_node_to_head(node);
-        l.traverse();
+        l.move_to_front(node).unwrap();
+        assert_eq!(format!("{:?}", l), r#"["2", "1", "3"]"#);
         assert_eq!(l.length(), 3);
         assert_eq!(l.peek_front().unwrap(), &"2".to_string());
+
+        l.move_to_back(node).unwrap();
+        assert_eq!(l.peek_back().unwrap(), &"2".to_string());
+        assert_eq!(
+            l.iter().rev().cloned().collect::<Vec<_>>(),
+            vec!["2", "3", "1"]
+        );
+    }
+
+    #[test]
+    fn test_handle() {
+        let mut l = LinkedList::new();
+        let a = l.push_front_handle(1);
+        let b = l.push_back_handle(2);
+        let c = l.push_back_handle(3);
+        l.push_front(0);
+
+        assert_eq!(l.get(b), Some(&2));
+        *l.get_mut(b).unwrap() = 20;
+        assert_eq!(l.remove(b), Some(20));
+        assert_eq!(l.remove(b), None);
+        assert_eq!(l.get(b), None);
+        assert!(matches!(
+            l.move_to_front(b),
+            Err(CollectionError::KeyNotFound)
+        ));
+        assert!(!l.contains_handle(b));
+        assert_eq!(l.iter().copied().collect::<Vec<_>>(), vec![0, 1, 3]);
+
+        // Removing the element in any other way invalidates the handle.
+        assert_eq!(l.pop_back(), Some(3));
+        assert_eq!(l.get(c), None);
+        assert_eq!(l.remove_by_idx(1).unwrap(), 1);
+        assert_eq!(l.get(a), None);
+        assert_eq!(l.length(), 1);
+
+        // A handle is rejected by other lists.
+        let mut other = LinkedList::new();
+        let d = other.push_front_handle(0);
+        assert_eq!(l.get(d), None);
+        assert_eq!(l.remove(d), None);
+        assert_eq!(other.get(d), Some(&0));
+    }
+
+    #[test]
+    fn test_handle_follows_split_and_splice() {
+        let mut l = LinkedList::new();
+        let handles: Vec<_> = (0..6).map(|x| l.push_back_handle(x)).collect();
+
+        let mut cursor = l.cursor_front_mut();
+        cursor.move_next();
+        let mut tail = cursor.split_after();
+        assert_eq!(l.get(handles[1]), Some(&1));
+        assert_eq!(l.get(handles[2]), None);
+        assert_eq!(tail.get(handles[2]), Some(&2));
+        tail.move_to_front(handles[5]).unwrap();
+
+        let mut cursor = tail.cursor_back_mut();
+        cursor.splice_after(mem::take(&mut l));
+        assert_eq!(l.get(handles[0]), None);
+        assert_eq!(tail.remove(handles[0]), Some(0));
+        assert_eq!(tail.remove(handles[4]), Some(4));
+        assert_eq!(tail.iter().copied().collect::<Vec<_>>(), vec![5, 2, 3, 1]);
+
+        // Either part can be the shorter one, on either side of the cursor.
+        let mut l = LinkedList::new();
+        let handles: Vec<_> = (0..6).map(|x| l.push_back_handle(x)).collect();
+        let mut cursor = l.cursor_back_mut();
+        cursor.move_prev();
+        let mut last = cursor.split_after();
+        let mut cursor = l.cursor_back_mut();
+        let mut first = cursor.split_before();
+        assert_eq!(first.length(), 4);
+        assert_eq!((l.length(), last.length()), (1, 1));
+        assert!((0..4).all(|i| first.contains_handle(handles[i])));
+        assert!(l.contains_handle(handles[4]) && !first.contains_handle(handles[4]));
+        assert_eq!(last.remove(handles[5]), Some(5));
+
+        last.append(&mut first);
+        assert_eq!(first.length(), 0);
+        assert_eq!(last.get(handles[3]), Some(&3));
+        assert_eq!(first.get(handles[3]), None);
     }
 
     #[test]
@@ -834,21 +2091,350 @@ mod test {
     fn test_iterator() {
         let mut list1 = _new_list_i32();
 
-        print!("before change: ");
-        list1.traverse();
         list1.iter_mut().for_each(|x| *x -= 1);
-        print!("after change: ");
-        list1.traverse();
+        assert_eq!(format!("{:?}", list1), "[-2, 122, 455, 788, 2147483646]");
 
         let list2 = _new_list_string();
         let list2_to_len = list2.into_iter().map(|x| x.len()).collect::<Vec<usize>>();
-        println!(
-            "transform list2 into len vec, list2_to_len: {:?}",
-            list2_to_len
-        );
+        assert_eq!(list2_to_len, vec![3, 3, 3]);
 
         // Compiling err:
-        // list2.traverse()
+        // println!("{:?}", list2)
+    }
+
+    #[test]
+    fn test_cursor() {
+        let list = _new_list_i32();
+
+        let mut cursor = list.cursor_front();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.current(), Some(&-1));
+        assert_eq!(cursor.peek_prev(), None);
+        assert_eq!(cursor.peek_next(), Some(&123));
+
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&456));
+
+        let mut cursor = list.cursor_back();
+        assert_eq!(cursor.index(), Some(4));
+        cursor.move_next();
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some(&-1));
+        assert_eq!(cursor.peek_prev(), Some(&i32::MAX));
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(4));
+
+        let forward: Vec<_> = (0..5)
+            .scan(list.cursor_front(), |c, _| {
+                let x = c.current().copied();
+                c.move_next();
+                x
+            })
+            .collect();
+        assert_eq!(forward, list.iter().copied().collect::<Vec<_>>());
+
+        let empty: LinkedList<i32> = LinkedList::new();
+        let mut cursor = empty.cursor_front();
+        assert_eq!(cursor.index(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.current(), None);
+    }
+
+    #[test]
+    fn test_cursor_mut_insert_and_remove() {
+        let mut list: LinkedList<i32> = LinkedList::new();
+        let mut cursor = list.cursor_front_mut();
+        cursor.insert_after(3);
+        cursor.insert_before(4);
+        assert_eq!(cursor.index(), None);
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 3));
+
+        cursor.insert_before(1);
+        cursor.insert_after(2);
+        assert_eq!(cursor.index(), Some(1));
+        if let Some(x) = cursor.peek_next() {
+            *x *= 10;
+        }
+        // 1, 3, 20, 4
+        assert_eq!(cursor.remove_current(), Some(3));
+        assert_eq!(cursor.current(), Some(&mut 20));
+        assert_eq!(cursor.index(), Some(1));
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(4));
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.remove_current(), None);
+        assert_eq!(cursor.as_cursor().peek_prev(), Some(&20));
+
+        assert_eq!(list.length(), 2);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 20]);
+        assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), vec![20, 1]);
+    }
+
+    #[test]
+    fn test_cursor_mut_split_and_splice() {
+        let mut list: LinkedList<i32> = LinkedList::new();
+        (0..6).for_each(|x| list.push_back(x));
+
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.move_next();
+        let mut tail = cursor.split_after();
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.peek_next(), None);
+
+        let mut head = cursor.split_before();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.current(), Some(&mut 2));
+        assert_eq!(head.iter().copied().collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(tail.iter().copied().collect::<Vec<_>>(), vec![3, 4, 5]);
+        assert_eq!((head.length(), tail.length()), (2, 3));
+
+        // Splice them back the other way around: 3, 4, 5, 2, 0, 1
+        cursor.splice_before(mem::take(&mut tail));
+        assert_eq!(cursor.index(), Some(3));
+        cursor.splice_after(mem::take(&mut head));
+        cursor.splice_after(LinkedList::new());
+        assert_eq!(cursor.index(), Some(3));
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&mut 5));
+
+        assert_eq!(list.length(), 6);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![3, 4, 5, 2, 0, 1]
+        );
+        assert_eq!(
+            list.iter().rev().copied().collect::<Vec<_>>(),
+            vec![1, 0, 2, 5, 4, 3]
+        );
+        assert!(head.peek_front().is_none() && tail.peek_back().is_none());
+
+        // At the ghost, the whole list is split off.
+        let mut cursor = list.cursor_back_mut();
+        cursor.move_next();
+        let all = cursor.split_after();
+        assert_eq!(cursor.index(), None);
+        assert_eq!(all.length(), 6);
+        let mut cursor = list.cursor_front_mut();
+        cursor.splice_before(all);
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&mut 1));
+        assert_eq!(cursor.index(), Some(5));
+        assert_eq!(list.length(), 6);
+    }
+
+    #[test]
+    fn test_append_and_split_off() {
+        let mut a: LinkedList<i32> = LinkedList::new();
+        let mut b: LinkedList<i32> = LinkedList::new();
+        (0..3).for_each(|x| a.push_back(x));
+        let h = b.push_back_handle(3);
+        b.push_back(4);
+
+        a.append(&mut b);
+        assert_eq!(b.length(), 0);
+        assert_eq!(b.peek_front(), None);
+        assert_eq!(a.get(h), Some(&3));
+        assert_eq!(
+            a.iter().rev().copied().collect::<Vec<_>>(),
+            vec![4, 3, 2, 1, 0]
+        );
+
+        b.append(&mut a);
+        assert_eq!((a.length(), b.length()), (0, 5));
+
+        assert!(b.split_off(6).is_err());
+        let mut tail = b.split_off(3).unwrap();
+        assert_eq!(b.iter().copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(tail.iter().copied().collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!(b.get(h), None);
+        assert_eq!(tail.remove(h), Some(3));
+
+        let all = b.split_off(0).unwrap();
+        assert_eq!((b.length(), all.length()), (0, 3));
+        assert_eq!(tail.split_off(1).unwrap().length(), 0);
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut list = _new_list_i32();
+        let mut expected: Vec<_> = list.iter().copied().collect();
+        list.reverse();
+        expected.reverse();
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), expected);
+        assert_eq!(
+            list.iter().rev().copied().collect::<Vec<_>>(),
+            expected.iter().rev().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(list.peek_front(), Some(&i32::MAX));
+
+        let mut empty: LinkedList<i32> = LinkedList::new();
+        empty.reverse();
+        assert_eq!(empty.peek_back(), None);
+    }
+
+    #[test]
+    fn test_sort_by() {
+        use crate::collection::test_util::seeded_rng;
+        use rand::Rng;
+
+        let mut rng = seeded_rng();
+        for len in [0, 1, 2, 3, 10, 333] {
+            let mut list = LinkedList::new();
+            let mut v = Vec::new();
+            for i in 0..len {
+                let x = (rng.gen_range(0..20), i);
+                list.push_back(x);
+                v.push(x);
+            }
+            let handle = list.push_front_handle((10, len));
+            v.insert(0, (10, len));
+
+            // Only compare the keys, to check for stability.
+            list.sort_by(|a, b| a.0.cmp(&b.0));
+            v.sort_by_key(|a| a.0);
+            assert_eq!(list.iter().copied().collect::<Vec<_>>(), v);
+            assert!(list.iter().rev().eq(v.iter().rev()));
+            assert_eq!(list.get(handle), Some(&(10, len)));
+        }
+
+        let mut list = _new_list_i32();
+        list.sort();
+        assert_eq!(
+            list.into_iter().collect::<Vec<_>>(),
+            vec![-1, 123, 456, 789, i32::MAX]
+        );
+    }
+
+    #[test]
+    fn test_dedup() {
+        let mut list = LinkedList::new();
+        for x in vec![1, 1, 2, 3, 3, 3, 1, 4, 4] {
+            list.push_back(x);
+        }
+        list.dedup();
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3, 1, 4]
+        );
+        assert_eq!(list.peek_back(), Some(&4));
+
+        // Elements within 10 of the previous kept one are removed.
+        let mut list = LinkedList::new();
+        for x in [1, 5, 12, 20, 21, 35] {
+            list.push_back(x);
+        }
+        list.dedup_by(|a, b| *a - *b < 10);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 12, 35]);
+        assert_eq!(list.length(), 3);
+    }
+
+    #[test]
+    fn test_retain_and_drain_filter() {
+        let mut list = LinkedList::new();
+        (0..10).for_each(|x| list.push_back(x));
+
+        list.retain(|x| x % 3 != 0);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![1, 2, 4, 5, 7, 8]
+        );
+
+        let mut drain = list.drain_filter(|x| {
+            *x *= 10;
+            *x % 20 == 0
+        });
+        assert_eq!(drain.next(), Some(20));
+        drop(drain);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![10, 50, 70]);
+        assert_eq!(list.peek_back(), Some(&70));
+        assert_eq!(list.length(), 3);
+
+        assert_eq!(list.drain_filter(|_| true).count(), 3);
+        assert_eq!(list.peek_front(), None);
+        assert_eq!(list.peek_back(), None);
+    }
+
+    #[test]
+    fn test_std_traits() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        fn hash_of<T: Hash>(x: &T) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            x.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let a: LinkedList<i32> = (1..=3).collect();
+        let mut b = a.clone();
+        assert_eq!(a, b);
+        assert_eq!(hash_of(&a), hash_of(&b));
+        assert_eq!(format!("{:?}", a), "[1, 2, 3]");
+
+        b.extend(&[4]);
+        assert_ne!(a, b);
+        assert!(a < b);
+        assert_eq!(b.iter().len(), 4);
+        assert_eq!(b.clone().into_iter().len(), 4);
+        for x in &mut b {
+            *x *= 2;
+        }
+        assert_eq!(
+            (&b).into_iter().copied().collect::<Vec<_>>(),
+            vec![2, 4, 6, 8]
+        );
+
+        let h = b.push_back_handle(10);
+        b[h] += 1;
+        assert_eq!(b[h], 11);
+        // The clone has no handles.
+        assert_eq!(b.clone().get(h), None);
+
+        let mut it = a.iter();
+        it.by_ref().for_each(drop);
+        assert_eq!(it.next(), None);
+        assert_eq!(it.next_back(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid node handle")]
+    fn test_index_invalid_handle() {
+        let mut a: LinkedList<i32> = LinkedList::new();
+        let h = a.push_front_handle(1);
+        a.pop_front();
+        let _ = a[h];
+    }
+
+    #[test]
+    fn test_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<LinkedList<String>>();
+        assert_send_sync::<crate::collection::linkedlist::Iter<'_, String>>();
+        assert_send_sync::<crate::collection::linkedlist::CursorMut<'_, String>>();
+
+        let list: LinkedList<i32> = (0..100).collect();
+        let sum = std::thread::scope(|s| s.spawn(|| list.iter().sum::<i32>()).join().unwrap());
+        assert_eq!(sum, 4950);
+        let list = std::thread::spawn(move || list).join().unwrap();
+        assert_eq!(list.length(), 100);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let list = _new_list_string();
+        let json = serde_json::to_string(&list).unwrap();
+        assert_eq!(json, r#"["abc","def","hij"]"#);
+
+        let de: LinkedList<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(de, list);
+        assert_eq!(de.peek_back(), Some(&String::from("hij")));
+        assert!(serde_json::from_str::<LinkedList<i32>>(r#"[1,"2"]"#).is_err());
     }
 
     struct ZeroSizeType {}
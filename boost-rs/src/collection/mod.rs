@@ -23,16 +23,67 @@
 //! Note that rust macros are flexible in which brackets you use for the invocation.
 //! You can use them as `hashmap!{}` or `hashmap![]` or `hashmap!()`.
 
+use std::ops::{Bound, Range, RangeBounds};
+
+use crate::collection::error::CollectionError;
+
+pub mod bimap;
+pub mod bitset;
 pub mod bloom_filter;
 pub mod bst;
 pub mod btreemap;
 pub mod btreeset;
 pub mod cache;
+pub mod disjoint_set;
 pub mod error;
+pub mod fenwick_tree;
+pub mod hash_ring;
 pub mod hashmap;
 pub mod hashset;
+pub mod heap;
+pub mod interval_tree;
 pub mod linkedlist;
+pub mod multimap;
+pub mod ring_buffer;
+pub mod segment_tree;
+pub mod set_ops;
 pub mod skiplist;
+#[cfg(test)]
+pub(crate) mod test_util;
+pub mod trie;
+
+/// Converts a range of indices into a `Range` within `0..len`.
+pub(crate) fn _index_range<R: RangeBounds<usize>>(
+    range: R,
+    len: usize,
+) -> Result<Range<usize>, CollectionError> {
+    // An excluded start or an included end of `usize::MAX` is past any
+    // length, so the overflow is reported as out of range.
+    let out_of_range = || CollectionError::IndexOutOfRange {
+        index: usize::MAX,
+        len,
+    };
+    let start = match range.start_bound() {
+        Bound::Included(&s) => s,
+        Bound::Excluded(&s) => s.checked_add(1).ok_or_else(out_of_range)?,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&e) => e.checked_add(1).ok_or_else(out_of_range)?,
+        Bound::Excluded(&e) => e,
+        Bound::Unbounded => len,
+    };
+    if end > len {
+        return Err(CollectionError::IndexOutOfRange { index: end, len });
+    }
+    if start > end {
+        return Err(CollectionError::InvalidParameter(format!(
+            "range starts at {} but ends at {}",
+            start, end
+        )));
+    }
+    Ok(start..end)
+}
 
 /// Identity function. Used as the fallback for conversion.
 #[doc(hidden)]
@@ -0,0 +1,330 @@
+//! A hash map from every key to one or more values.
+//!
+//! The values of a key are kept in a `Vec` in the order they are inserted.
+//!
+//! Wikipedia:
+//!  - https://en.wikipedia.org/wiki/Multimap
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::hash::Hash;
+use std::iter::FromIterator;
+
+/// Create a **MultiMap** from a list of key-value pairs, a repeated key gets
+/// all of its values
+///
+/// ## Example
+///
+/// ```
+/// # fn main() {
+/// use boost_rs::multimap;
+/// let tags = multimap!{
+///     "rust" => "fast",
+///     "rust" => "safe",
+///     "go" => "simple",
+/// };
+/// assert_eq!(tags.get_all("rust"), &["fast", "safe"]);
+/// assert_eq!(tags.get("go"), Some(&"simple"));
+/// assert_eq!(tags.length(), 3);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! multimap {
+    // trailing comma case
+    ($($key:expr => $value:expr,)+) => (multimap!($($key => $value),+));
+
+    ( $($key:expr => $value:expr),* ) => {
+        {
+            let mut _map = $crate::collection::multimap::MultiMap::new();
+            $(
+                _map.insert($key, $value);
+            )*
+            _map
+        }
+    };
+}
+
+/// A hash map from every key to a non-empty `Vec` of values, in the order
+/// they are inserted.
+#[derive(Clone)]
+pub struct MultiMap<K, V> {
+    map: HashMap<K, Vec<V>>,
+    // The number of the values of all keys.
+    len: usize,
+}
+
+impl<K, V> Default for MultiMap<K, V> {
+    fn default() -> Self {
+        MultiMap {
+            map: HashMap::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq, V> MultiMap<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty map with space for `capacity` keys.
+    pub fn with_capacity(capacity: usize) -> Self {
+        MultiMap {
+            map: HashMap::with_capacity(capacity),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of the values of all keys.
+    #[inline]
+    pub fn length(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the number of distinct keys.
+    #[inline]
+    pub fn key_count(&self) -> usize {
+        self.map.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.len = 0;
+    }
+
+    /// Appends a value to the values of the key.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.map.entry(key).or_default().push(value);
+        self.len += 1;
+    }
+
+    /// Returns the first value of the key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get(key).and_then(|values| values.first())
+    }
+
+    /// Returns all values of the key, which is empty if the key is absent.
+    pub fn get_all<Q>(&self, key: &Q) -> &[V]
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns all values of the key as a mutable slice.
+    pub fn get_all_mut<Q>(&mut self, key: &Q) -> Option<&mut [V]>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get_mut(key).map(Vec::as_mut_slice)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Removes the key, and returns all of its values.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<Vec<V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let values = self.map.remove(key)?;
+        self.len -= values.len();
+        Some(values)
+    }
+
+    /// Removes the values of the key for which `f` returns `false`, and the
+    /// key if no value is left.
+    pub fn retain<F: FnMut(&K, &V) -> bool>(&mut self, mut f: F) {
+        let mut len = 0;
+        self.map.retain(|key, values| {
+            values.retain(|value| f(key, value));
+            len += values.len();
+            !values.is_empty()
+        });
+        self.len = len;
+    }
+}
+
+impl<K, V> MultiMap<K, V> {
+    /// Gets an iterator over every key and each of its values, the keys are
+    /// in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        self.map
+            .iter()
+            .flat_map(|(key, values)| values.iter().map(move |value| (key, value)))
+    }
+
+    /// Gets an iterator over every key and all of its values.
+    pub fn iter_all(&self) -> impl Iterator<Item = (&K, &[V])> + '_ {
+        self.map
+            .iter()
+            .map(|(key, values)| (key, values.as_slice()))
+    }
+
+    /// Gets an iterator over the distinct keys.
+    pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+        self.map.keys()
+    }
+}
+
+impl<K: Hash + Eq, V: PartialEq> PartialEq for MultiMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<K: Hash + Eq, V: Eq> Eq for MultiMap<K, V> {}
+
+impl<K: Debug, V: Debug> Debug for MultiMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.map.iter()).finish()
+    }
+}
+
+impl<K: Hash + Eq, V> Extend<(K, V)> for MultiMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Hash + Eq, V> FromIterator<(K, V)> for MultiMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = MultiMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::collection::multimap::MultiMap;
+
+    impl<K: Serialize, V: Serialize> Serialize for MultiMap<K, V> {
+        /// Serializes a map from every key to the sequence of its values.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_map(self.map.iter())
+        }
+    }
+
+    impl<'de, K, V> Deserialize<'de> for MultiMap<K, V>
+    where
+        K: Deserialize<'de> + Hash + Eq,
+        V: Deserialize<'de>,
+    {
+        /// Deserializes a map from every key to a non-empty sequence of its
+        /// values.
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let map = HashMap::<K, Vec<V>>::deserialize(deserializer)?;
+            if map.values().any(Vec::is_empty) {
+                return Err(D::Error::custom("a key has no values"));
+            }
+            let len = map.values().map(Vec::len).sum();
+            Ok(MultiMap { map, len })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collection::multimap::MultiMap;
+    use crate::convert_args;
+
+    #[test]
+    fn test_insert_and_get() {
+        let mut m = MultiMap::new();
+        m.insert("a", 1);
+        m.insert("b", 2);
+        m.insert("a", 3);
+        assert_eq!((m.length(), m.key_count()), (3, 2));
+        assert_eq!(m.get("a"), Some(&1));
+        assert_eq!(m.get_all("a"), &[1, 3]);
+        assert!(m.get_all("c").is_empty());
+        assert!(m.contains_key("b"));
+
+        m.get_all_mut("a").unwrap()[1] = 30;
+        assert_eq!(m.get_all("a"), &[1, 30]);
+        assert!(m.get_all_mut("c").is_none());
+
+        let mut pairs: Vec<_> = m.iter().map(|(k, v)| (*k, *v)).collect();
+        pairs.sort_unstable();
+        assert_eq!(pairs, vec![("a", 1), ("a", 30), ("b", 2)]);
+        assert_eq!(m.iter_all().count(), 2);
+
+        assert_eq!(m.remove("a"), Some(vec![1, 30]));
+        assert_eq!(m.remove("a"), None);
+        assert_eq!((m.length(), m.key_count()), (1, 1));
+        m.clear();
+        assert!(m.is_empty());
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut m: MultiMap<_, _> = (0..10).map(|i| (i % 3, i)).collect();
+        m.retain(|k, v| *k != 1 && *v > 2);
+        assert_eq!(m.length(), 5);
+        assert_eq!(m.get_all(&0), &[3, 6, 9]);
+        assert!(!m.contains_key(&1));
+        assert_eq!(m.get_all(&2), &[5, 8]);
+    }
+
+    #[test]
+    fn test_macro() {
+        let m = multimap! {
+            1 => "one",
+            1 => "uno",
+            2 => "two",
+        };
+        assert_eq!(m.get_all(&1), &["one", "uno"]);
+        assert_eq!(
+            format!("{:?}", multimap! {1 => 'a', 1 => 'b'}),
+            "{1: ['a', 'b']}"
+        );
+
+        let empty: MultiMap<i32, i32> = multimap! {};
+        assert!(empty.is_empty());
+
+        let converted: MultiMap<String, i64> = convert_args!(multimap!(
+            "one" => 1,
+            "one" => 2,
+        ));
+        assert_eq!(converted.get_all("one"), &[1, 2]);
+        assert_eq!(m, m.clone());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let m = multimap! {"a" => 1, "a" => 2};
+        let json = serde_json::to_string(&m).unwrap();
+        assert_eq!(json, r#"{"a":[1,2]}"#);
+        let back: MultiMap<String, i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.length(), 2);
+        assert!(serde_json::from_str::<MultiMap<String, i32>>(r#"{"a":[]}"#).is_err());
+    }
+}
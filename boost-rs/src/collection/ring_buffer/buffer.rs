@@ -0,0 +1,583 @@
+//! Ring buffers with inline or heap-allocated storage.
+
+use std::fmt::{self, Debug};
+use std::iter::Chain;
+use std::mem::MaybeUninit;
+use std::ops::{Index, IndexMut};
+use std::{ptr, slice};
+
+use crate::collection::error::CollectionError;
+use crate::collection::ring_buffer::Overflow;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// The storage of the slots of a ring buffer.
+///
+/// It is implemented by arrays and boxed slices of `MaybeUninit<T>`, and
+/// can't be implemented outside of this crate.
+pub trait Storage<T>: private::Sealed {
+    fn slots(&self) -> &[MaybeUninit<T>];
+
+    fn slots_mut(&mut self) -> &mut [MaybeUninit<T>];
+}
+
+impl<T, const N: usize> private::Sealed for [MaybeUninit<T>; N] {}
+
+impl<T, const N: usize> Storage<T> for [MaybeUninit<T>; N] {
+    #[inline]
+    fn slots(&self) -> &[MaybeUninit<T>] {
+        self
+    }
+
+    #[inline]
+    fn slots_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        self
+    }
+}
+
+impl<T> private::Sealed for Box<[MaybeUninit<T>]> {}
+
+impl<T> Storage<T> for Box<[MaybeUninit<T>]> {
+    #[inline]
+    fn slots(&self) -> &[MaybeUninit<T>] {
+        self
+    }
+
+    #[inline]
+    fn slots_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        self
+    }
+}
+
+/// A ring buffer with a capacity of `N` elements, stored inline.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::ring_buffer::{Overflow, RingBuffer};
+///
+/// // The latest 3 samples of a metric.
+/// let mut window: RingBuffer<u32, 3> = RingBuffer::new(Overflow::Overwrite);
+/// for sample in [7, 3, 9] {
+///     window.push_back(sample).unwrap();
+/// }
+/// assert_eq!(window.push_back(4).unwrap(), Some(7));
+/// assert_eq!(window.iter().sum::<u32>(), 16);
+/// assert_eq!(window[0], 3);
+///
+/// let mut strict: RingBuffer<u32, 1> = RingBuffer::new(Overflow::Reject);
+/// strict.push_front(1).unwrap();
+/// assert!(strict.push_front(2).is_err());
+/// ```
+pub type RingBuffer<T, const N: usize> = GenericRingBuffer<T, [MaybeUninit<T>; N]>;
+
+/// A ring buffer with a capacity given at runtime, allocated once on the
+/// heap.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::ring_buffer::{HeapRingBuffer, Overflow};
+///
+/// let mut buf = HeapRingBuffer::with_capacity(4, Overflow::Overwrite);
+/// buf.extend(1..=6);
+/// assert_eq!(buf.iter().copied().collect::<Vec<_>>(), vec![3, 4, 5, 6]);
+///
+/// // The elements wrap around the end of the storage.
+/// let (front, back) = buf.as_slices();
+/// assert_eq!((front, back), (&[3, 4][..], &[5, 6][..]));
+/// ```
+pub type HeapRingBuffer<T> = GenericRingBuffer<T, Box<[MaybeUninit<T>]>>;
+
+/// A double-ended ring buffer over the slots of `S`, see [`RingBuffer`] and
+/// [`HeapRingBuffer`].
+pub struct GenericRingBuffer<T, S: Storage<T>> {
+    storage: S,
+    // The slot of the front element.
+    head: usize,
+    length: usize,
+    overflow: Overflow,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<T, const N: usize> GenericRingBuffer<T, [MaybeUninit<T>; N]> {
+    /// Creates an empty buffer with the overflow policy.
+    pub fn new(overflow: Overflow) -> Self {
+        Self::_with_storage(
+            // SAFETY: an array of `MaybeUninit` doesn't need initialization.
+            unsafe { MaybeUninit::<[MaybeUninit<T>; N]>::uninit().assume_init() },
+            overflow,
+        )
+    }
+}
+
+impl<T, const N: usize> Default for GenericRingBuffer<T, [MaybeUninit<T>; N]> {
+    /// Creates an empty buffer which overwrites when full.
+    fn default() -> Self {
+        Self::new(Overflow::default())
+    }
+}
+
+impl<T> GenericRingBuffer<T, Box<[MaybeUninit<T>]>> {
+    /// Creates an empty buffer of `capacity` elements with the overflow
+    /// policy.
+    pub fn with_capacity(capacity: usize, overflow: Overflow) -> Self {
+        let storage = std::iter::repeat_with(MaybeUninit::uninit)
+            .take(capacity)
+            .collect();
+        Self::_with_storage(storage, overflow)
+    }
+}
+
+impl<T, S: Storage<T>> GenericRingBuffer<T, S> {
+    fn _with_storage(storage: S, overflow: Overflow) -> Self {
+        GenericRingBuffer {
+            storage,
+            head: 0,
+            length: 0,
+            overflow,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the maximum number of elements.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.storage.slots().len()
+    }
+
+    /// Returns the number of elements.
+    #[inline]
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if there are no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns `true` if the number of elements reaches the capacity.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.length == self.capacity()
+    }
+
+    /// Returns the overflow policy.
+    #[inline]
+    pub fn overflow(&self) -> Overflow {
+        self.overflow
+    }
+
+    /// Removes all elements.
+    pub fn clear(&mut self) {
+        while self.pop_back().is_some() {}
+        self.head = 0;
+    }
+
+    /// Appends an element to the back.
+    ///
+    /// If the buffer is full, the front element is removed and returned with
+    /// [`Overflow::Overwrite`], or [`CollectionError::CapacityExceeded`] is
+    /// returned together with `elem` with [`Overflow::Reject`].
+    pub fn push_back(&mut self, elem: T) -> Result<Option<T>, (CollectionError, T)> {
+        let evicted = if self.is_full() {
+            if let Err(e) = self._make_room() {
+                return Err((e, elem));
+            }
+            if self.capacity() == 0 {
+                return Ok(Some(elem));
+            }
+            self.pop_front()
+        } else {
+            None
+        };
+        let slot = self._slot(self.length);
+        self.storage.slots_mut()[slot].write(elem);
+        self.length += 1;
+        Ok(evicted)
+    }
+
+    /// Prepends an element to the front.
+    ///
+    /// If the buffer is full, the back element is removed and returned with
+    /// [`Overflow::Overwrite`], or [`CollectionError::CapacityExceeded`] is
+    /// returned together with `elem` with [`Overflow::Reject`].
+    pub fn push_front(&mut self, elem: T) -> Result<Option<T>, (CollectionError, T)> {
+        let evicted = if self.is_full() {
+            if let Err(e) = self._make_room() {
+                return Err((e, elem));
+            }
+            if self.capacity() == 0 {
+                return Ok(Some(elem));
+            }
+            self.pop_back()
+        } else {
+            None
+        };
+        self.head = self._slot(self.capacity() - 1);
+        self.storage.slots_mut()[self.head].write(elem);
+        self.length += 1;
+        Ok(evicted)
+    }
+
+    /// Removes the front element and returns it.
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.length == 0 {
+            return None;
+        }
+        let head = self.head;
+        self.head = self._slot(1);
+        self.length -= 1;
+        // SAFETY: the slot was initialized and is now outside of the elements.
+        Some(unsafe { self.storage.slots()[head].assume_init_read() })
+    }
+
+    /// Removes the back element and returns it.
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.length == 0 {
+            return None;
+        }
+        self.length -= 1;
+        let slot = self._slot(self.length);
+        // SAFETY: the slot was initialized and is now outside of the elements.
+        Some(unsafe { self.storage.slots()[slot].assume_init_read() })
+    }
+
+    /// Returns the front element.
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Returns the back element.
+    pub fn back(&self) -> Option<&T> {
+        self.length.checked_sub(1).and_then(|i| self.get(i))
+    }
+
+    /// Returns the element at `index`, counted from the front.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.length {
+            return None;
+        }
+        let slot = self._slot(index);
+        // SAFETY: the slots of the elements are initialized.
+        Some(unsafe { self.storage.slots()[slot].assume_init_ref() })
+    }
+
+    /// Returns the mutable element at `index`, counted from the front.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.length {
+            return None;
+        }
+        let slot = self._slot(index);
+        // SAFETY: the slots of the elements are initialized.
+        Some(unsafe { self.storage.slots_mut()[slot].assume_init_mut() })
+    }
+
+    /// Returns the elements as two slices, which are in order when
+    /// concatenated. The second slice is empty unless the elements wrap
+    /// around the end of the storage.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (front, back) = self._ranges();
+        let slots = self.storage.slots();
+        // SAFETY: the slots of the elements are initialized, and
+        // `MaybeUninit<T>` has the same layout as `T`.
+        unsafe {
+            (
+                &*(&slots[front.0..front.1] as *const [MaybeUninit<T>] as *const [T]),
+                &*(&slots[back.0..back.1] as *const [MaybeUninit<T>] as *const [T]),
+            )
+        }
+    }
+
+    /// Returns the elements as two mutable slices, see
+    /// [`GenericRingBuffer::as_slices`].
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (front, back) = self._ranges();
+        let (wrapped, rest) = self.storage.slots_mut().split_at_mut(front.0);
+        // SAFETY: the same as `as_slices`, and the slices are disjoint.
+        unsafe {
+            (
+                &mut *(&mut rest[..front.1 - front.0] as *mut [MaybeUninit<T>] as *mut [T]),
+                &mut *(&mut wrapped[back.0..back.1] as *mut [MaybeUninit<T>] as *mut [T]),
+            )
+        }
+    }
+
+    /// Gets an iterator over the elements, from the front to the back.
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (front, back) = self.as_slices();
+        front.iter().chain(back.iter())
+    }
+
+    /// Gets a mutable iterator over the elements, from the front to the back.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let (front, back) = self.as_mut_slices();
+        front.iter_mut().chain(back.iter_mut())
+    }
+
+    /// Returns the slot of the element at `index`, which may be past the
+    /// back.
+    #[inline]
+    fn _slot(&self, index: usize) -> usize {
+        let slot = self.head + index;
+        if slot >= self.capacity() {
+            slot - self.capacity()
+        } else {
+            slot
+        }
+    }
+
+    /// Returns the slot ranges of the elements, before and after wrapping
+    /// around.
+    fn _ranges(&self) -> ((usize, usize), (usize, usize)) {
+        let tail = self.head + self.length;
+        if tail <= self.capacity() {
+            ((self.head, tail), (0, 0))
+        } else {
+            ((self.head, self.capacity()), (0, tail - self.capacity()))
+        }
+    }
+
+    fn _make_room(&self) -> Result<(), CollectionError> {
+        match self.overflow {
+            Overflow::Overwrite => Ok(()),
+            Overflow::Reject => Err(CollectionError::CapacityExceeded {
+                capacity: self.capacity(),
+            }),
+        }
+    }
+}
+
+/// An iterator over the elements of a ring buffer.
+pub type Iter<'a, T> = Chain<slice::Iter<'a, T>, slice::Iter<'a, T>>;
+
+/// A mutable iterator over the elements of a ring buffer.
+pub type IterMut<'a, T> = Chain<slice::IterMut<'a, T>, slice::IterMut<'a, T>>;
+
+impl<T, S: Storage<T>> Drop for GenericRingBuffer<T, S> {
+    fn drop(&mut self) {
+        let (front, back) = self.as_mut_slices();
+        // SAFETY: the elements are initialized, and never used again.
+        unsafe {
+            ptr::drop_in_place(front);
+            ptr::drop_in_place(back);
+        }
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for GenericRingBuffer<T, [MaybeUninit<T>; N]> {
+    fn clone(&self) -> Self {
+        let mut buf = Self::new(self.overflow);
+        buf.extend(self.iter().cloned());
+        buf
+    }
+}
+
+impl<T: Clone> Clone for GenericRingBuffer<T, Box<[MaybeUninit<T>]>> {
+    fn clone(&self) -> Self {
+        let mut buf = Self::with_capacity(self.capacity(), self.overflow);
+        buf.extend(self.iter().cloned());
+        buf
+    }
+}
+
+impl<T: Debug, S: Storage<T>> Debug for GenericRingBuffer<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, S: Storage<T>, S2: Storage<T>> PartialEq<GenericRingBuffer<T, S2>>
+    for GenericRingBuffer<T, S>
+{
+    /// Compares the elements, regardless of the capacities and the policies.
+    fn eq(&self, other: &GenericRingBuffer<T, S2>) -> bool {
+        self.length == other.length && self.iter().eq(other.iter())
+    }
+}
+
+impl<T, S: Storage<T>> Index<usize> for GenericRingBuffer<T, S> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        let len = self.length;
+        self.get(index)
+            .unwrap_or_else(|| panic!("{}", CollectionError::IndexOutOfRange { index, len }))
+    }
+}
+
+impl<T, S: Storage<T>> IndexMut<usize> for GenericRingBuffer<T, S> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        let len = self.length;
+        self.get_mut(index)
+            .unwrap_or_else(|| panic!("{}", CollectionError::IndexOutOfRange { index, len }))
+    }
+}
+
+impl<T, S: Storage<T>> Extend<T> for GenericRingBuffer<T, S> {
+    /// Pushes the elements to the back, the rejected elements are dropped.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            let _ = self.push_back(elem);
+        }
+    }
+}
+
+impl<'a, T, S: Storage<T>> IntoIterator for &'a GenericRingBuffer<T, S> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, S: Storage<T>> IntoIterator for &'a mut GenericRingBuffer<T, S> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+
+    use crate::collection::error::CollectionError;
+    use crate::collection::ring_buffer::{HeapRingBuffer, Overflow, RingBuffer};
+
+    #[test]
+    fn test_push_and_pop() {
+        let mut buf: RingBuffer<i32, 4> = RingBuffer::default();
+        assert_eq!(buf.capacity(), 4);
+        assert_eq!(buf.overflow(), Overflow::Overwrite);
+        buf.push_back(1).unwrap();
+        buf.push_back(2).unwrap();
+        buf.push_front(0).unwrap();
+        assert_eq!(buf.front(), Some(&0));
+        assert_eq!(buf.back(), Some(&2));
+        assert_eq!(buf.as_slices(), (&[0][..], &[1, 2][..]));
+
+        buf.push_back(3).unwrap();
+        assert!(buf.is_full());
+        assert_eq!(buf.push_back(4).unwrap(), Some(0));
+        assert_eq!(buf.push_front(-1).unwrap(), Some(4));
+        assert_eq!(buf.iter().copied().collect::<Vec<_>>(), vec![-1, 1, 2, 3]);
+
+        buf[1] = 10;
+        *buf.get_mut(3).unwrap() += 1;
+        assert_eq!(buf.pop_front(), Some(-1));
+        assert_eq!(buf.pop_back(), Some(4));
+        assert_eq!(buf.get(1), Some(&2));
+        assert_eq!(buf.get(2), None);
+        assert_eq!(format!("{:?}", buf), "[10, 2]");
+
+        buf.clear();
+        assert!(buf.is_empty());
+        assert_eq!(buf.pop_back(), None);
+        assert_eq!(buf.back(), None);
+    }
+
+    #[test]
+    fn test_reject() {
+        let mut buf = HeapRingBuffer::with_capacity(2, Overflow::Reject);
+        buf.push_back("a").unwrap();
+        buf.push_front("b").unwrap();
+        assert!(matches!(
+            buf.push_back("c"),
+            Err((CollectionError::CapacityExceeded { capacity: 2 }, "c"))
+        ));
+        assert!(matches!(buf.push_front("c"), Err((_, "c"))));
+        buf.extend(vec!["d", "e"]);
+        assert_eq!(buf.iter().copied().collect::<Vec<_>>(), vec!["b", "a"]);
+
+        let mut empty: RingBuffer<u8, 0> = RingBuffer::new(Overflow::Overwrite);
+        assert_eq!(empty.push_back(1).unwrap(), Some(1));
+        assert!(empty.is_empty());
+        let mut empty = HeapRingBuffer::with_capacity(0, Overflow::Reject);
+        assert!(matches!(
+            empty.push_front(1),
+            Err((CollectionError::CapacityExceeded { capacity: 0 }, 1))
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of range: the len is 1 but the index is 1")]
+    fn test_index_out_of_range() {
+        let mut buf: RingBuffer<u8, 2> = RingBuffer::default();
+        buf.push_back(1).unwrap();
+        let _ = buf[1];
+    }
+
+    #[test]
+    fn test_drop_and_clone() {
+        let rc = Rc::new(());
+        let mut buf = HeapRingBuffer::with_capacity(3, Overflow::Overwrite);
+        for _ in 0..5 {
+            buf.push_back(Rc::clone(&rc)).unwrap();
+        }
+        assert_eq!(Rc::strong_count(&rc), 4);
+
+        let copy = buf.clone();
+        assert_eq!(Rc::strong_count(&rc), 7);
+        assert_eq!(copy.capacity(), 3);
+        assert_eq!(copy, buf);
+        drop(copy);
+        drop(buf);
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        let mut arr: RingBuffer<Rc<()>, 2> = RingBuffer::default();
+        arr.push_front(Rc::clone(&rc)).unwrap();
+        arr.push_front(Rc::clone(&rc)).unwrap();
+        let copy = arr.clone();
+        drop(arr);
+        drop(copy);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn test_random_consistency() {
+        use crate::collection::test_util::random_ops;
+        use rand::Rng;
+
+        let mut buf: RingBuffer<usize, 7> = RingBuffer::new(Overflow::Overwrite);
+        let mut deque = VecDeque::new();
+        random_ops(5000, |rng, i| {
+            match rng.gen_range(0..4) {
+                0 => {
+                    let evicted = if deque.len() == 7 {
+                        deque.pop_front()
+                    } else {
+                        None
+                    };
+                    deque.push_back(i);
+                    assert_eq!(buf.push_back(i).unwrap(), evicted);
+                }
+                1 => {
+                    let evicted = if deque.len() == 7 {
+                        deque.pop_back()
+                    } else {
+                        None
+                    };
+                    deque.push_front(i);
+                    assert_eq!(buf.push_front(i).unwrap(), evicted);
+                }
+                2 => assert_eq!(buf.pop_front(), deque.pop_front()),
+                _ => assert_eq!(buf.pop_back(), deque.pop_back()),
+            }
+            for x in buf.iter_mut() {
+                *x += 1;
+            }
+            deque.iter_mut().for_each(|x| *x += 1);
+            assert!(buf.iter().eq(deque.iter()));
+            assert_eq!(buf.length(), deque.len());
+        });
+    }
+}
@@ -0,0 +1,32 @@
+//! Fixed-capacity ring buffers, which never allocate after creation.
+//!
+//! - [`RingBuffer`] stores its elements inline, with a capacity known at
+//!   compile time.
+//! - [`HeapRingBuffer`] allocates its storage once, with a capacity given at
+//!   runtime.
+//! - [`spsc`] is a lock-free ring buffer shared between a single producer and
+//!   a single consumer thread.
+//!
+//! When [`RingBuffer`] or [`HeapRingBuffer`] is full, a push either
+//! overwrites the element at the other end or is rejected, as chosen by the
+//! [`Overflow`] policy of the buffer.
+//!
+//! Wikipedia:
+//!  - https://en.wikipedia.org/wiki/Circular_buffer
+
+pub use self::buffer::{GenericRingBuffer, HeapRingBuffer, Iter, IterMut, RingBuffer, Storage};
+pub use self::spsc::{spsc, Consumer, Producer};
+
+pub mod buffer;
+pub mod spsc;
+
+/// What a push does when the buffer is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Overflow {
+    /// Drops the element at the other end to make room, that is the oldest
+    /// element for `push_back`.
+    #[default]
+    Overwrite,
+    /// Rejects the new element.
+    Reject,
+}
@@ -0,0 +1,295 @@
+//! A lock-free ring buffer for a single producer and a single consumer.
+//!
+//! The producer only writes the tail and the consumer only writes the head,
+//! so every push and pop is a few atomic loads and one atomic store, without
+//! locks or compare-and-swap loops.
+
+use std::cell::UnsafeCell;
+use std::fmt::{self, Debug};
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Keeps the head and the tail on separate cache lines, so that the producer
+/// and the consumer don't invalidate each other's cache line on every store.
+#[repr(align(64))]
+struct CachePadded<T>(T);
+
+struct Shared<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    // The head and the tail count in `0..2 * capacity`, so that a full
+    // buffer and an empty one are told apart.
+    head: CachePadded<AtomicUsize>,
+    tail: CachePadded<AtomicUsize>,
+}
+
+// SAFETY: a slot is only accessed by the producer before it is published by
+// the tail, and only by the consumer after that, until it is released by the
+// head.
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+impl<T> Shared<T> {
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    #[inline]
+    fn slot(&self, pos: usize) -> &UnsafeCell<MaybeUninit<T>> {
+        let cap = self.capacity();
+        &self.slots[if pos >= cap { pos - cap } else { pos }]
+    }
+
+    #[inline]
+    fn next(&self, pos: usize) -> usize {
+        if pos + 1 == 2 * self.capacity() {
+            0
+        } else {
+            pos + 1
+        }
+    }
+
+    #[inline]
+    fn distance(&self, head: usize, tail: usize) -> usize {
+        if tail >= head {
+            tail - head
+        } else {
+            tail + 2 * self.capacity() - head
+        }
+    }
+
+    fn length(&self) -> usize {
+        let head = self.head.0.load(Ordering::Acquire);
+        let tail = self.tail.0.load(Ordering::Acquire);
+        self.distance(head, tail)
+    }
+}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        let (mut head, tail) = (*self.head.0.get_mut(), *self.tail.0.get_mut());
+        while head != tail {
+            // SAFETY: the slots between the head and the tail are initialized.
+            unsafe { (*self.slot(head).get()).assume_init_drop() };
+            head = self.next(head);
+        }
+    }
+}
+
+/// Creates a ring buffer of `capacity` elements, and returns its producer
+/// and consumer halves.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+///
+/// use boost_rs::collection::ring_buffer::spsc;
+///
+/// let (mut producer, mut consumer) = spsc(16);
+/// let sampler = thread::spawn(move || {
+///     for sample in 0..100u32 {
+///         let mut sample = sample;
+///         // Retry until the consumer makes room.
+///         while let Err(rejected) = producer.push(sample) {
+///             sample = rejected;
+///             thread::yield_now();
+///         }
+///     }
+/// });
+///
+/// let mut received = Vec::new();
+/// while received.len() < 100 {
+///     match consumer.pop() {
+///         Some(sample) => received.push(sample),
+///         None => thread::yield_now(),
+///     }
+/// }
+/// sampler.join().unwrap();
+/// assert_eq!(received, (0..100).collect::<Vec<_>>());
+/// ```
+pub fn spsc<T>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+    let shared = Arc::new(Shared {
+        slots: std::iter::repeat_with(|| UnsafeCell::new(MaybeUninit::uninit()))
+            .take(capacity)
+            .collect(),
+        head: CachePadded(AtomicUsize::new(0)),
+        tail: CachePadded(AtomicUsize::new(0)),
+    });
+    (
+        Producer {
+            shared: Arc::clone(&shared),
+        },
+        Consumer { shared },
+    )
+}
+
+/// The pushing half of a [`spsc`] ring buffer.
+pub struct Producer<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Producer<T> {
+    /// Appends an element to the back, or returns it back if the buffer is
+    /// full.
+    pub fn push(&mut self, elem: T) -> Result<(), T> {
+        let shared = &*self.shared;
+        let tail = shared.tail.0.load(Ordering::Relaxed);
+        let head = shared.head.0.load(Ordering::Acquire);
+        if shared.distance(head, tail) == shared.capacity() {
+            return Err(elem);
+        }
+        // SAFETY: the slot is released by the consumer, and not published yet.
+        unsafe { (*shared.slot(tail).get()).write(elem) };
+        shared.tail.0.store(shared.next(tail), Ordering::Release);
+        Ok(())
+    }
+
+    /// Returns the maximum number of elements.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity()
+    }
+
+    /// Returns the number of elements, which may be outdated by the time it
+    /// returns.
+    pub fn length(&self) -> usize {
+        self.shared.length()
+    }
+
+    /// Returns `true` if the buffer is full, the consumer may make room at any
+    /// time.
+    pub fn is_full(&self) -> bool {
+        self.length() == self.capacity()
+    }
+}
+
+impl<T> Debug for Producer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Producer")
+            .field("capacity", &self.capacity())
+            .field("length", &self.length())
+            .finish()
+    }
+}
+
+/// The popping half of a [`spsc`] ring buffer.
+pub struct Consumer<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Consumer<T> {
+    /// Removes the front element and returns it, or `None` if the buffer is
+    /// empty.
+    pub fn pop(&mut self) -> Option<T> {
+        let shared = &*self.shared;
+        let head = shared.head.0.load(Ordering::Relaxed);
+        let tail = shared.tail.0.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+        // SAFETY: the slot is published by the producer, and not released yet.
+        let elem = unsafe { (*shared.slot(head).get()).assume_init_read() };
+        shared.head.0.store(shared.next(head), Ordering::Release);
+        Some(elem)
+    }
+
+    /// Returns the maximum number of elements.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity()
+    }
+
+    /// Returns the number of elements, which may be outdated by the time it
+    /// returns.
+    pub fn length(&self) -> usize {
+        self.shared.length()
+    }
+
+    /// Returns `true` if the buffer is empty, the producer may push at any
+    /// time.
+    pub fn is_empty(&self) -> bool {
+        self.length() == 0
+    }
+}
+
+impl<T> Debug for Consumer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Consumer")
+            .field("capacity", &self.capacity())
+            .field("length", &self.length())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use crate::collection::ring_buffer::spsc;
+
+    #[test]
+    fn test_push_and_pop() {
+        let (mut p, mut c) = spsc(3);
+        assert_eq!(p.capacity(), 3);
+        assert!(c.is_empty());
+        for round in 0..5 {
+            for i in 0..3 {
+                p.push(round * 10 + i).unwrap();
+            }
+            assert!(p.is_full());
+            assert_eq!(p.push(-1), Err(-1));
+            assert_eq!(c.length(), 3);
+            assert_eq!(c.pop(), Some(round * 10));
+            p.push(round * 10 + 3).unwrap();
+            let rest: Vec<_> = std::iter::from_fn(|| c.pop()).collect();
+            assert_eq!(rest, vec![round * 10 + 1, round * 10 + 2, round * 10 + 3]);
+        }
+
+        let (mut p, mut c) = spsc(0);
+        assert_eq!(p.push(1), Err(1));
+        assert_eq!(c.pop(), None);
+    }
+
+    #[test]
+    fn test_drop() {
+        let counter = Arc::new(());
+        let (mut p, mut c) = spsc(4);
+        for _ in 0..4 {
+            p.push(Arc::clone(&counter)).unwrap();
+        }
+        drop(c.pop());
+        drop(p);
+        assert_eq!(Arc::strong_count(&counter), 4);
+        drop(c);
+        assert_eq!(Arc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn test_threads() {
+        const N: u64 = 100_000;
+        let (mut p, mut c) = spsc(7);
+        let producer = thread::spawn(move || {
+            for mut x in 0..N {
+                while let Err(rejected) = p.push(x) {
+                    x = rejected;
+                    thread::yield_now();
+                }
+            }
+        });
+
+        let mut expected = 0;
+        while expected < N {
+            match c.pop() {
+                Some(x) => {
+                    assert_eq!(x, expected);
+                    expected += 1;
+                }
+                None => thread::yield_now(),
+            }
+        }
+        producer.join().unwrap();
+        assert!(c.is_empty());
+    }
+}
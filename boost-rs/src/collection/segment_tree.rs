@@ -0,0 +1,773 @@
+//! Segment trees over a monoid, answering range queries in *O*(log *n*) time.
+//!
+//! [`SegmentTree`] supports point updates, while [`LazySegmentTree`] also
+//! supports range updates by propagating them lazily.
+//!
+//! Wikipedia:
+//!  - https://en.wikipedia.org/wiki/Segment_tree
+
+use std::fmt::{self, Debug};
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::ops::RangeBounds;
+
+use crate::collection::_index_range;
+use crate::collection::error::CollectionError;
+use crate::types::integer::Bounded;
+use crate::types::ops::NumOps;
+
+/// An associative operation on `T` with an identity element.
+///
+/// `combine` must be associative, and `identity` must be neutral for it. The
+/// operation doesn't need to be commutative.
+pub trait Monoid<T> {
+    fn identity() -> T;
+
+    fn combine(a: &T, b: &T) -> T;
+}
+
+/// A monoid whose values can be updated for a whole range at once.
+pub trait LazyMonoid<T>: Monoid<T> {
+    type Update: Clone;
+
+    /// Applies the update to the combined value of `len` elements.
+    fn apply(update: &Self::Update, value: &T, len: usize) -> T;
+
+    /// Composes two updates, `newer` is applied after `older`.
+    fn compose(newer: &Self::Update, older: &Self::Update) -> Self::Update;
+}
+
+/// The sum of the values, updated by adding to every value.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sum;
+
+/// The least value, updated by adding to every value.
+///
+/// It is a monoid over `T` with the identity `T::MAX`, and over `Option<T>`
+/// with the identity `None`. Updates are added to every element of `T`,
+/// including `T::MAX`, while `None` elements are left unset by updates.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Min;
+
+/// The greatest value, updated by adding to every value.
+///
+/// It is a monoid over `T` with the identity `T::MIN`, and over `Option<T>`
+/// with the identity `None`. Updates are added to every element of `T`,
+/// including `T::MIN`, while `None` elements are left unset by updates.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Max;
+
+impl<T: NumOps + Copy + Default> Monoid<T> for Sum {
+    fn identity() -> T {
+        T::default()
+    }
+
+    fn combine(a: &T, b: &T) -> T {
+        *a + *b
+    }
+}
+
+impl<T: NumOps + Copy + Default> LazyMonoid<T> for Sum {
+    type Update = T;
+
+    fn apply(update: &T, value: &T, len: usize) -> T {
+        *value + _times(*update, len)
+    }
+
+    fn compose(newer: &T, older: &T) -> T {
+        *newer + *older
+    }
+}
+
+impl<T: NumOps + Ord + Copy + Bounded> Monoid<T> for Min {
+    fn identity() -> T {
+        T::MAX
+    }
+
+    fn combine(a: &T, b: &T) -> T {
+        *a.min(b)
+    }
+}
+
+impl<T: NumOps + Ord + Copy + Bounded> LazyMonoid<T> for Min {
+    type Update = T;
+
+    fn apply(update: &T, value: &T, _len: usize) -> T {
+        *value + *update
+    }
+
+    fn compose(newer: &T, older: &T) -> T {
+        *newer + *older
+    }
+}
+
+impl<T: NumOps + Ord + Copy + Bounded> Monoid<T> for Max {
+    fn identity() -> T {
+        T::MIN
+    }
+
+    fn combine(a: &T, b: &T) -> T {
+        *a.max(b)
+    }
+}
+
+impl<T: NumOps + Ord + Copy + Bounded> LazyMonoid<T> for Max {
+    type Update = T;
+
+    fn apply(update: &T, value: &T, _len: usize) -> T {
+        *value + *update
+    }
+
+    fn compose(newer: &T, older: &T) -> T {
+        *newer + *older
+    }
+}
+
+impl<T: NumOps + Ord + Copy> Monoid<Option<T>> for Min {
+    fn identity() -> Option<T> {
+        None
+    }
+
+    fn combine(a: &Option<T>, b: &Option<T>) -> Option<T> {
+        match (a, b) {
+            (Some(x), Some(y)) => Some(*x.min(y)),
+            _ => a.or(*b),
+        }
+    }
+}
+
+impl<T: NumOps + Ord + Copy> LazyMonoid<Option<T>> for Min {
+    type Update = T;
+
+    fn apply(update: &T, value: &Option<T>, _len: usize) -> Option<T> {
+        value.map(|x| x + *update)
+    }
+
+    fn compose(newer: &T, older: &T) -> T {
+        *newer + *older
+    }
+}
+
+impl<T: NumOps + Ord + Copy> Monoid<Option<T>> for Max {
+    fn identity() -> Option<T> {
+        None
+    }
+
+    fn combine(a: &Option<T>, b: &Option<T>) -> Option<T> {
+        match (a, b) {
+            (Some(x), Some(y)) => Some(*x.max(y)),
+            _ => a.or(*b),
+        }
+    }
+}
+
+impl<T: NumOps + Ord + Copy> LazyMonoid<Option<T>> for Max {
+    type Update = T;
+
+    fn apply(update: &T, value: &Option<T>, _len: usize) -> Option<T> {
+        value.map(|x| x + *update)
+    }
+
+    fn compose(newer: &T, older: &T) -> T {
+        *newer + *older
+    }
+}
+
+/// Returns `x` added `n` times, with *O*(log *n*) additions.
+fn _times<T: NumOps + Copy + Default>(x: T, mut n: usize) -> T {
+    let (mut acc, mut power) = (T::default(), x);
+    while n > 0 {
+        if n & 1 == 1 {
+            acc = acc + power;
+        }
+        n >>= 1;
+        if n > 0 {
+            power = power + power;
+        }
+    }
+    acc
+}
+
+/// A segment tree with point updates and range queries.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::segment_tree::{Max, SegmentTree, Sum};
+///
+/// let mut sum: SegmentTree<i64, Sum> = SegmentTree::from(vec![1, 2, 3, 4, 5]);
+/// assert_eq!(sum.query(1..4).unwrap(), 9);
+/// sum.set(2, 10).unwrap();
+/// assert_eq!(sum.query(..).unwrap(), 22);
+///
+/// let max: SegmentTree<u8, Max> = vec![3, 9, 4].into_iter().collect();
+/// assert_eq!(max.query(..=1).unwrap(), 9);
+/// assert!(max.query(2..5).is_err());
+/// ```
+pub struct SegmentTree<T, Op> {
+    len: usize,
+    // The leaves are at `len..2 * len`, and the node `i` combines the nodes
+    // `2 * i` and `2 * i + 1`.
+    tree: Vec<T>,
+    _op: PhantomData<Op>,
+}
+
+impl<T: Clone, Op: Monoid<T>> SegmentTree<T, Op> {
+    /// Creates a tree of `n` elements, all of them the identity.
+    pub fn new(n: usize) -> Self {
+        Self::from(vec![Op::identity(); n])
+    }
+
+    /// Returns the number of elements.
+    #[inline]
+    pub fn length(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the element at `index`.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        Some(&self.tree[self.len + index])
+    }
+
+    /// Replaces the element at `index`, and returns the old element.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn set(&mut self, index: usize, value: T) -> Result<T, CollectionError> {
+        if index >= self.len {
+            return Err(CollectionError::IndexOutOfRange {
+                index,
+                len: self.len,
+            });
+        }
+        let mut i = self.len + index;
+        let old = std::mem::replace(&mut self.tree[i], value);
+        while i > 1 {
+            i /= 2;
+            self.tree[i] = Op::combine(&self.tree[2 * i], &self.tree[2 * i + 1]);
+        }
+        Ok(old)
+    }
+
+    /// Returns the combination of the elements in `range`, in order, or the
+    /// identity if the range is empty.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn query<R: RangeBounds<usize>>(&self, range: R) -> Result<T, CollectionError> {
+        let range = _index_range(range, self.len)?;
+        let (mut l, mut r) = (range.start + self.len, range.end + self.len);
+        // The results of the left and the right borders, kept apart since the
+        // operation may not be commutative.
+        let (mut left, mut right) = (Op::identity(), Op::identity());
+        while l < r {
+            if l & 1 == 1 {
+                left = Op::combine(&left, &self.tree[l]);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                right = Op::combine(&self.tree[r], &right);
+            }
+            l /= 2;
+            r /= 2;
+        }
+        Ok(Op::combine(&left, &right))
+    }
+
+    /// Gets an iterator over the elements.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.tree[self.len..].iter()
+    }
+}
+
+impl<T: Clone, Op: Monoid<T>> From<Vec<T>> for SegmentTree<T, Op> {
+    /// Builds a tree in *O*(*n*) time.
+    fn from(vec: Vec<T>) -> Self {
+        let len = vec.len();
+        let mut tree = Vec::with_capacity(2 * len);
+        tree.resize(len, Op::identity());
+        tree.extend(vec);
+        for i in (1..len).rev() {
+            tree[i] = Op::combine(&tree[2 * i], &tree[2 * i + 1]);
+        }
+        SegmentTree {
+            len,
+            tree,
+            _op: PhantomData,
+        }
+    }
+}
+
+impl<T: Clone, Op: Monoid<T>> FromIterator<T> for SegmentTree<T, Op> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<T: Clone, Op> Clone for SegmentTree<T, Op> {
+    fn clone(&self) -> Self {
+        SegmentTree {
+            len: self.len,
+            tree: self.tree.clone(),
+            _op: PhantomData,
+        }
+    }
+}
+
+impl<T: Debug, Op> Debug for SegmentTree<T, Op> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.tree[self.len..].iter())
+            .finish()
+    }
+}
+
+/// A segment tree with range updates and range queries.
+///
+/// The updates of a node are kept pending until a query or another update
+/// descends below it.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::segment_tree::{LazySegmentTree, Min, Sum};
+///
+/// let mut sum: LazySegmentTree<i32, Sum> = LazySegmentTree::from(vec![0; 8]);
+/// sum.update(2..6, &3).unwrap();
+/// sum.update(4.., &1).unwrap();
+/// assert_eq!(sum.query(..).unwrap(), 16);
+/// assert_eq!(sum.get(5).unwrap(), 4);
+///
+/// let mut min: LazySegmentTree<i32, Min> = LazySegmentTree::from(vec![5, 1, 7]);
+/// min.update(..2, &10).unwrap();
+/// assert_eq!(min.query(..).unwrap(), 7);
+/// ```
+pub struct LazySegmentTree<T, Op: LazyMonoid<T>> {
+    len: usize,
+    // The node `1` covers `0..len`, and the node `i` is split into the nodes
+    // `2 * i` and `2 * i + 1` at the middle of its range.
+    tree: Vec<T>,
+    // The pending update of the children of every node.
+    lazy: Vec<Option<Op::Update>>,
+}
+
+impl<T: Clone, Op: LazyMonoid<T>> LazySegmentTree<T, Op> {
+    /// Creates a tree of `n` elements, all of them the identity.
+    pub fn new(n: usize) -> Self {
+        Self::from(vec![Op::identity(); n])
+    }
+
+    /// Returns the number of elements.
+    #[inline]
+    pub fn length(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the element at `index`.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn get(&mut self, index: usize) -> Result<T, CollectionError> {
+        self.query(index..=index)
+    }
+
+    /// Replaces the element at `index`.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn set(&mut self, index: usize, value: T) -> Result<(), CollectionError> {
+        if index >= self.len {
+            return Err(CollectionError::IndexOutOfRange {
+                index,
+                len: self.len,
+            });
+        }
+        self._set(1, 0, self.len, index, value);
+        Ok(())
+    }
+
+    /// Applies the update to every element in `range`.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn update<R: RangeBounds<usize>>(
+        &mut self,
+        range: R,
+        update: &Op::Update,
+    ) -> Result<(), CollectionError> {
+        let range = _index_range(range, self.len)?;
+        if !range.is_empty() {
+            self._update(1, 0, self.len, range.start, range.end, update);
+        }
+        Ok(())
+    }
+
+    /// Returns the combination of the elements in `range`, in order, or the
+    /// identity if the range is empty.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn query<R: RangeBounds<usize>>(&mut self, range: R) -> Result<T, CollectionError> {
+        let range = _index_range(range, self.len)?;
+        if range.is_empty() {
+            return Ok(Op::identity());
+        }
+        Ok(self._query(1, 0, self.len, range.start, range.end))
+    }
+
+    /// Applies the update to the node covering `len` elements.
+    fn _apply(&mut self, node: usize, len: usize, update: &Op::Update) {
+        self.tree[node] = Op::apply(update, &self.tree[node], len);
+        if len > 1 {
+            self.lazy[node] = Some(match &self.lazy[node] {
+                Some(older) => Op::compose(update, older),
+                None => update.clone(),
+            });
+        }
+    }
+
+    /// Pushes the pending update of the node covering `lo..hi` down to its
+    /// children.
+    fn _push(&mut self, node: usize, lo: usize, hi: usize) {
+        if let Some(update) = self.lazy[node].take() {
+            let mid = (lo + hi) / 2;
+            self._apply(2 * node, mid - lo, &update);
+            self._apply(2 * node + 1, hi - mid, &update);
+        }
+    }
+
+    fn _pull(&mut self, node: usize) {
+        self.tree[node] = Op::combine(&self.tree[2 * node], &self.tree[2 * node + 1]);
+    }
+
+    fn _build(&mut self, node: usize, lo: usize, hi: usize, values: &mut Vec<Option<T>>) {
+        if hi - lo == 1 {
+            self.tree[node] = values[lo].take().unwrap();
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        self._build(2 * node, lo, mid, values);
+        self._build(2 * node + 1, mid, hi, values);
+        self._pull(node);
+    }
+
+    fn _set(&mut self, node: usize, lo: usize, hi: usize, index: usize, value: T) {
+        if hi - lo == 1 {
+            self.tree[node] = value;
+            return;
+        }
+        self._push(node, lo, hi);
+        let mid = (lo + hi) / 2;
+        if index < mid {
+            self._set(2 * node, lo, mid, index, value);
+        } else {
+            self._set(2 * node + 1, mid, hi, index, value);
+        }
+        self._pull(node);
+    }
+
+    fn _update(
+        &mut self,
+        node: usize,
+        lo: usize,
+        hi: usize,
+        l: usize,
+        r: usize,
+        update: &Op::Update,
+    ) {
+        if l <= lo && hi <= r {
+            self._apply(node, hi - lo, update);
+            return;
+        }
+        self._push(node, lo, hi);
+        let mid = (lo + hi) / 2;
+        if l < mid {
+            self._update(2 * node, lo, mid, l, r, update);
+        }
+        if mid < r {
+            self._update(2 * node + 1, mid, hi, l, r, update);
+        }
+        self._pull(node);
+    }
+
+    fn _query(&mut self, node: usize, lo: usize, hi: usize, l: usize, r: usize) -> T {
+        if l <= lo && hi <= r {
+            return self.tree[node].clone();
+        }
+        self._push(node, lo, hi);
+        let mid = (lo + hi) / 2;
+        if r <= mid {
+            self._query(2 * node, lo, mid, l, r)
+        } else if mid <= l {
+            self._query(2 * node + 1, mid, hi, l, r)
+        } else {
+            let left = self._query(2 * node, lo, mid, l, r);
+            let right = self._query(2 * node + 1, mid, hi, l, r);
+            Op::combine(&left, &right)
+        }
+    }
+}
+
+impl<T: Clone, Op: LazyMonoid<T>> From<Vec<T>> for LazySegmentTree<T, Op> {
+    /// Builds a tree in *O*(*n*) time.
+    fn from(vec: Vec<T>) -> Self {
+        let len = vec.len();
+        let mut tree = LazySegmentTree {
+            len,
+            tree: vec![Op::identity(); 4 * len],
+            lazy: vec![None; 4 * len],
+        };
+        if len > 0 {
+            let mut values = vec.into_iter().map(Some).collect();
+            tree._build(1, 0, len, &mut values);
+        }
+        tree
+    }
+}
+
+impl<T: Clone, Op: LazyMonoid<T>> FromIterator<T> for LazySegmentTree<T, Op> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<T: Clone, Op: LazyMonoid<T>> Clone for LazySegmentTree<T, Op> {
+    fn clone(&self) -> Self {
+        LazySegmentTree {
+            len: self.len,
+            tree: self.tree.clone(),
+            lazy: self.lazy.clone(),
+        }
+    }
+}
+
+impl<T: Debug, Op: LazyMonoid<T>> Debug for LazySegmentTree<T, Op> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazySegmentTree")
+            .field("length", &self.len)
+            .field("total", &self.tree.get(1))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use crate::collection::error::CollectionError;
+    use crate::collection::segment_tree::{
+        LazyMonoid, LazySegmentTree, Max, Min, Monoid, SegmentTree, Sum,
+    };
+
+    /// Concatenation of strings, which is not commutative.
+    struct Concat;
+
+    impl Monoid<String> for Concat {
+        fn identity() -> String {
+            String::new()
+        }
+
+        fn combine(a: &String, b: &String) -> String {
+            format!("{}{}", a, b)
+        }
+    }
+
+    /// Affine maps `x -> a * x + b` composed in order, with assignment as
+    /// the range update.
+    struct Affine;
+
+    impl Monoid<(i64, i64)> for Affine {
+        fn identity() -> (i64, i64) {
+            (1, 0)
+        }
+
+        fn combine(f: &(i64, i64), g: &(i64, i64)) -> (i64, i64) {
+            (g.0 * f.0, g.0 * f.1 + g.1)
+        }
+    }
+
+    impl LazyMonoid<(i64, i64)> for Affine {
+        type Update = (i64, i64);
+
+        fn apply(update: &(i64, i64), _value: &(i64, i64), len: usize) -> (i64, i64) {
+            (0..len).fold((1, 0), |acc, _| Affine::combine(&acc, update))
+        }
+
+        fn compose(newer: &(i64, i64), _older: &(i64, i64)) -> (i64, i64) {
+            *newer
+        }
+    }
+
+    #[test]
+    fn test_query_and_set() {
+        let mut t: SegmentTree<i32, Sum> = SegmentTree::from(vec![5, -2, 7, 1, 3, 0, 4]);
+        assert_eq!(t.length(), 7);
+        assert_eq!(t.query(..).unwrap(), 18);
+        assert_eq!(t.query(2..5).unwrap(), 11);
+        assert_eq!(t.query(3..3).unwrap(), 0);
+        assert_eq!(t.set(1, 10).unwrap(), -2);
+        assert_eq!(t.query(..=1).unwrap(), 15);
+        assert_eq!(t.get(1), Some(&10));
+        assert_eq!(t.get(7), None);
+        assert_eq!(
+            t.iter().copied().collect::<Vec<_>>(),
+            vec![5, 10, 7, 1, 3, 0, 4]
+        );
+
+        assert!(matches!(
+            t.set(7, 0),
+            Err(CollectionError::IndexOutOfRange { index: 7, len: 7 })
+        ));
+        assert!(matches!(
+            t.query(2..8),
+            Err(CollectionError::IndexOutOfRange { index: 8, len: 7 })
+        ));
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = t.query(4..2);
+        assert!(matches!(
+            reversed,
+            Err(CollectionError::InvalidParameter(_))
+        ));
+
+        let mut min: SegmentTree<u64, Min> = SegmentTree::new(4);
+        assert_eq!(min.query(..).unwrap(), u64::MAX);
+        min.set(2, 8).unwrap();
+        assert_eq!(min.query(1..).unwrap(), 8);
+
+        let empty: SegmentTree<i8, Max> = SegmentTree::new(0);
+        assert!(empty.is_empty());
+        assert_eq!(empty.query(..).unwrap(), i8::MIN);
+    }
+
+    #[test]
+    fn test_non_commutative() {
+        let words = ["a", "b", "c", "d", "e", "f"];
+        let mut t: SegmentTree<String, Concat> = words.iter().map(|w| w.to_string()).collect();
+        assert_eq!(t.query(..).unwrap(), "abcdef");
+        assert_eq!(t.query(1..5).unwrap(), "bcde");
+        t.set(3, "X".to_string()).unwrap();
+        assert_eq!(t.query(2..).unwrap(), "cXef");
+    }
+
+    #[test]
+    fn test_lazy() {
+        let mut t: LazySegmentTree<u8, Sum> = LazySegmentTree::new(300);
+        t.update(.., &0).unwrap();
+        t.update(100..200, &1).unwrap();
+        assert_eq!(t.query(..).unwrap(), 100);
+        assert_eq!(t.query(150..250).unwrap(), 50);
+        t.set(150, 9).unwrap();
+        assert_eq!(t.get(150).unwrap(), 9);
+        assert_eq!(t.query(140..160).unwrap(), 28);
+        assert!(t.update(0..301, &1).is_err());
+
+        let mut t: LazySegmentTree<(i64, i64), Affine> = LazySegmentTree::from(vec![(2, 1); 5]);
+        // x -> 2x + 1 three times.
+        assert_eq!(t.query(1..4).unwrap(), (8, 7));
+        t.update(1..3, &(1, 5)).unwrap();
+        assert_eq!(t.query(..).unwrap(), (8, 47));
+    }
+
+    #[test]
+    fn test_lazy_update_bounds() {
+        // Elements equal to the identity are updated like any other.
+        let mut t: LazySegmentTree<i32, Min> = LazySegmentTree::from(vec![i32::MAX, i32::MAX]);
+        t.update(1.., &-1).unwrap();
+        assert_eq!(t.query(..).unwrap(), i32::MAX - 1);
+        assert_eq!(t.get(0).unwrap(), i32::MAX);
+        let mut t: LazySegmentTree<u8, Max> = LazySegmentTree::from(vec![0, 0, 0]);
+        t.update(..2, &1).unwrap();
+        t.update(1..=1, &254).unwrap();
+        assert_eq!(t.query(..).unwrap(), u8::MAX);
+        assert_eq!(t.get(0).unwrap(), 1);
+        assert_eq!(t.get(2).unwrap(), 0);
+
+        // Unset elements are left out of updates.
+        let mut t: LazySegmentTree<Option<i32>, Min> = LazySegmentTree::new(4);
+        t.update(.., &1).unwrap();
+        assert_eq!(t.query(..).unwrap(), None);
+        t.set(2, Some(5)).unwrap();
+        t.update(1..4, &1).unwrap();
+        assert_eq!(t.query(..).unwrap(), Some(6));
+        assert_eq!(t.get(1).unwrap(), None);
+        let mut t: LazySegmentTree<Option<u8>, Max> = LazySegmentTree::new(4);
+        t.set(0, Some(u8::MAX)).unwrap();
+        t.set(3, Some(0)).unwrap();
+        t.update(.., &0).unwrap();
+        assert_eq!(t.query(1..).unwrap(), Some(0));
+        assert_eq!(t.query(1..3).unwrap(), None);
+        assert_eq!(t.query(..).unwrap(), Some(u8::MAX));
+
+        assert!(matches!(
+            t.get(usize::MAX),
+            Err(CollectionError::IndexOutOfRange { .. })
+        ));
+        assert!(matches!(
+            t.query(..=usize::MAX),
+            Err(CollectionError::IndexOutOfRange {
+                index: usize::MAX,
+                len: 4
+            })
+        ));
+        assert!(matches!(
+            t.query((Bound::Excluded(usize::MAX), Bound::Unbounded)),
+            Err(CollectionError::IndexOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn test_random_consistency() {
+        use crate::collection::test_util::seeded_rng;
+        use rand::Rng;
+
+        let mut rng = seeded_rng();
+        let mut v: Vec<i64> = (0..97).map(|_| rng.gen_range(-100..100)).collect();
+        let mut sum: LazySegmentTree<i64, Sum> = LazySegmentTree::from(v.clone());
+        let mut max: LazySegmentTree<i64, Max> = LazySegmentTree::from(v.clone());
+        let mut min: SegmentTree<i64, Min> = SegmentTree::from(v.clone());
+        for _ in 0..2000 {
+            let l = rng.gen_range(0..=v.len());
+            let r = rng.gen_range(l..=v.len());
+            match rng.gen_range(0..3) {
+                0 => {
+                    let d = rng.gen_range(-50..50);
+                    v[l..r].iter_mut().for_each(|x| *x += d);
+                    sum.update(l..r, &d).unwrap();
+                    max.update(l..r, &d).unwrap();
+                    for (i, &x) in v.iter().enumerate().take(r).skip(l) {
+                        min.set(i, x).unwrap();
+                    }
+                }
+                1 if l < v.len() => {
+                    let x = rng.gen_range(-100..100);
+                    v[l] = x;
+                    sum.set(l, x).unwrap();
+                    max.set(l, x).unwrap();
+                    min.set(l, x).unwrap();
+                }
+                _ => {
+                    let s = &v[l..r];
+                    assert_eq!(sum.query(l..r).unwrap(), s.iter().sum::<i64>());
+                    assert_eq!(
+                        max.query(l..r).unwrap(),
+                        s.iter().copied().max().unwrap_or(i64::MIN)
+                    );
+                    assert_eq!(
+                        min.query(l..r).unwrap(),
+                        s.iter().copied().min().unwrap_or(i64::MAX)
+                    );
+                }
+            }
+        }
+    }
+}
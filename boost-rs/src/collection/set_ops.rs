@@ -0,0 +1,328 @@
+//! Lazy set operations over two iterators which yield their elements in
+//! ascending order, such as the iterators of `BinarySearchTree` and `OrdSkipList`.
+//!
+//! Every operation merges both inputs in a single pass, so it runs in
+//! *O*(*n* + *m*) time and never allocates.
+//!
+//! When an input contains duplicates, each element of one side cancels at most
+//! one equal element of the other side, like merging two sorted multisets.
+
+use std::cmp::Ordering;
+use std::iter::Peekable;
+
+/// A lazy iterator producing elements in the union of two sorted sequences.
+///
+/// Equal elements are yielded once, taken from the first sequence.
+pub struct Union<'a, T: 'a, I, C>
+where
+    I: Iterator<Item = &'a T>,
+{
+    a: Peekable<I>,
+    b: Peekable<I>,
+    cmp: C,
+}
+
+impl<'a, T: 'a, I, C> Union<'a, T, I, C>
+where
+    I: Iterator<Item = &'a T>,
+    C: Fn(&T, &T) -> Ordering,
+{
+    pub fn new(a: I, b: I, cmp: C) -> Self {
+        Self {
+            a: a.peekable(),
+            b: b.peekable(),
+            cmp,
+        }
+    }
+}
+
+impl<'a, T: 'a, I, C> Iterator for Union<'a, T, I, C>
+where
+    I: Iterator<Item = &'a T>,
+    C: Fn(&T, &T) -> Ordering,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.a.peek(), self.b.peek()) {
+            (Some(x), Some(y)) => match (self.cmp)(x, y) {
+                Ordering::Less => self.a.next(),
+                Ordering::Greater => self.b.next(),
+                Ordering::Equal => {
+                    self.b.next();
+                    self.a.next()
+                }
+            },
+            (Some(_), None) => self.a.next(),
+            (None, _) => self.b.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_min, a_max) = self.a.size_hint();
+        let (b_min, b_max) = self.b.size_hint();
+        let max = match (a_max, b_max) {
+            (Some(a), Some(b)) => a.checked_add(b),
+            _ => None,
+        };
+        (a_min.max(b_min), max)
+    }
+}
+
+/// A lazy iterator producing elements in the intersection of two sorted sequences.
+///
+/// Equal elements are taken from the first sequence.
+pub struct Intersection<'a, T: 'a, I, C>
+where
+    I: Iterator<Item = &'a T>,
+{
+    a: Peekable<I>,
+    b: Peekable<I>,
+    cmp: C,
+}
+
+impl<'a, T: 'a, I, C> Intersection<'a, T, I, C>
+where
+    I: Iterator<Item = &'a T>,
+    C: Fn(&T, &T) -> Ordering,
+{
+    pub fn new(a: I, b: I, cmp: C) -> Self {
+        Self {
+            a: a.peekable(),
+            b: b.peekable(),
+            cmp,
+        }
+    }
+}
+
+impl<'a, T: 'a, I, C> Iterator for Intersection<'a, T, I, C>
+where
+    I: Iterator<Item = &'a T>,
+    C: Fn(&T, &T) -> Ordering,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match (self.cmp)(self.a.peek()?, self.b.peek()?) {
+                Ordering::Less => {
+                    self.a.next();
+                }
+                Ordering::Greater => {
+                    self.b.next();
+                }
+                Ordering::Equal => {
+                    self.b.next();
+                    return self.a.next();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let max = match (self.a.size_hint().1, self.b.size_hint().1) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        (0, max)
+    }
+}
+
+/// A lazy iterator producing elements of the first sorted sequence which are
+/// not in the second one.
+pub struct Difference<'a, T: 'a, I, C>
+where
+    I: Iterator<Item = &'a T>,
+{
+    a: Peekable<I>,
+    b: Peekable<I>,
+    cmp: C,
+}
+
+impl<'a, T: 'a, I, C> Difference<'a, T, I, C>
+where
+    I: Iterator<Item = &'a T>,
+    C: Fn(&T, &T) -> Ordering,
+{
+    pub fn new(a: I, b: I, cmp: C) -> Self {
+        Self {
+            a: a.peekable(),
+            b: b.peekable(),
+            cmp,
+        }
+    }
+}
+
+impl<'a, T: 'a, I, C> Iterator for Difference<'a, T, I, C>
+where
+    I: Iterator<Item = &'a T>,
+    C: Fn(&T, &T) -> Ordering,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let x = self.a.peek()?;
+            let y = match self.b.peek() {
+                Some(y) => y,
+                None => return self.a.next(),
+            };
+            match (self.cmp)(x, y) {
+                Ordering::Less => return self.a.next(),
+                Ordering::Greater => {
+                    self.b.next();
+                }
+                Ordering::Equal => {
+                    self.a.next();
+                    self.b.next();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.a.size_hint().1)
+    }
+}
+
+/// A lazy iterator producing elements which are in exactly one of two sorted
+/// sequences.
+pub struct SymmetricDifference<'a, T: 'a, I, C>
+where
+    I: Iterator<Item = &'a T>,
+{
+    a: Peekable<I>,
+    b: Peekable<I>,
+    cmp: C,
+}
+
+impl<'a, T: 'a, I, C> SymmetricDifference<'a, T, I, C>
+where
+    I: Iterator<Item = &'a T>,
+    C: Fn(&T, &T) -> Ordering,
+{
+    pub fn new(a: I, b: I, cmp: C) -> Self {
+        Self {
+            a: a.peekable(),
+            b: b.peekable(),
+            cmp,
+        }
+    }
+}
+
+impl<'a, T: 'a, I, C> Iterator for SymmetricDifference<'a, T, I, C>
+where
+    I: Iterator<Item = &'a T>,
+    C: Fn(&T, &T) -> Ordering,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match (self.a.peek(), self.b.peek()) {
+                (Some(x), Some(y)) => match (self.cmp)(x, y) {
+                    Ordering::Less => return self.a.next(),
+                    Ordering::Greater => return self.b.next(),
+                    Ordering::Equal => {
+                        self.a.next();
+                        self.b.next();
+                    }
+                },
+                (Some(_), None) => return self.a.next(),
+                (None, _) => return self.b.next(),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let max = match (self.a.size_hint().1, self.b.size_hint().1) {
+            (Some(a), Some(b)) => a.checked_add(b),
+            _ => None,
+        };
+        (0, max)
+    }
+}
+
+/// Returns `true` if every element of the sorted sequence `a` is also in the
+/// sorted sequence `b`.
+pub fn is_subset<'a, T: 'a, I, C>(a: I, b: I, cmp: C) -> bool
+where
+    I: Iterator<Item = &'a T>,
+    C: Fn(&T, &T) -> Ordering,
+{
+    Difference::new(a, b, cmp).next().is_none()
+}
+
+/// Returns `true` if the sorted sequences `a` and `b` have no elements in common.
+pub fn is_disjoint<'a, T: 'a, I, C>(a: I, b: I, cmp: C) -> bool
+where
+    I: Iterator<Item = &'a T>,
+    C: Fn(&T, &T) -> Ordering,
+{
+    Intersection::new(a, b, cmp).next().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use crate::collection::set_ops::*;
+
+    fn cmp(x: &i32, y: &i32) -> Ordering {
+        x.cmp(y)
+    }
+
+    #[test]
+    fn test_union() {
+        let (a, b) = ([1, 3, 5, 7], [2, 3, 4, 7, 9]);
+        let res: Vec<_> = Union::new(a.iter(), b.iter(), cmp).copied().collect();
+        assert_eq!(res, vec![1, 2, 3, 4, 5, 7, 9]);
+
+        let res: Vec<_> = Union::new([].iter(), b.iter(), cmp).copied().collect();
+        assert_eq!(res, b);
+    }
+
+    #[test]
+    fn test_intersection() {
+        let (a, b) = ([1, 3, 5, 7], [2, 3, 4, 7, 9]);
+        let res: Vec<_> = Intersection::new(a.iter(), b.iter(), cmp)
+            .copied()
+            .collect();
+        assert_eq!(res, vec![3, 7]);
+
+        let (a, b) = ([1, 1, 2], [1, 2, 2]);
+        let res: Vec<_> = Intersection::new(a.iter(), b.iter(), cmp)
+            .copied()
+            .collect();
+        assert_eq!(res, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_difference() {
+        let (a, b) = ([1, 3, 5, 7], [2, 3, 4, 7, 9]);
+        let res: Vec<_> = Difference::new(a.iter(), b.iter(), cmp).copied().collect();
+        assert_eq!(res, vec![1, 5]);
+
+        let res: Vec<_> = Difference::new(b.iter(), a.iter(), cmp).copied().collect();
+        assert_eq!(res, vec![2, 4, 9]);
+    }
+
+    #[test]
+    fn test_symmetric_difference() {
+        let (a, b) = ([1, 3, 5, 7], [2, 3, 4, 7, 9]);
+        let res: Vec<_> = SymmetricDifference::new(a.iter(), b.iter(), cmp)
+            .copied()
+            .collect();
+        assert_eq!(res, vec![1, 2, 4, 5, 9]);
+    }
+
+    #[test]
+    fn test_subset_and_disjoint() {
+        assert!(is_subset([2, 4].iter(), [1, 2, 3, 4].iter(), cmp));
+        assert!(!is_subset([2, 5].iter(), [1, 2, 3, 4].iter(), cmp));
+        assert!(is_subset([].iter(), [1].iter(), cmp));
+
+        assert!(is_disjoint([1, 3].iter(), [2, 4].iter(), cmp));
+        assert!(!is_disjoint([1, 3].iter(), [3, 4].iter(), cmp));
+    }
+}
@@ -0,0 +1,825 @@
+//! A lock-free concurrent skiplist, which can be shared between threads.
+//!
+//! The algorithm follows the lock-free skiplist of Herlihy and Shavit (*The
+//! Art of Multiprocessor Programming*, chapter 14). A node is removed by
+//! marking its links from the top level down to level 0, and the mark on level
+//! 0 is the point where the removal takes effect. Marked nodes are unlinked
+//! with CAS by later searches passing by. Inserts link a node on level 0 first,
+//! which is the point where the insertion takes effect, and then build the
+//! tower upwards. Reads never write to the skiplist.
+//!
+//! Memory is reclaimed with the epoch based garbage collector of
+//! `crossbeam-epoch`. Every node counts the levels it is linked on, plus one
+//! while its inserter is still building its tower. Once the count drops to 0,
+//! no new reference to the node can be obtained and it is handed over to the
+//! garbage collector, which frees it after all the threads which could still
+//! see it are done.
+
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::HashSet;
+use std::iter;
+use std::marker::PhantomData;
+use std::sync::atomic::{self, AtomicU64, AtomicUsize, Ordering};
+
+use crossbeam_epoch::{self as epoch, Atomic, Guard, Owned, Shared};
+use rand::{thread_rng, Rng};
+
+/// The max number of levels of a concurrent skiplist.
+const LEVEL_BOUND: usize = 32;
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+
+    // The number of levels the node is linked on, plus one while its inserter
+    // is still building the tower.
+    refs: AtomicUsize,
+
+    // Links to the next node on each level. A link tagged with 1 is marked,
+    // which means the node is removed on that level and the link is frozen.
+    tower: Box<[Atomic<Node<K, V>>]>,
+}
+
+impl<K, V> Node<K, V> {
+    /// Returns `true` if the node has been removed.
+    fn is_removed(&self, guard: &Guard) -> bool {
+        self.tower[0].load(Ordering::Acquire, guard).tag() == 1
+    }
+
+    /// Drops a reference to the node, and hands the node over to the garbage
+    /// collector if it was the last one.
+    unsafe fn release(&self, guard: &Guard) {
+        if self.refs.fetch_sub(1, Ordering::Release) == 1 {
+            atomic::fence(Ordering::Acquire);
+            guard.defer_destroy(Shared::from(self as *const Self));
+        }
+    }
+}
+
+/// The nodes right before and right after the position of a key, on every
+/// level below the height of the search.
+struct Position<'g, K, V> {
+    preds: [&'g [Atomic<Node<K, V>>]; LEVEL_BOUND],
+    succs: [Shared<'g, Node<K, V>>; LEVEL_BOUND],
+}
+
+impl<'g, K: Ord, V> Position<'g, K, V> {
+    /// Returns the node of the given key, if it is right after the position.
+    fn found(&self, key: &K) -> Option<&'g Node<K, V>> {
+        unsafe { self.succs[0].as_ref() }.filter(|n| n.key.cmp(key) == CmpOrdering::Equal)
+    }
+}
+
+/// A lock-free concurrent ordered map based on a skiplist.
+///
+/// All the operations take `&self`, so the map can be shared between threads,
+/// e.g. through an `Arc`. Lookups never block, and inserts and removes are
+/// done with CAS. Since entries may be removed by other threads at any time,
+/// values are returned as clones.
+///
+/// An existing entry is never overwritten: [`ConcurrentSkipMap::insert`] only
+/// inserts absent keys.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// use boost_rs::collection::skiplist::ConcurrentSkipMap;
+///
+/// let map = Arc::new(ConcurrentSkipMap::new());
+/// let handles: Vec<_> = (0..4)
+///     .map(|t| {
+///         let map = Arc::clone(&map);
+///         thread::spawn(move || {
+///             for i in 0..100 {
+///                 map.insert(i * 4 + t, t);
+///             }
+///         })
+///     })
+///     .collect();
+/// handles.into_iter().for_each(|h| h.join().unwrap());
+///
+/// assert_eq!(map.len(), 400);
+/// assert_eq!(map.get(&9), Some(1));
+/// ```
+pub struct ConcurrentSkipMap<K, V> {
+    head: Box<[Atomic<Node<K, V>>]>,
+
+    // The highest height of the nodes, searches start on this level.
+    height: AtomicUsize,
+
+    length: AtomicUsize,
+
+    // The state of the xorshift generator for node heights.
+    seed: AtomicU64,
+
+    _marker: PhantomData<Box<Node<K, V>>>,
+}
+
+unsafe impl<K: Send + Sync, V: Send + Sync> Send for ConcurrentSkipMap<K, V> {}
+
+unsafe impl<K: Send + Sync, V: Send + Sync> Sync for ConcurrentSkipMap<K, V> {}
+
+impl<K, V> Default for ConcurrentSkipMap<K, V> {
+    fn default() -> Self {
+        Self {
+            head: iter::repeat_with(Atomic::null).take(LEVEL_BOUND).collect(),
+            height: AtomicUsize::new(1),
+            length: AtomicUsize::new(0),
+            seed: AtomicU64::new(thread_rng().gen::<u64>() | 1),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K, V> ConcurrentSkipMap<K, V> {
+    /// Returns the number of entries in the map.
+    ///
+    /// The number may be outdated as soon as it is returned, if other threads
+    /// are modifying the map.
+    pub fn len(&self) -> usize {
+        self.length.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Generates the height of a new node, which is geometrically distributed
+    /// with `p = 1/2`.
+    fn random_height(&self) -> usize {
+        // Racing threads may get the same number, which is fine as the heights
+        // only need to look random.
+        let mut x = self.seed.load(Ordering::Relaxed);
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.seed.store(x, Ordering::Relaxed);
+        (x.trailing_zeros() as usize + 1).min(LEVEL_BOUND)
+    }
+}
+
+impl<K, V> ConcurrentSkipMap<K, V>
+where
+    K: Ord + Send + 'static,
+    V: Send + 'static,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a key-value pair into the map, if the map does not contain the
+    /// key yet.
+    ///
+    /// Returns `true` if the pair has been inserted.
+    pub fn insert(&self, key: K, value: V) -> bool {
+        let guard = &epoch::pin();
+        let height = self.random_height();
+        self.height.fetch_max(height, Ordering::AcqRel);
+
+        let mut pos = self.search(&key, guard);
+        if pos.found(&key).is_some() {
+            return false;
+        }
+
+        let mut node = Owned::new(Node {
+            key,
+            value,
+            refs: AtomicUsize::new(2),
+            tower: iter::repeat_with(Atomic::null).take(height).collect(),
+        });
+        // Linking the node on level 0 makes it part of the map. It is counted
+        // beforehand, so that a concurrent removal cannot underflow the length.
+        self.length.fetch_add(1, Ordering::Relaxed);
+        let node = loop {
+            node.tower[0].store(pos.succs[0], Ordering::Relaxed);
+            match pos.preds[0][0].compare_exchange(
+                pos.succs[0],
+                node,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+                guard,
+            ) {
+                Ok(node) => break node,
+                Err(e) => {
+                    node = e.new;
+                    pos = self.search(&node.key, guard);
+                    if pos.found(&node.key).is_some() {
+                        self.length.fetch_sub(1, Ordering::Relaxed);
+                        return false;
+                    }
+                }
+            }
+        };
+
+        let n = unsafe { node.deref() };
+        'build: for level in 1..height {
+            loop {
+                // Stop building as soon as the node is being removed.
+                let next = n.tower[level].load(Ordering::Acquire, guard);
+                if next.tag() == 1 {
+                    break 'build;
+                }
+                let succ = pos.succs[level];
+                if n.tower[level]
+                    .compare_exchange(next, succ, Ordering::AcqRel, Ordering::Acquire, guard)
+                    .is_err()
+                {
+                    break 'build;
+                }
+
+                n.refs.fetch_add(1, Ordering::Relaxed);
+                if pos.preds[level][level]
+                    .compare_exchange(succ, node, Ordering::AcqRel, Ordering::Acquire, guard)
+                    .is_ok()
+                {
+                    break;
+                }
+                n.refs.fetch_sub(1, Ordering::Relaxed);
+
+                pos = self.search(&n.key, guard);
+                if !matches!(pos.found(&n.key), Some(f) if std::ptr::eq(f, n)) {
+                    break 'build;
+                }
+            }
+        }
+
+        // The node may have been removed while its tower was built, and the
+        // remover may have missed the levels linked afterwards.
+        if n.is_removed(guard) {
+            self.search(&n.key, guard);
+        }
+        unsafe { n.release(guard) };
+        true
+    }
+
+    /// Returns `true` if the map contains the given key.
+    pub fn contains_key(&self, key: &K) -> bool {
+        let guard = &epoch::pin();
+        self.find(key, guard).is_some()
+    }
+
+    /// Removes the given key from the map.
+    ///
+    /// Returns `true` if the key has been removed by this call.
+    pub fn remove_key(&self, key: &K) -> bool {
+        let guard = &epoch::pin();
+        self.remove_node(key, guard).is_some()
+    }
+
+    /// Finds the position of `key` on every level, unlinking the marked nodes
+    /// on the way.
+    fn search<'g>(&'g self, key: &K, guard: &'g Guard) -> Position<'g, K, V> {
+        'retry: loop {
+            let mut pos = Position {
+                preds: [&self.head[..]; LEVEL_BOUND],
+                succs: [Shared::null(); LEVEL_BOUND],
+            };
+
+            let mut pred: &'g [Atomic<Node<K, V>>] = &self.head;
+            for level in (0..self.height.load(Ordering::Acquire)).rev() {
+                let mut curr = pred[level].load(Ordering::Acquire, guard);
+                if curr.tag() == 1 {
+                    // The predecessor has been removed in the meantime.
+                    continue 'retry;
+                }
+
+                while let Some(c) = unsafe { curr.as_ref() } {
+                    let succ = c.tower[level].load(Ordering::Acquire, guard);
+                    if succ.tag() == 1 {
+                        match pred[level].compare_exchange(
+                            curr,
+                            succ.with_tag(0),
+                            Ordering::AcqRel,
+                            Ordering::Acquire,
+                            guard,
+                        ) {
+                            Ok(_) => {
+                                unsafe { c.release(guard) };
+                                curr = succ.with_tag(0);
+                            }
+                            Err(_) => continue 'retry,
+                        }
+                    } else if c.key < *key {
+                        pred = &c.tower;
+                        curr = succ;
+                    } else {
+                        break;
+                    }
+                }
+
+                pos.preds[level] = pred;
+                pos.succs[level] = curr;
+            }
+            return pos;
+        }
+    }
+
+    /// Finds the node of `key` without modifying the skiplist.
+    fn find<'g>(&'g self, key: &K, guard: &'g Guard) -> Option<&'g Node<K, V>> {
+        let mut pred: &'g [Atomic<Node<K, V>>] = &self.head;
+        for level in (0..self.height.load(Ordering::Acquire)).rev() {
+            let mut curr = pred[level].load(Ordering::Acquire, guard).with_tag(0);
+            while let Some(c) = unsafe { curr.as_ref() } {
+                match c.key.cmp(key) {
+                    CmpOrdering::Less => {
+                        pred = &c.tower;
+                        curr = c.tower[level].load(Ordering::Acquire, guard).with_tag(0);
+                    }
+                    CmpOrdering::Equal if !c.is_removed(guard) => return Some(c),
+                    // A removed node may linger on the upper levels, while a
+                    // new node of the same key is only reachable below.
+                    _ => break,
+                }
+            }
+        }
+        None
+    }
+
+    /// Removes the node of `key`, and returns it if it has been removed by
+    /// this call.
+    fn remove_node<'g>(&'g self, key: &K, guard: &'g Guard) -> Option<&'g Node<K, V>> {
+        let pos = self.search(key, guard);
+        let n = pos.found(key)?;
+
+        for level in (1..n.tower.len()).rev() {
+            n.tower[level].fetch_or(1, Ordering::AcqRel, guard);
+        }
+        // Whoever marks level 0 removes the node.
+        let mut next = n.tower[0].load(Ordering::Acquire, guard);
+        loop {
+            if next.tag() == 1 {
+                return None;
+            }
+            match n.tower[0].compare_exchange(
+                next,
+                next.with_tag(1),
+                Ordering::AcqRel,
+                Ordering::Acquire,
+                guard,
+            ) {
+                Ok(_) => break,
+                Err(e) => next = e.current,
+            }
+        }
+        self.length.fetch_sub(1, Ordering::Relaxed);
+
+        // Unlink the node from all levels.
+        self.search(key, guard);
+        Some(n)
+    }
+}
+
+impl<K, V> ConcurrentSkipMap<K, V>
+where
+    K: Ord + Send + 'static,
+    V: Clone + Send + 'static,
+{
+    /// Returns a clone of the value of the given key.
+    pub fn get(&self, key: &K) -> Option<V> {
+        let guard = &epoch::pin();
+        self.find(key, guard).map(|n| n.value.clone())
+    }
+
+    /// Removes the given key from the map, and returns its value if it has
+    /// been removed by this call.
+    pub fn remove(&self, key: &K) -> Option<V> {
+        let guard = &epoch::pin();
+        self.remove_node(key, guard).map(|n| n.value.clone())
+    }
+}
+
+impl<K: Clone, V: Clone> ConcurrentSkipMap<K, V> {
+    /// Gets an iterator over the entries of the map, sorted by key.
+    ///
+    /// The iterator is weakly consistent: it yields the entries which are in
+    /// the map when it passes by, and is not disturbed by concurrent updates.
+    /// It keeps the current thread pinned, so memory removed in the meantime
+    /// is not reclaimed until it is dropped.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let guard = epoch::pin();
+        let next = self.head[0].load(Ordering::Acquire, &guard).as_raw();
+        Iter {
+            guard,
+            next,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K, V> Drop for ConcurrentSkipMap<K, V> {
+    fn drop(&mut self) {
+        unsafe {
+            let guard = epoch::unprotected();
+            // Removed nodes may still be linked on the upper levels, so the
+            // nodes on all levels have to be collected.
+            let mut nodes = HashSet::new();
+            for level in 0..LEVEL_BOUND {
+                let mut curr = self.head[level].load(Ordering::Relaxed, guard);
+                while let Some(c) = curr.with_tag(0).as_ref() {
+                    nodes.insert(c as *const Node<K, V>);
+                    curr = c.tower[level].load(Ordering::Relaxed, guard);
+                }
+            }
+            for node in nodes {
+                drop(Shared::from(node).into_owned());
+            }
+        }
+    }
+}
+
+/// An iterator over the entries of a `ConcurrentSkipMap`.
+pub struct Iter<'a, K, V> {
+    guard: Guard,
+    next: *const Node<K, V>,
+    _marker: PhantomData<&'a ConcurrentSkipMap<K, V>>,
+}
+
+impl<'a, K: Clone, V: Clone> Iterator for Iter<'a, K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let n = unsafe { self.next.as_ref() }?;
+            let succ = n.tower[0].load(Ordering::Acquire, &self.guard);
+            self.next = succ.with_tag(0).as_raw();
+            if succ.tag() == 0 {
+                return Some((n.key.clone(), n.value.clone()));
+            }
+        }
+    }
+}
+
+/// A lock-free concurrent ordered set based on a skiplist.
+///
+/// See [`ConcurrentSkipMap`] for details.
+pub struct ConcurrentSkipList<T> {
+    map: ConcurrentSkipMap<T, ()>,
+}
+
+impl<T> Default for ConcurrentSkipList<T> {
+    fn default() -> Self {
+        Self {
+            map: ConcurrentSkipMap::default(),
+        }
+    }
+}
+
+impl<T: Ord + Send + 'static> ConcurrentSkipList<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a value into the set, and returns `true` if it was absent.
+    pub fn insert(&self, v: T) -> bool {
+        self.map.insert(v, ())
+    }
+
+    /// Returns `true` if the set contains the value.
+    pub fn contains(&self, v: &T) -> bool {
+        self.map.contains_key(v)
+    }
+
+    /// Removes a value from the set, and returns `true` if it has been removed
+    /// by this call.
+    pub fn remove(&self, v: &T) -> bool {
+        self.map.remove_key(v)
+    }
+}
+
+impl<T> ConcurrentSkipList<T> {
+    /// Returns the number of elements in the set.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the set contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl<T: Clone> ConcurrentSkipList<T> {
+    /// Gets a weakly consistent iterator over the elements of the set, in
+    /// ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.map.iter().map(|(k, _)| k)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use std::fmt;
+    use std::marker::PhantomData;
+
+    use serde::de::{MapAccess, SeqAccess, Visitor};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::collection::skiplist::{ConcurrentSkipList, ConcurrentSkipMap};
+
+    impl<K, V> Serialize for ConcurrentSkipMap<K, V>
+    where
+        K: Serialize + Clone,
+        V: Serialize + Clone,
+    {
+        /// Serializes the entries as a map, in ascending order of the keys.
+        ///
+        /// Just like [`ConcurrentSkipMap::iter`], this is weakly consistent
+        /// with concurrent updates.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_map(self.iter())
+        }
+    }
+
+    impl<'de, K, V> Deserialize<'de> for ConcurrentSkipMap<K, V>
+    where
+        K: Deserialize<'de> + Ord + Send + 'static,
+        V: Deserialize<'de> + Send + 'static,
+    {
+        /// Deserializes a map, the first value of a repeated key wins.
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct MapVisitor<K, V>(PhantomData<(K, V)>);
+
+            impl<'de, K, V> Visitor<'de> for MapVisitor<K, V>
+            where
+                K: Deserialize<'de> + Ord + Send + 'static,
+                V: Deserialize<'de> + Send + 'static,
+            {
+                type Value = ConcurrentSkipMap<K, V>;
+
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("a map")
+                }
+
+                fn visit_map<A: MapAccess<'de>>(
+                    self,
+                    mut access: A,
+                ) -> Result<Self::Value, A::Error> {
+                    let m = ConcurrentSkipMap::new();
+                    while let Some((k, v)) = access.next_entry()? {
+                        m.insert(k, v);
+                    }
+                    Ok(m)
+                }
+            }
+
+            deserializer.deserialize_map(MapVisitor(PhantomData))
+        }
+    }
+
+    impl<T: Serialize + Clone> Serialize for ConcurrentSkipList<T> {
+        /// Serializes the elements as a sequence, in ascending order.
+        ///
+        /// Just like [`ConcurrentSkipList::iter`], this is weakly consistent
+        /// with concurrent updates.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.iter())
+        }
+    }
+
+    impl<'de, T> Deserialize<'de> for ConcurrentSkipList<T>
+    where
+        T: Deserialize<'de> + Ord + Send + 'static,
+    {
+        /// Deserializes a sequence of elements, duplicates are ignored.
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct SeqVisitor<T>(PhantomData<T>);
+
+            impl<'de, T> Visitor<'de> for SeqVisitor<T>
+            where
+                T: Deserialize<'de> + Ord + Send + 'static,
+            {
+                type Value = ConcurrentSkipList<T>;
+
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("a sequence")
+                }
+
+                fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                    let l = ConcurrentSkipList::new();
+                    while let Some(v) = seq.next_element()? {
+                        l.insert(v);
+                    }
+                    Ok(l)
+                }
+            }
+
+            deserializer.deserialize_seq(SeqVisitor(PhantomData))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::collection::skiplist::concurrent::{ConcurrentSkipList, ConcurrentSkipMap};
+
+    const THREADS: usize = 8;
+
+    fn run<F>(f: F)
+    where
+        F: Fn(usize) + Send + Sync + 'static,
+    {
+        let f = Arc::new(f);
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let f = Arc::clone(&f);
+                thread::spawn(move || f(t))
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn basic() {
+        let m = ConcurrentSkipMap::new();
+        assert!(m.is_empty());
+        assert!(m.insert(3, "c"));
+        assert!(m.insert(1, "a"));
+        assert!(!m.insert(3, "C"));
+        assert!(m.insert(2, "b"));
+
+        assert_eq!(m.len(), 3);
+        assert_eq!(m.get(&3), Some("c"));
+        assert_eq!(m.get(&4), None);
+        assert_eq!(
+            m.iter().collect::<Vec<_>>(),
+            vec![(1, "a"), (2, "b"), (3, "c")]
+        );
+
+        assert_eq!(m.remove(&2), Some("b"));
+        assert_eq!(m.remove(&2), None);
+        assert!(!m.contains_key(&2));
+        assert!(m.insert(2, "B"));
+        assert_eq!(m.get(&2), Some("B"));
+        assert_eq!(m.len(), 3);
+    }
+
+    #[test]
+    fn concurrent_insert_disjoint() {
+        let s = Arc::new(ConcurrentSkipList::new());
+        let s2 = Arc::clone(&s);
+        run(move |t| {
+            for i in 0..1000 {
+                assert!(s2.insert(i * THREADS + t));
+            }
+        });
+
+        assert_eq!(s.len(), 1000 * THREADS);
+        assert!(s.iter().eq(0..1000 * THREADS));
+    }
+
+    #[test]
+    fn concurrent_insert_same_keys() {
+        let s = Arc::new(ConcurrentSkipList::new());
+        let inserted = Arc::new(AtomicUsize::new(0));
+        let (s2, inserted2) = (Arc::clone(&s), Arc::clone(&inserted));
+        run(move |t| {
+            for i in 0..1000 {
+                // Every thread goes through the keys in a different order.
+                let k = (i * 7 + t * 131) % 1000;
+                if s2.insert(k) {
+                    inserted2.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        });
+
+        assert_eq!(inserted.load(Ordering::Relaxed), 1000);
+        assert_eq!(s.len(), 1000);
+        assert!(s.iter().eq(0..1000));
+    }
+
+    #[test]
+    fn concurrent_remove_same_keys() {
+        let s = Arc::new(ConcurrentSkipList::new());
+        for i in 0..2000 {
+            s.insert(i);
+        }
+
+        let removed = Arc::new(AtomicUsize::new(0));
+        let (s2, removed2) = (Arc::clone(&s), Arc::clone(&removed));
+        run(move |t| {
+            for i in 0..2000 {
+                if s2.remove(&((i + t * 250) % 2000)) {
+                    removed2.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        });
+
+        assert_eq!(removed.load(Ordering::Relaxed), 2000);
+        assert!(s.is_empty());
+        assert_eq!(s.iter().count(), 0);
+    }
+
+    #[test]
+    fn concurrent_mixed_operations() {
+        const KEYS: usize = 64;
+
+        let m = Arc::new(ConcurrentSkipMap::new());
+        let inserts: Arc<Vec<AtomicUsize>> =
+            Arc::new((0..KEYS).map(|_| AtomicUsize::new(0)).collect());
+        let removes: Arc<Vec<AtomicUsize>> =
+            Arc::new((0..KEYS).map(|_| AtomicUsize::new(0)).collect());
+
+        let (m2, inserts2, removes2) = (Arc::clone(&m), Arc::clone(&inserts), Arc::clone(&removes));
+        run(move |t| {
+            let mut rng = StdRng::seed_from_u64(t as u64);
+            for _ in 0..5000 {
+                let k = rng.gen_range(0..KEYS);
+                match rng.gen_range(0..4) {
+                    0 => {
+                        if m2.insert(k, k * 10) {
+                            inserts2[k].fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                    1 => {
+                        if let Some(v) = m2.remove(&k) {
+                            assert_eq!(v, k * 10);
+                            removes2[k].fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                    2 => {
+                        if let Some(v) = m2.get(&k) {
+                            assert_eq!(v, k * 10);
+                        }
+                    }
+                    _ => {
+                        // Every snapshot must be strictly ascending.
+                        let keys: Vec<_> = m2.iter().map(|(k, _)| k).collect();
+                        assert!(keys.windows(2).all(|w| w[0] < w[1]));
+                    }
+                }
+            }
+        });
+
+        // Successful inserts and removes of a key must alternate, starting
+        // with an insert, so their counts tell whether the key is present.
+        let mut len = 0;
+        for k in 0..KEYS {
+            let i = inserts[k].load(Ordering::Relaxed);
+            let r = removes[k].load(Ordering::Relaxed);
+            assert!(
+                i == r || i == r + 1,
+                "key {}: {} inserts, {} removes",
+                k,
+                i,
+                r
+            );
+            assert_eq!(m.contains_key(&k), i == r + 1);
+            len += i - r;
+        }
+        assert_eq!(m.len(), len);
+        assert_eq!(m.iter().count(), len);
+    }
+
+    #[test]
+    fn drop_values() {
+        struct Counted(Arc<AtomicUsize>);
+
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let m = ConcurrentSkipMap::new();
+        for i in 0..100 {
+            assert!(m.insert(i, Counted(Arc::clone(&dropped))));
+        }
+        assert!(!m.insert(0, Counted(Arc::clone(&dropped))));
+        assert_eq!(dropped.load(Ordering::Relaxed), 1);
+
+        drop(m);
+        assert_eq!(dropped.load(Ordering::Relaxed), 101);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let m = ConcurrentSkipMap::new();
+        for k in [3, 1, 2] {
+            m.insert(k, k * 10);
+        }
+        let json = serde_json::to_string(&m).unwrap();
+        assert_eq!(json, r#"{"1":10,"2":20,"3":30}"#);
+        let m: ConcurrentSkipMap<i32, i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(m.len(), 3);
+        assert_eq!(m.get(&2), Some(20));
+
+        let l: ConcurrentSkipList<i32> = serde_json::from_str("[3,1,3,2]").unwrap();
+        assert_eq!(l.len(), 3);
+        assert_eq!(serde_json::to_string(&l).unwrap(), "[1,2,3]");
+    }
+}
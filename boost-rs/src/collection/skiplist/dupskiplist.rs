@@ -0,0 +1,376 @@
+//! A skiplist which permits duplicates, i.e. a sorted multiset.
+//!
+//! Equal elements are kept in insertion order, which makes the list suitable
+//! as an event queue where several events may share the same timestamp.
+
+use std::iter;
+use std::ops::RangeBounds;
+
+use crate::collection::error::CollectionError;
+use crate::collection::skiplist::ordskiplist::{
+    IntoIter, Iter, IterMut, Options, OrdSkipList, Range, RangeMut,
+};
+
+/// A sorted skiplist which permits duplicates.
+///
+/// It offers the same operations as [`OrdSkipList`], but `insert` never
+/// fails: a new element is placed after all the elements equal to it.
+/// Operations on a single element, such as [`DupSkipList::remove`] and
+/// [`DupSkipList::index_of`], refer to the earliest inserted one among equals.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::skiplist::{DupSkipList, Options};
+///
+/// // Events sorted by timestamp only.
+/// let mut events = DupSkipList::with_options(Options {
+///     cmp: Some(Box::new(|x: &(u64, &str), y: &(u64, &str)| x.0.cmp(&y.0))),
+///     level_bound: None,
+///     level_generator: None,
+/// })
+/// .unwrap();
+/// events.insert((2, "b"));
+/// events.insert((1, "a"));
+/// events.insert((2, "c"));
+///
+/// assert_eq!(events.count(&(2, "")), 2);
+/// assert_eq!(events.iter().map(|e| e.1).collect::<Vec<_>>(), vec!["a", "b", "c"]);
+/// ```
+pub struct DupSkipList<T> {
+    list: OrdSkipList<T>,
+}
+
+impl<T: Ord> DupSkipList<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ord_with_options(options: Options<T>) -> Result<Self, CollectionError> {
+        Ok(Self {
+            list: OrdSkipList::ord_with_options(options)?,
+        })
+    }
+}
+
+impl<T: Ord> Default for DupSkipList<T> {
+    fn default() -> Self {
+        Self {
+            list: OrdSkipList::default(),
+        }
+    }
+}
+
+impl<T> DupSkipList<T> {
+    pub fn with_options(options: Options<T>) -> Result<Self, CollectionError> {
+        Ok(Self {
+            list: OrdSkipList::with_options(options)?,
+        })
+    }
+
+    /// Inserts an element after all the elements equal to it.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn insert(&mut self, data: T) {
+        self.list._insert_dup(data)
+    }
+
+    /// Returns the number of elements equal to `v`.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn count(&self, v: &T) -> usize {
+        self.list._equal_range(v).len()
+    }
+
+    /// Returns true if the value is contained in the skiplist.
+    pub fn contains(&self, v: &T) -> bool {
+        self.list.contains(v)
+    }
+
+    /// Returns the index of the first element equal to `v`.
+    pub fn index_of(&self, v: &T) -> Option<usize> {
+        self.list.index_of(v)
+    }
+
+    /// Returns the element at the given index.
+    pub fn get(&self, idx: usize) -> Option<&T> {
+        self.list.get(idx)
+    }
+
+    /// Removes the earliest inserted element equal to `v` and returns it.
+    pub fn remove(&mut self, v: &T) -> Option<T> {
+        self.list.remove(v)
+    }
+
+    /// Removes all the elements equal to `v`, and returns them in insertion order.
+    ///
+    /// This operation should compute in *O*(log *n* + *k*) time, where *k* is
+    /// the number of removed elements.
+    pub fn remove_all(&mut self, v: &T) -> Vec<T> {
+        self.list._remove_equals(v)
+    }
+
+    /// Removes the element at the given index and returns it.
+    pub fn remove_at(&mut self, idx: usize) -> Result<T, CollectionError> {
+        self.list.remove_at(idx)
+    }
+
+    /// Returns the first element of the skiplist.
+    pub fn first(&self) -> Option<&T> {
+        self.list.first()
+    }
+
+    /// Returns the last element of the skiplist.
+    pub fn last(&self) -> Option<&T> {
+        self.list.last()
+    }
+
+    /// Removes the first element from the skiplist and returns it.
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    /// Removes the last element from the skiplist and returns it.
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+
+    /// Returns the first element which is not less than `v`.
+    pub fn lower_bound(&self, v: &T) -> Option<&T> {
+        self.list.lower_bound(v)
+    }
+
+    /// Returns the first element which is greater than `v`.
+    pub fn upper_bound(&self, v: &T) -> Option<&T> {
+        self.list.upper_bound(v)
+    }
+
+    /// Clears the skiplist, removing all values.
+    pub fn clear(&mut self) {
+        self.list.clear()
+    }
+
+    /// Returns `true` if the skiplist contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Returns the number of elements in the skiplist.
+    #[inline]
+    pub fn length(&self) -> usize {
+        self.list.length()
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.list.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.list.iter_mut()
+    }
+
+    /// Constructs a double-ended iterator over the elements within `range`.
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Range<'_, T> {
+        self.list.range(range)
+    }
+
+    /// Constructs a mutable double-ended iterator over the elements within `range`.
+    pub fn range_mut<R: RangeBounds<T>>(&mut self, range: R) -> RangeMut<'_, T> {
+        self.list.range_mut(range)
+    }
+}
+
+impl<T> IntoIterator for DupSkipList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.list.into_iter()
+    }
+}
+
+impl<T> Extend<T> for DupSkipList<T> {
+    /// Inserts all the elements of the iterator.
+    ///
+    /// Elements not less than the last element are appended directly at the
+    /// end, so extending by sorted input computes in *O*(*n*) time.
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iterable: I) {
+        self.list._extend_dup(iterable)
+    }
+}
+
+impl<T: Ord> iter::FromIterator<T> for DupSkipList<T> {
+    #[inline]
+    fn from_iter<I>(iter: I) -> DupSkipList<T>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut skiplist = DupSkipList::default();
+        skiplist.extend(iter);
+        skiplist
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::collection::skiplist::DupSkipList;
+
+    impl<T: Serialize> Serialize for DupSkipList<T> {
+        /// Serializes the elements as a sequence, in ascending order.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.iter())
+        }
+    }
+
+    impl<'de, T: Deserialize<'de> + Ord> Deserialize<'de> for DupSkipList<T> {
+        /// Deserializes a sequence of elements, equal elements keep their
+        /// order in the sequence.
+        ///
+        /// A sorted sequence is built in *O*(*n*) time.
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let elements = Vec::<T>::deserialize(deserializer)?;
+            Ok(elements.into_iter().collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collection::skiplist::{DupSkipList, Options};
+
+    fn by_key() -> DupSkipList<(i32, usize)> {
+        DupSkipList::with_options(Options {
+            cmp: Some(Box::new(|x: &(i32, usize), y: &(i32, usize)| x.0.cmp(&y.0))),
+            level_bound: None,
+            level_generator: None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn insert_duplicates() {
+        let mut l: DupSkipList<i32> = vec![3, 1, 3, 2, 3, 1].into_iter().collect();
+        assert_eq!(l.length(), 6);
+        assert_eq!(
+            l.iter().copied().collect::<Vec<_>>(),
+            vec![1, 1, 2, 3, 3, 3]
+        );
+
+        assert_eq!(l.count(&3), 3);
+        assert_eq!(l.count(&2), 1);
+        assert_eq!(l.count(&0), 0);
+        assert_eq!(l.count(&4), 0);
+        assert_eq!(l.index_of(&3), Some(3));
+        assert_eq!(l.upper_bound(&1), Some(&2));
+
+        assert_eq!(l.remove(&3), Some(3));
+        assert_eq!(l.count(&3), 2);
+        assert_eq!(l.range(2..).count(), 3);
+    }
+
+    #[test]
+    fn insertion_order() {
+        let mut l = by_key();
+        for (i, k) in vec![5, 1, 5, 3, 1, 5].into_iter().enumerate() {
+            l.insert((k, i));
+        }
+        assert_eq!(
+            l.iter().copied().collect::<Vec<_>>(),
+            vec![(1, 1), (1, 4), (3, 3), (5, 0), (5, 2), (5, 5)]
+        );
+
+        assert_eq!(l.remove(&(5, 0)), Some((5, 0)));
+        assert_eq!(l.first(), Some(&(1, 1)));
+        assert_eq!(l.last(), Some(&(5, 5)));
+        assert_eq!(l.pop_front(), Some((1, 1)));
+    }
+
+    #[test]
+    fn remove_all() {
+        let mut l = by_key();
+        for (i, k) in vec![2, 7, 2, 2, 9, 7].into_iter().enumerate() {
+            l.insert((k, i));
+        }
+
+        assert_eq!(l.remove_all(&(2, 0)), vec![(2, 0), (2, 2), (2, 3)]);
+        assert_eq!(l.remove_all(&(2, 0)), vec![]);
+        assert_eq!(l.length(), 3);
+        assert_eq!(l.get(0), Some(&(7, 1)));
+
+        assert_eq!(l.remove_all(&(9, 0)), vec![(9, 4)]);
+        assert_eq!(l.into_iter().collect::<Vec<_>>(), vec![(7, 1), (7, 5)]);
+    }
+
+    #[test]
+    fn random_consistency() {
+        use crate::collection::test_util::random_ops;
+        use rand::Rng;
+
+        let mut l = by_key();
+        let mut v: Vec<(i32, usize)> = Vec::new();
+        random_ops(2000, |rng, i| {
+            let k = rng.gen_range(0..50);
+            match rng.gen_range(0..10) {
+                0 => {
+                    let removed: Vec<_> = v.iter().filter(|x| x.0 == k).copied().collect();
+                    v.retain(|x| x.0 != k);
+                    assert_eq!(l.remove_all(&(k, 0)), removed);
+                }
+                1..=2 => {
+                    let pos = v.iter().position(|x| x.0 == k);
+                    assert_eq!(l.remove(&(k, 0)), pos.map(|p| v.remove(p)));
+                }
+                _ => {
+                    l.insert((k, i));
+                    let pos = v.partition_point(|x| x.0 <= k);
+                    v.insert(pos, (k, i));
+                }
+            }
+            assert_eq!(l.count(&(k, 0)), v.iter().filter(|x| x.0 == k).count());
+        });
+
+        assert!(l.iter().eq(v.iter()));
+        assert!(l.iter().rev().eq(v.iter().rev()));
+    }
+
+    #[test]
+    fn extend_sorted_and_unsorted() {
+        let mut l = by_key();
+        l.extend(vec![(1, 0), (2, 1), (2, 2), (4, 3)]);
+        l.extend(vec![(2, 4), (4, 5), (5, 6), (0, 7)]);
+        assert_eq!(
+            l.iter().copied().collect::<Vec<_>>(),
+            vec![
+                (0, 7),
+                (1, 0),
+                (2, 1),
+                (2, 2),
+                (2, 4),
+                (4, 3),
+                (4, 5),
+                (5, 6)
+            ]
+        );
+        assert!(l
+            .iter()
+            .rev()
+            .eq(l.iter().collect::<Vec<_>>().into_iter().rev()));
+        assert_eq!(l.index_of(&(4, 0)), Some(5));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let l: DupSkipList<i32> = vec![2, 1, 2, 3].into_iter().collect();
+        let json = serde_json::to_string(&l).unwrap();
+        assert_eq!(json, "[1,2,2,3]");
+
+        let l: DupSkipList<i32> = serde_json::from_str("[3,1,3,2]").unwrap();
+        assert_eq!(l.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 3]);
+        assert_eq!(l.count(&3), 2);
+    }
+}
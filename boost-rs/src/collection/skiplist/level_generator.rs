@@ -8,15 +8,52 @@
 //!
 //! It is very unlikely that this will need to be changed as the default should
 //! suffice, but if need be custom level generators can be implemented.
+//!
+//! For reproducible skiplist shapes, e.g. in benchmarks, use
+//! [`DefaultLevelGenerator::with_seed`] or the deterministic
+//! [`PerfectLevelGenerator`].
 
 use rand::rngs::SmallRng;
 use rand::{thread_rng, Rng, SeedableRng};
 
 use crate::collection::error::CollectionError;
 
+/// Returns the number of levels which suits a skiplist of `expected_len`
+/// elements, where `p` is the probability that a node is present in the next
+/// level.
+///
+/// It is the smallest `level_bound` such that the top level is still expected to
+/// hold about one node, i.e. `ceil(log(1/p, expected_len))`, and at least 1.
+///
+/// # Panics
+///
+/// Panics if `p` is not in `(0, 1)`.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::skiplist::level_generator::level_bound_for;
+///
+/// assert_eq!(level_bound_for(1 << 16, 0.5), 16);
+/// assert_eq!(level_bound_for(1000, 0.25), 5);
+/// ```
+pub fn level_bound_for(expected_len: usize, p: f64) -> usize {
+    assert!(p > 0.0 && p < 1.0, "p must be in (0,1).");
+    let mut level_bound = 1;
+    let mut capacity = 1.0 / p;
+    while capacity < expected_len as f64 {
+        level_bound += 1;
+        capacity /= p;
+    }
+    level_bound
+}
+
 /// Upon the insertion of a new node in the list, the node is replicated to high
 /// levels with a certain probability as determined by a `LevelGenerator`.
-pub trait GenerateLevel {
+///
+/// Generators are owned by the skiplists, which can be sent and shared across
+/// threads, so they must be `Send + Sync` as well since 0.0.10.
+pub trait GenerateLevel: Send + Sync {
     /// The max number of levels that are assumed to exist for this level
     /// generator.
     ///
@@ -26,6 +63,15 @@ pub trait GenerateLevel {
     ///
     /// This must never return a level that is `>= self.level_bound)`.
     fn random(&mut self) -> usize;
+    /// Creates a generator for a skiplist split off or cloned from the one
+    /// owning this generator.
+    ///
+    /// The default is a [`DefaultLevelGenerator`] with the same
+    /// `level_bound`, so custom generators should override it to be carried
+    /// over.
+    fn box_clone(&self) -> Box<dyn GenerateLevel> {
+        Box::new(DefaultLevelGenerator::new(self.level_bound(), 1.0 / 2.0).unwrap())
+    }
 }
 
 /// A level generator which will produce geometrically distributed numbers.
@@ -33,6 +79,7 @@ pub trait GenerateLevel {
 /// The probability of generating level `n` is `p` times the probability of
 /// generating level `n-1`, with the probability truncated at the maximum number
 /// of levels allowed.
+#[derive(Clone)]
 pub struct DefaultLevelGenerator {
     level_bound: usize,
     p: f64,
@@ -71,6 +118,26 @@ impl DefaultLevelGenerator {
             rng: SmallRng::from_rng(thread_rng()).unwrap(),
         })
     }
+
+    /// Same as [`DefaultLevelGenerator::new`], but the random numbers are
+    /// generated from the given seed, so the generated levels, and with them
+    /// the shapes of the skiplists, are reproducible.
+    pub fn with_seed(level_bound: usize, p: f64, seed: u64) -> Result<Self, CollectionError> {
+        let mut g = Self::new(level_bound, p)?;
+        g.rng = SmallRng::seed_from_u64(seed);
+        Ok(g)
+    }
+
+    /// Same as [`DefaultLevelGenerator::new`], but the `level_bound` is sized
+    /// for a skiplist of `expected_len` elements by [`level_bound_for`].
+    pub fn with_expected_len(expected_len: usize, p: f64) -> Result<Self, CollectionError> {
+        if !(p > 0.0 && p < 1.0) {
+            return Err(CollectionError::InvalidParameter(
+                "p must be in (0,1).".to_string(),
+            ));
+        }
+        Self::new(level_bound_for(expected_len, p), p)
+    }
 }
 
 impl GenerateLevel for DefaultLevelGenerator {
@@ -88,11 +155,95 @@ impl GenerateLevel for DefaultLevelGenerator {
         }
         level
     }
+
+    /// Clones the generator with a random number generator seeded from the
+    /// state of this one, so the copy doesn't repeat the levels of this
+    /// generator, and a seeded generator stays reproducible.
+    fn box_clone(&self) -> Box<dyn GenerateLevel> {
+        let mut g = self.clone();
+        g.rng = SmallRng::from_rng(&mut g.rng).unwrap();
+        Box::new(g)
+    }
+}
+
+/// A deterministic level generator, which levels the nodes by the number of
+/// generated levels so far.
+///
+/// The `n`-th generated level (counting from 1) is the number of times `n` is
+/// divisible by 2, truncated at the maximum level, so every second node is on
+/// level 1, every fourth node on level 2 and so on. When the elements are
+/// inserted in ascending order, this builds a perfectly balanced skiplist.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::skiplist::level_generator::{GenerateLevel, PerfectLevelGenerator};
+///
+/// let mut g = PerfectLevelGenerator::new(3).unwrap();
+/// let levels: Vec<_> = (0..8).map(|_| g.random()).collect();
+/// assert_eq!(levels, vec![0, 1, 0, 2, 0, 1, 0, 2]);
+/// ```
+#[derive(Clone)]
+pub struct PerfectLevelGenerator {
+    level_bound: usize,
+    count: u64,
+}
+
+impl Default for PerfectLevelGenerator {
+    fn default() -> Self {
+        PerfectLevelGenerator::new(16).unwrap()
+    }
+}
+
+impl PerfectLevelGenerator {
+    /// Create a new PerfectLevelGenerator with `level_bound` number of levels.
+    ///
+    /// `level_bound` must be greater or equal to 1.
+    pub fn new(level_bound: usize) -> Result<Self, CollectionError> {
+        if level_bound == 0 {
+            return Err(CollectionError::InvalidParameter(
+                "total must be non-zero.".to_string(),
+            ));
+        }
+        Ok(PerfectLevelGenerator {
+            level_bound,
+            count: 0,
+        })
+    }
+
+    /// Same as [`PerfectLevelGenerator::new`], but the `level_bound` is sized
+    /// for a skiplist of `expected_len` elements by [`level_bound_for`].
+    pub fn with_expected_len(expected_len: usize) -> Self {
+        PerfectLevelGenerator {
+            level_bound: level_bound_for(expected_len, 0.5),
+            count: 0,
+        }
+    }
+}
+
+impl GenerateLevel for PerfectLevelGenerator {
+    fn level_bound(&self) -> usize {
+        self.level_bound
+    }
+
+    fn random(&mut self) -> usize {
+        self.count = self.count.wrapping_add(1).max(1);
+        (self.count.trailing_zeros() as usize).min(self.level_bound - 1)
+    }
+
+    /// Clones the generator with its count, so the copy continues the same
+    /// deterministic levels.
+    fn box_clone(&self) -> Box<dyn GenerateLevel> {
+        Box::new(self.clone())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::collection::skiplist::level_generator::{DefaultLevelGenerator, GenerateLevel};
+    use crate::collection::skiplist::level_generator::{
+        level_bound_for, DefaultLevelGenerator, GenerateLevel, PerfectLevelGenerator,
+    };
+    use crate::collection::skiplist::{Options, OrdSkipList};
 
     #[test]
     fn invalid_total() {
@@ -126,4 +277,94 @@ mod tests {
             assert!(level < level_bound);
         }
     }
+
+    #[test]
+    fn with_seed() {
+        let mut a = DefaultLevelGenerator::with_seed(16, 0.5, 42).unwrap();
+        let mut b = DefaultLevelGenerator::with_seed(16, 0.5, 42).unwrap();
+        let levels: Vec<_> = (0..1000).map(|_| a.random()).collect();
+        assert!(levels.iter().all(|&l| l == b.random()));
+        assert!(levels.iter().any(|&l| l > 0));
+
+        assert!(DefaultLevelGenerator::with_seed(0, 0.5, 42).is_err());
+    }
+
+    #[test]
+    fn box_clone() {
+        let mut a = DefaultLevelGenerator::with_seed(16, 0.5, 42).unwrap();
+        let (mut b, mut c) = (a.box_clone(), a.box_clone());
+        assert_eq!(b.level_bound(), 16);
+        let levels: Vec<_> = (0..1000).map(|_| a.random()).collect();
+        let copied: Vec<_> = (0..1000).map(|_| b.random()).collect();
+        assert_ne!(levels, copied);
+        assert!(copied.iter().all(|&l| l == c.random()));
+
+        struct Constant;
+
+        impl GenerateLevel for Constant {
+            fn level_bound(&self) -> usize {
+                3
+            }
+
+            fn random(&mut self) -> usize {
+                0
+            }
+        }
+
+        let mut g = Constant.box_clone();
+        assert_eq!(g.level_bound(), 3);
+        assert!((0..100).all(|_| g.random() < 3));
+    }
+
+    #[test]
+    fn perfect() {
+        let mut g = PerfectLevelGenerator::new(4).unwrap();
+        let levels: Vec<_> = (0..16).map(|_| g.random()).collect();
+        assert_eq!(levels, vec![0, 1, 0, 2, 0, 1, 0, 3, 0, 1, 0, 2, 0, 1, 0, 3]);
+
+        let mut g = PerfectLevelGenerator::new(1).unwrap();
+        assert!((0..10).all(|_| g.random() == 0));
+        assert!(PerfectLevelGenerator::new(0).is_err());
+    }
+
+    #[test]
+    fn expected_len() {
+        assert_eq!(level_bound_for(0, 0.5), 1);
+        assert_eq!(level_bound_for(2, 0.5), 1);
+        assert_eq!(level_bound_for(3, 0.5), 2);
+        assert_eq!(level_bound_for(1_000_000, 0.5), 20);
+        assert_eq!(level_bound_for(1_000_000, 0.25), 10);
+
+        let g = DefaultLevelGenerator::with_expected_len(1000, 0.5).unwrap();
+        assert_eq!(g.level_bound(), 10);
+        assert!(DefaultLevelGenerator::with_expected_len(1000, 1.5).is_err());
+        assert_eq!(
+            PerfectLevelGenerator::with_expected_len(100).level_bound(),
+            7
+        );
+    }
+
+    #[test]
+    fn plug_into_options() {
+        let mut l: OrdSkipList<u32> = OrdSkipList::ord_with_options(Options {
+            cmp: None,
+            level_bound: None,
+            level_generator: Some(Box::new(PerfectLevelGenerator::with_expected_len(1000))),
+        })
+        .unwrap();
+        l.extend(0..1000);
+        assert!(l.iter().copied().eq(0..1000));
+        assert_eq!(l.get(500), Some(&500));
+
+        let mut l: OrdSkipList<u32> = OrdSkipList::ord_with_options(Options {
+            cmp: None,
+            level_bound: None,
+            level_generator: Some(Box::new(
+                DefaultLevelGenerator::with_seed(12, 0.5, 7).unwrap(),
+            )),
+        })
+        .unwrap();
+        l.extend((0..1000).rev());
+        assert!(l.iter().copied().eq(0..1000));
+    }
 }
@@ -38,13 +38,29 @@
 //! **Failure to satisfy these properties can result in unexpected behavior at
 //! best, and at worst will cause a segfault, null deref, or some other bad
 //! behavior.**
+//!
+//! # Thread safety
+//!
+//! The skiplists are `Send` and `Sync` when their elements are, so the
+//! comparators and the level generators they own must be `Send + Sync` as
+//! well. This is a breaking change since 0.0.10: a comparator capturing an
+//! `Rc` or a `RefCell`, or a custom [`GenerateLevel`] which isn't `Send + Sync`,
+//! no longer compiles, and has to use `Arc` and `Mutex` or atomics instead.
+//!
+//! [`GenerateLevel`]: level_generator::GenerateLevel
 
 /// In this library, the notion of 'height' of a node refers to how many links a
 /// node has (as a result, the minimum height is 1).  The 'levels' refer to the
 /// layers in the above diagram, with level 0 being the bottom-most layer, level
 /// 1 being the one above level 0, etc.
+pub use self::concurrent::{ConcurrentSkipList, ConcurrentSkipMap};
+pub use self::dupskiplist::DupSkipList;
 pub use self::ordskiplist::*;
+pub use self::skipmap::SkipMap;
 
+pub mod concurrent;
+pub mod dupskiplist;
 pub mod level_generator;
 pub mod ordskiplist;
+pub mod skipmap;
 mod skipnode;
@@ -3,17 +3,25 @@
 //! Wikipedia: https://en.wikipedia.org/wiki/Skip_list
 
 use std::cmp::Ordering;
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
+use std::iter::FusedIterator;
 use std::marker::PhantomData;
+use std::ops::{Bound, Index, RangeBounds};
 use std::ptr::NonNull;
+use std::sync::Arc;
 use std::{iter, mem};
 
 use crate::collection::error::CollectionError;
+use crate::collection::set_ops::{self, Difference, Intersection, SymmetricDifference, Union};
 use crate::collection::skiplist::level_generator::{DefaultLevelGenerator, GenerateLevel};
-use crate::collection::skiplist::skipnode::{Link, SkipNode};
+use crate::collection::skiplist::skipnode::{Link, Path, RangeEnds, SkipNode};
 
 /// The inner comparator in skiplist
-type Comparator<T> = Box<dyn Fn(&T, &T) -> Ordering>;
+pub(crate) type Comparator<T> = Box<dyn Fn(&T, &T) -> Ordering + Send + Sync>;
+
+/// The comparator used by the set operations of the skiplist.
+pub type SetCmp<T> = dyn Fn(&T, &T) -> Ordering + Send + Sync;
 
 /// The skiplist provides a way of storing elements such that they are
 /// always sorted and at the same time provides efficient way to access, insert
@@ -37,14 +45,15 @@ type Comparator<T> = Box<dyn Fn(&T, &T) -> Ordering>;
 pub struct OrdSkipList<T> {
     length: usize,
     head: NonNull<SkipNode<T>>,
-    cmp: Comparator<T>,
+    // Shared with the skiplists split off from this one.
+    cmp: Arc<SetCmp<T>>,
     level_generator: Box<dyn GenerateLevel>,
     _marker: PhantomData<Box<SkipNode<T>>>,
 }
 
 /// The options to create a skip list
 pub struct Options<T: 'static> {
-    // Custom comparator
+    // Custom comparator, which must be `Send + Sync` since 0.0.10
     pub cmp: Option<Comparator<T>>,
     // Use default level generator, but set different max level(default is 16)
     pub level_bound: Option<usize>,
@@ -53,9 +62,22 @@ pub struct Options<T: 'static> {
 }
 
 impl<T> Options<T> {
+    /// Takes the level generator, or creates a default one with the given
+    /// `level_bound`.
+    ///
+    /// Giving both a level generator and a different `level_bound` is an error.
     pub fn take_level_generator(&mut self) -> Result<Box<dyn GenerateLevel>, CollectionError> {
         match self.level_generator.take() {
-            Some(g) => Ok(g),
+            Some(g) => match self.level_bound {
+                Some(level_bound) if level_bound != g.level_bound() => {
+                    Err(CollectionError::IncompatibleParameters(format!(
+                        "level_bound is {} but the level generator has {}",
+                        level_bound,
+                        g.level_bound()
+                    )))
+                }
+                _ => Ok(g),
+            },
             None => {
                 let g = match self.level_bound {
                     Some(level_bound) => DefaultLevelGenerator::new(level_bound, 1.0 / 2.0)?,
@@ -81,7 +103,7 @@ impl<T: Ord> OrdSkipList<T> {
         let g = DefaultLevelGenerator::default();
         Self {
             length: 0,
-            cmp: Box::new(|x, y| x.cmp(y)),
+            cmp: Arc::new(|x: &T, y: &T| x.cmp(y)),
             head: NonNull::new(Box::into_raw(Box::new(SkipNode::head(g.level_bound())))).unwrap(),
             level_generator: Box::new(g),
             _marker: PhantomData,
@@ -101,7 +123,7 @@ impl<T: Ord> Default for OrdSkipList<T> {
         let g = DefaultLevelGenerator::default();
         Self {
             length: 0,
-            cmp: Box::new(|x, y| x.cmp(y)),
+            cmp: Arc::new(|x: &T, y: &T| x.cmp(y)),
             head: NonNull::new(Box::into_raw(Box::new(SkipNode::head(g.level_bound())))).unwrap(),
             level_generator: Box::new(g),
             _marker: PhantomData,
@@ -109,13 +131,67 @@ impl<T: Ord> Default for OrdSkipList<T> {
     }
 }
 
+impl<T: Ord> OrdSkipList<T> {
+    /// Builds a skiplist from an iterator which yields its elements in
+    /// ascending order.
+    ///
+    /// The towers are wired directly while the elements are appended, which
+    /// computes in *O*(*n*) time. An error is returned if the input is not
+    /// sorted, or if it contains duplicates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use boost_rs::collection::skiplist::OrdSkipList;
+    ///
+    /// let l = OrdSkipList::from_sorted_iter(0..100).unwrap();
+    /// assert_eq!(l.get(42), Some(&42));
+    /// assert!(OrdSkipList::from_sorted_iter(vec![2, 1]).is_err());
+    /// ```
+    pub fn from_sorted_iter<I>(iter: I) -> Result<Self, CollectionError>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut l = Self::new();
+        let mut path = l._tail_path();
+        for data in iter {
+            if let Some(last) = unsafe { path.nodes[0].as_ref().val.as_ref() } {
+                match (l.cmp)(last, &data) {
+                    Ordering::Less => {}
+                    Ordering::Equal => return Err(CollectionError::DuplicateKey),
+                    Ordering::Greater => {
+                        return Err(CollectionError::InvalidParameter(
+                            "input must be sorted in ascending order".to_string(),
+                        ))
+                    }
+                }
+            }
+            l._push_back(&mut path, data);
+        }
+        Ok(l)
+    }
+}
+
 impl<T> OrdSkipList<T> {
+    fn _with_parts(cmp: Arc<SetCmp<T>>, level_generator: Box<dyn GenerateLevel>) -> Self {
+        Self {
+            length: 0,
+            head: NonNull::new(Box::into_raw(Box::new(SkipNode::head(
+                level_generator.level_bound(),
+            ))))
+            .unwrap(),
+            cmp,
+            level_generator,
+            _marker: PhantomData,
+        }
+    }
+
     pub fn with_options(mut options: Options<T>) -> Result<Self, CollectionError> {
         let g = options.take_level_generator()?;
         Ok(Self {
             length: 0,
             head: NonNull::new(Box::into_raw(Box::new(SkipNode::head(g.level_bound())))).unwrap(),
-            cmp: options.take_comparator()?,
+            cmp: Arc::from(options.take_comparator()?),
             level_generator: g,
             _marker: PhantomData,
         })
@@ -123,151 +199,412 @@ impl<T> OrdSkipList<T> {
 
     /// Returns true if the value is contained in the skiplist.
     pub fn contains(&self, v: &T) -> bool {
-        unsafe {
-            let mut cur = self.head.as_ref();
+        self.index_of(v).is_some()
+    }
 
-            for i in (0..=cur.level).rev() {
-                while let Some(next_node) = cur.next[i] {
-                    let next_node = next_node.as_ref();
-                    if (self.cmp)(next_node.val.as_ref().unwrap(), v) == Ordering::Less {
-                        cur = next_node;
-                    } else {
-                        break;
-                    }
-                }
-                if cur.next[i].is_some()
-                    && (self.cmp)(cur.next[i].unwrap().as_ref().val.as_ref().unwrap(), v)
-                        == Ordering::Equal
-                {
-                    return true;
-                }
-            }
+    /// Returns the index of the given value in the skiplist, or `None` if the
+    /// value is not contained.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn index_of(&self, v: &T) -> Option<usize> {
+        let (node, pos) =
+            unsafe { SkipNode::find(self.head, |x, _| (self.cmp)(x, v) == Ordering::Less) };
+        match self._next_val(node) {
+            Some(x) if (self.cmp)(x, v) == Ordering::Equal => Some(pos),
+            _ => None,
         }
-        false
     }
 
     /// Insert a new node by the given data
     pub fn insert(&mut self, data: T) -> Result<(), CollectionError> {
-        if self.contains(&data) {
+        let path = self._path_to(&data);
+        if matches!(self._next_val(path.nodes[0]), Some(x) if (self.cmp)(x, &data) == Ordering::Equal)
+        {
             return Err(CollectionError::DuplicateKey);
         }
 
-        let level = self.level_generator.random();
-        let new_node = Box::new(SkipNode::new(data, level));
-        let mut new_node = NonNull::new(Box::into_raw(new_node));
+        self._link_new(&path, data);
+        Ok(())
+    }
 
-        unsafe {
-            let mut cur = self.head.as_mut();
-            for i in (0..=cur.level).rev() {
-                while let Some(mut next_node) = cur.next[i] {
-                    let next_node = next_node.as_mut();
-                    if (self.cmp)(
-                        next_node.val.as_ref().unwrap(),
-                        new_node.as_ref().unwrap().as_ref().val.as_ref().unwrap(),
-                    ) == Ordering::Less
-                    {
-                        cur = next_node;
-                    } else {
-                        break;
-                    }
-                }
+    /// Peek the front value
+    pub fn peek_front(&self) -> Option<&T> {
+        self._next_val(self.head)
+    }
 
-                if level >= i {
-                    match cur.next[i] {
-                        Some(next) => {
-                            cur.next[i] = new_node;
-                            new_node.as_mut().unwrap().as_mut().next[i] = Some(next);
-                        }
-                        None => {
-                            cur.next[i] = new_node;
+    /// Returns the first element of the skiplist, or `None` if it is empty.
+    ///
+    /// This operation should compute in *O*(1) time.
+    pub fn first(&self) -> Option<&T> {
+        self.peek_front()
+    }
+
+    /// Returns the last element of the skiplist, or `None` if it is empty.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn last(&self) -> Option<&T> {
+        let (node, _) = unsafe { SkipNode::find(self.head, |_, _| true) };
+        unsafe { (*node.as_ptr()).val.as_ref() }
+    }
+
+    /// Returns the first element which is not less than `v`, or `None` if
+    /// there is no such element.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn lower_bound(&self, v: &T) -> Option<&T> {
+        let (node, _) =
+            unsafe { SkipNode::before_start(self.head, Bound::Included(v), &*self.cmp) };
+        self._next_val(node)
+    }
+
+    /// Returns the first element which is greater than `v`, or `None` if
+    /// there is no such element.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn upper_bound(&self, v: &T) -> Option<&T> {
+        let (node, _) =
+            unsafe { SkipNode::before_start(self.head, Bound::Excluded(v), &*self.cmp) };
+        self._next_val(node)
+    }
+
+    /// Returns the element at the given index, or `None` if the index is out
+    /// of range.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn get(&self, idx: usize) -> Option<&T> {
+        if idx >= self.length {
+            return None;
+        }
+
+        let (node, _) = unsafe { SkipNode::find(self.head, |_, pos| pos <= idx + 1) };
+        unsafe { (*node.as_ptr()).val.as_ref() }
+    }
+
+    /// Remove the node that value equals to the given value
+    pub fn remove(&mut self, val: &T) -> Option<T> {
+        let path = self._path_to(val);
+        match self._next_val(path.nodes[0]) {
+            Some(x) if (self.cmp)(x, val) == Ordering::Equal => Some(self._remove_after(&path)),
+            _ => None,
+        }
+    }
+
+    /// Removes the element at the given index and returns it.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn remove_at(&mut self, idx: usize) -> Result<T, CollectionError> {
+        if idx >= self.length {
+            return Err(CollectionError::IndexOutOfRange {
+                index: idx,
+                len: self.length,
+            });
+        }
+
+        let path = unsafe { SkipNode::find_path(self.head, |_, pos| pos <= idx) };
+        Ok(self._remove_after(&path))
+    }
+
+    /// Remove the first element from the skiplist
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.remove_at(0).ok()
+    }
+
+    /// Removes the last element from the skiplist and returns it, or `None` if
+    /// it is empty.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.remove_at(self.length.checked_sub(1)?).ok()
+    }
+
+    /// Moves all elements from `other` into `self`, leaving `other` empty.
+    ///
+    /// Elements of `other` which are already in `self` are dropped. Both
+    /// skiplists must be sorted by the same comparator.
+    ///
+    /// If all the elements of `other` are greater than the ones of `self`, the
+    /// towers of `other` are attached to the end of `self` in *O*(log *n*)
+    /// time. Otherwise both skiplists are merged in *O*(*n* + *m*) time.
+    pub fn append(&mut self, other: &mut Self) {
+        let overlaps = match (self.last(), other.first()) {
+            (Some(last), Some(first)) => (self.cmp)(last, first) != Ordering::Less,
+            _ => false,
+        };
+        if !overlaps {
+            self._attach(other);
+            return;
+        }
+
+        let mut a = self._take_nodes().into_iter().peekable();
+        let mut b = other._take_nodes().into_iter().peekable();
+        let mut path = self._tail_path();
+        loop {
+            let node = match (a.peek(), b.peek()) {
+                (Some(x), Some(y)) => {
+                    let (x, y) = unsafe { (x.as_ref().val.as_ref(), y.as_ref().val.as_ref()) };
+                    match (self.cmp)(x.unwrap(), y.unwrap()) {
+                        Ordering::Less => a.next(),
+                        Ordering::Greater => b.next(),
+                        Ordering::Equal => {
+                            unsafe { drop(Box::from_raw(b.next().unwrap().as_ptr())) };
+                            a.next()
                         }
                     }
                 }
+                (Some(_), None) => a.next(),
+                (None, _) => b.next(),
+            };
+            match node {
+                Some(node) => self._push_back_node(&mut path, node),
+                None => break,
+            }
+        }
+    }
+
+    /// Splits the skiplist into two at the given key. Returns everything after
+    /// the given key, including the key.
+    ///
+    /// The returned skiplist shares the comparator of `self`, and uses the
+    /// level generator made by [`GenerateLevel::box_clone`].
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use boost_rs::collection::skiplist::OrdSkipList;
+    ///
+    /// let mut a: OrdSkipList<i32> = (1..=5).collect();
+    /// let b = a.split_off(&3);
+    /// assert_eq!(a.iter().copied().collect::<Vec<_>>(), vec![1, 2]);
+    /// assert_eq!(b.iter().copied().collect::<Vec<_>>(), vec![3, 4, 5]);
+    /// ```
+    pub fn split_off(&mut self, key: &T) -> Self {
+        let mut other = Self::_with_parts(self.cmp.clone(), self._sibling_generator());
+
+        let path = self._path_to(key);
+        let offset = path.positions[0];
+        unsafe {
+            let head = &mut *other.head.as_ptr();
+            for (i, (prev, &pos)) in path.nodes.iter().zip(&path.positions).enumerate() {
+                let prev = &mut *prev.as_ptr();
+                if let Some(next) = prev.next[i].take() {
+                    head.next[i] = Some(next);
+                    head.links_len[i] = pos + prev.links_len[i] - offset;
+                    prev.links_len[i] = 0;
+                }
+            }
+            if let Some(first) = head.next[0] {
+                (*first.as_ptr()).prev = Some(other.head);
+            }
+        }
+
+        other.length = self.length - offset;
+        self.length = offset;
+        other
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// The skiplist is relinked in a single pass, which computes in *O*(*n*)
+    /// time.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let nodes = self._take_nodes();
+        let mut path = self._tail_path();
+        for node in nodes {
+            if f(unsafe { node.as_ref().val.as_ref().unwrap() }) {
+                self._push_back_node(&mut path, node);
+            } else {
+                unsafe { drop(Box::from_raw(node.as_ptr())) };
             }
         }
+    }
+
+    /// Inserts `data` after all the elements equal to it, so that equal
+    /// elements keep their insertion order.
+    pub(crate) fn _insert_dup(&mut self, data: T) {
+        let path = unsafe {
+            SkipNode::find_path(self.head, |x, _| (self.cmp)(x, &data) != Ordering::Greater)
+        };
+        self._link_new(&path, data);
+    }
+
+    /// Returns the range of indices of the elements equal to `v`.
+    pub(crate) fn _equal_range(&self, v: &T) -> std::ops::Range<usize> {
+        let (_, start) =
+            unsafe { SkipNode::before_start(self.head, Bound::Included(v), &*self.cmp) };
+        let (_, end) = unsafe { SkipNode::last_within(self.head, Bound::Included(v), &*self.cmp) };
+        start..end.max(start)
+    }
 
+    /// Removes all the elements equal to `v`, and returns them in order.
+    pub(crate) fn _remove_equals(&mut self, v: &T) -> Vec<T> {
+        let path = self._path_to(v);
+        let mut removed = Vec::new();
+        while matches!(self._next_val(path.nodes[0]), Some(x) if (self.cmp)(x, v) == Ordering::Equal)
+        {
+            // The path stays valid, as the next equal element moves into the
+            // position of the removed one.
+            removed.push(self._remove_after(&path));
+        }
+        removed
+    }
+
+    /// Links a new node of `data` right after the node where `path` ends.
+    fn _link_new(&mut self, path: &Path<T>, data: T) {
+        let level = self.level_generator.random();
+        let new_node = NonNull::from(Box::leak(Box::new(SkipNode::new(data, level))));
+        unsafe {
+            SkipNode::link(path, new_node);
+        }
         self.length += 1;
+    }
+
+    /// Creates a level generator for a new skiplist sharing the comparator of
+    /// this one, see [`GenerateLevel::box_clone`].
+    fn _sibling_generator(&self) -> Box<dyn GenerateLevel> {
+        self.level_generator.box_clone()
+    }
 
+    /// Finds the path to the end of the skiplist.
+    fn _tail_path(&self) -> Path<T> {
+        unsafe { SkipNode::find_path(self.head, |_, _| true) }
+    }
+
+    /// Inserts all the elements of the iterator, the ones greater than the
+    /// last element are appended directly at the end.
+    fn _try_extend<I: IntoIterator<Item = T>>(
+        &mut self,
+        iterable: I,
+    ) -> Result<(), CollectionError> {
+        let mut tail: Option<Path<T>> = None;
+        for element in iterable {
+            let path = tail.get_or_insert_with(|| self._tail_path());
+            let after_last = match unsafe { path.nodes[0].as_ref().val.as_ref() } {
+                Some(last) => (self.cmp)(last, &element) == Ordering::Less,
+                None => true,
+            };
+            if after_last {
+                self._push_back(path, element);
+            } else {
+                tail = None;
+                self.insert(element)?;
+            }
+        }
         Ok(())
     }
 
-    /// Peek the front value
-    pub fn peek_front(&self) -> Option<&T> {
-        unsafe {
-            let first_node = self.head.as_ref().next[0].as_ref();
-            match first_node {
-                Some(node) => node.as_ref().val.as_ref(),
-                None => None,
+    /// Like [`OrdSkipList::_try_extend`], but places the elements after the
+    /// ones equal to them instead of failing.
+    pub(crate) fn _extend_dup<I: IntoIterator<Item = T>>(&mut self, iterable: I) {
+        let mut tail: Option<Path<T>> = None;
+        for element in iterable {
+            let path = tail.get_or_insert_with(|| self._tail_path());
+            let after_last = match unsafe { path.nodes[0].as_ref().val.as_ref() } {
+                Some(last) => (self.cmp)(last, &element) != Ordering::Greater,
+                None => true,
+            };
+            if after_last {
+                self._push_back(path, element);
+            } else {
+                tail = None;
+                self._insert_dup(element);
             }
         }
     }
 
-    /// Remove the node that value equals to the given value
-    pub fn remove(&mut self, val: &T) -> Option<T> {
-        if !self.contains(val) {
-            return None;
+    /// Appends a new node of `data` at the end of the skiplist, where `path`
+    /// ends, and moves `path` to the new node.
+    fn _push_back(&mut self, path: &mut Path<T>, data: T) {
+        let level = self.level_generator.random();
+        let node = NonNull::from(Box::leak(Box::new(SkipNode::new(data, level))));
+        self._push_back_node(path, node);
+    }
+
+    /// Appends a detached node at the end of the skiplist, where `path` ends,
+    /// and moves `path` to the node.
+    fn _push_back_node(&mut self, path: &mut Path<T>, node: NonNull<SkipNode<T>>) {
+        let pos = path.positions[0] + 1;
+        unsafe {
+            let n = &mut *node.as_ptr();
+            n.next.iter_mut().for_each(|next| *next = None);
+            n.links_len.iter_mut().for_each(|len| *len = 0);
+            SkipNode::link(path, node);
+            for i in 0..=n.level.min(path.nodes.len() - 1) {
+                path.nodes[i] = node;
+                path.positions[i] = pos;
+            }
         }
+        self.length += 1;
+    }
 
-        let mut cur = unsafe { self.head.as_mut() };
-        let max_level = cur.level;
-        let mut update: Vec<Option<*mut SkipNode<T>>> = vec![None; max_level + 1];
-        let ret_val;
+    /// Detaches all the nodes from the skiplist, and returns them in order.
+    fn _take_nodes(&mut self) -> Vec<NonNull<SkipNode<T>>> {
+        let mut nodes = Vec::with_capacity(self.length);
         unsafe {
-            for i in (0..=max_level).rev() {
-                while let Some(mut next_node) = cur.next[i] {
-                    let next_node = next_node.as_mut();
-                    if (self.cmp)(next_node.val.as_ref().unwrap(), val) == Ordering::Less {
-                        cur = next_node;
-                    } else {
-                        break;
-                    }
-                }
-                update[i] = Some(cur as *mut SkipNode<T>);
+            let head = &mut *self.head.as_ptr();
+            let mut cur = head.next[0];
+            while let Some(node) = cur {
+                nodes.push(node);
+                cur = node.as_ref().next[0];
             }
+            head.next.iter_mut().for_each(|next| *next = None);
+            head.links_len.iter_mut().for_each(|len| *len = 0);
+        }
+        self.length = 0;
+        nodes
+    }
 
-            let mut ret_val_ref = None;
-            if cur.next[0].is_some()
-                && (self.cmp)(cur.next[0].unwrap().as_ref().val.as_ref().unwrap(), val)
-                    == Ordering::Equal
-            {
-                ret_val_ref = cur.next[0];
-                for i in (0..=max_level).rev() {
-                    if update[i].is_some()
-                        && (*update[i].unwrap()).next[i].is_some()
-                        && (self.cmp)(
-                            (*update[i].unwrap()).next[i]
-                                .unwrap()
-                                .as_mut()
-                                .val
-                                .as_ref()
-                                .unwrap(),
-                            val,
-                        ) == Ordering::Equal
-                    {
-                        (*update[i].unwrap()).next[i] =
-                            (*update[i].unwrap()).next[i].unwrap().as_mut().next[i];
-                    }
+    /// Attaches the towers of `other` to the end of `self`, leaving `other`
+    /// empty. All the elements of `other` must be greater than the ones of `self`.
+    fn _attach(&mut self, other: &mut Self) {
+        let path = self._tail_path();
+        unsafe {
+            let other_head = &mut *other.head.as_ptr();
+            for (i, (prev, &pos)) in path.nodes.iter().zip(&path.positions).enumerate() {
+                let prev = &mut *prev.as_ptr();
+                // Towers of `other` above the levels of `self` are not
+                // reachable anymore, the nodes are still linked below.
+                if let Some(next) = other_head.next.get_mut(i).and_then(Option::take) {
+                    prev.next[i] = Some(next);
+                    prev.links_len[i] = self.length - pos + other_head.links_len[i];
                 }
             }
-            ret_val = match ret_val_ref {
-                None => None,
-                Some(ret) => Box::from_raw(ret.as_ptr()).into_val(),
+            if let Some(first) = path.nodes[0].as_ref().next[0] {
+                (*first.as_ptr()).prev = Some(path.nodes[0]);
             }
+            other_head.next.iter_mut().for_each(|next| *next = None);
+            other_head.links_len.iter_mut().for_each(|len| *len = 0);
         }
+        self.length += other.length;
+        other.length = 0;
+    }
 
-        self.length -= 1;
+    /// Finds the path to the first element which is not less than `v`.
+    fn _path_to(&self, v: &T) -> Path<T> {
+        unsafe { SkipNode::find_path(self.head, |x, _| (self.cmp)(x, v) == Ordering::Less) }
+    }
 
-        ret_val
+    /// Returns the first and the last node within `range`, and the number of
+    /// elements between them.
+    fn _range_ends<R: RangeBounds<T>>(&self, range: &R) -> RangeEnds<T> {
+        unsafe { SkipNode::range_ends(self.head, range, &*self.cmp) }
     }
 
-    /// Remove the first element from the skiplist
-    pub fn pop_front(&mut self) -> Option<T> {
+    /// Returns the value of the node right after the given node.
+    fn _next_val(&self, node: NonNull<SkipNode<T>>) -> Option<&T> {
+        unsafe { node.as_ref().next[0].and_then(|next| (*next.as_ptr()).val.as_ref()) }
+    }
+
+    /// Removes the node right after the node where `path` ends, which must exist.
+    fn _remove_after(&mut self, path: &Path<T>) -> T {
+        self.length -= 1;
         unsafe {
-            let first_node = self.head.as_ref().next[0].as_ref();
-            match first_node {
-                Some(node) => self.remove(node.as_ref().val.as_ref().unwrap()),
-                None => None,
-            }
+            let node = SkipNode::unlink(path);
+            Box::from_raw(node.as_ptr()).into_val().unwrap()
         }
     }
 
@@ -288,25 +625,101 @@ impl<T> OrdSkipList<T> {
         self.length
     }
 
-    pub fn iter(&self) -> Iter<T> {
-        let node = unsafe { self.head.as_ref().next[0] };
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (front, back, len) = self._range_ends(&..);
+        Iter::new(front, back, len)
+    }
 
-        Iter {
-            head: node,
-            len: self.length,
-            _marker: PhantomData,
-        }
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let (front, back, len) = self._range_ends(&..);
+        IterMut::new(front, back, len)
     }
 
-    pub fn iter_mut(&mut self) -> IterMut<T> {
-        let node = unsafe { self.head.as_ref().next[0] };
+    /// Constructs a double-ended iterator over the elements within `range`, in
+    /// ascending order.
+    ///
+    /// The bounds are compared with the comparator of the skiplist. If the
+    /// start bound lies after the end bound, the iterator is empty.
+    ///
+    /// Locating both ends of the range should compute in *O*(log *n*) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use boost_rs::collection::skiplist::OrdSkipList;
+    ///
+    /// let l: OrdSkipList<i32> = (1..10).collect();
+    /// assert_eq!(l.range(3..6).copied().collect::<Vec<_>>(), vec![3, 4, 5]);
+    /// assert_eq!(l.range(..=2).rev().copied().collect::<Vec<_>>(), vec![2, 1]);
+    /// ```
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Range<'_, T> {
+        let (front, back, len) = self._range_ends(&range);
+        Range {
+            iter: Iter::new(front, back, len),
+        }
+    }
 
-        IterMut {
-            head: node,
-            len: self.length,
-            _marker: PhantomData,
+    /// Constructs a mutable double-ended iterator over the elements within
+    /// `range`, in ascending order.
+    ///
+    /// The elements must not be modified in a way that changes their order.
+    pub fn range_mut<R: RangeBounds<T>>(&mut self, range: R) -> RangeMut<'_, T> {
+        let (front, back, len) = self._range_ends(&range);
+        RangeMut {
+            iter: IterMut::new(front, back, len),
         }
     }
+
+    /// Visits the elements representing the union, i.e., all the elements in
+    /// `self` or `other`, in ascending order.
+    ///
+    /// Both skiplists must be sorted by the same comparator, the one of `self`
+    /// is used for merging.
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<'a, T, Iter<'a, T>, &'a SetCmp<T>> {
+        Union::new(self.iter(), other.iter(), &*self.cmp)
+    }
+
+    /// Visits the elements representing the intersection, i.e., the elements
+    /// that are both in `self` and `other`, in ascending order.
+    pub fn intersection<'a>(
+        &'a self,
+        other: &'a Self,
+    ) -> Intersection<'a, T, Iter<'a, T>, &'a SetCmp<T>> {
+        Intersection::new(self.iter(), other.iter(), &*self.cmp)
+    }
+
+    /// Visits the elements representing the difference, i.e., the elements
+    /// that are in `self` but not in `other`, in ascending order.
+    pub fn difference<'a>(
+        &'a self,
+        other: &'a Self,
+    ) -> Difference<'a, T, Iter<'a, T>, &'a SetCmp<T>> {
+        Difference::new(self.iter(), other.iter(), &*self.cmp)
+    }
+
+    /// Visits the elements representing the symmetric difference, i.e., the
+    /// elements that are in `self` or in `other` but not in both, in ascending order.
+    pub fn symmetric_difference<'a>(
+        &'a self,
+        other: &'a Self,
+    ) -> SymmetricDifference<'a, T, Iter<'a, T>, &'a SetCmp<T>> {
+        SymmetricDifference::new(self.iter(), other.iter(), &*self.cmp)
+    }
+
+    /// Returns `true` if `self` is a subset of `other`, i.e., `other` contains
+    /// at least all the elements in `self`.
+    ///
+    /// This operation should compute in *O*(*n* + *m*) time.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.length <= other.length && set_ops::is_subset(self.iter(), other.iter(), &*self.cmp)
+    }
+
+    /// Returns `true` if `self` has no elements in common with `other`.
+    ///
+    /// This operation should compute in *O*(*n* + *m*) time.
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        set_ops::is_disjoint(self.iter(), other.iter(), &*self.cmp)
+    }
 }
 
 impl<T> Drop for OrdSkipList<T> {
@@ -326,26 +739,111 @@ impl<T> Drop for OrdSkipList<T> {
             drop(node);
             mem::forget(guard);
         }
+        unsafe { drop(Box::from_raw(self.head.as_ptr())) };
         // println!("OrdSkipList dropped!")
     }
 }
 
-impl<T: Debug> OrdSkipList<T> {
-    pub fn print(&self) {
-        print!("[");
-        self.iter().for_each(|i| {
-            print!("{:?}, ", i);
-        });
-        println!("]");
+// The skiplist owns its nodes, and both the comparator and the level generator
+// are `Send + Sync`.
+unsafe impl<T: Send> Send for OrdSkipList<T> {}
+
+unsafe impl<T: Sync> Sync for OrdSkipList<T> {}
+
+impl<T: Clone> Clone for OrdSkipList<T> {
+    /// Clones the skiplist in *O*(*n*) time.
+    ///
+    /// The clone shares the comparator of `self`, and uses the level generator
+    /// made by [`GenerateLevel::box_clone`].
+    fn clone(&self) -> Self {
+        let mut l = Self::_with_parts(self.cmp.clone(), self._sibling_generator());
+        let mut path = l._tail_path();
+        for x in self.iter() {
+            l._push_back(&mut path, x.clone());
+        }
+        l
+    }
+}
+
+impl<T: Debug> Debug for OrdSkipList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Two skiplists are equal if they contain equal elements in the same order,
+/// regardless of their comparators.
+impl<T: PartialEq> PartialEq for OrdSkipList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.length == other.length && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for OrdSkipList<T> {}
+
+impl<T: Hash> Hash for OrdSkipList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.length);
+        for x in self.iter() {
+            x.hash(state);
+        }
+    }
+}
+
+/// Skiplists are compared lexicographically by their elements.
+impl<T: PartialOrd> PartialOrd for OrdSkipList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for OrdSkipList<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T> Index<usize> for OrdSkipList<T> {
+    type Output = T;
+
+    /// Returns the element at the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of range.
+    fn index(&self, idx: usize) -> &T {
+        self.get(idx).expect("index out of range")
     }
 }
 
 pub struct Iter<'a, T: 'a> {
-    head: Link<T>,
+    front: Link<T>,
+    back: Link<T>,
     len: usize,
     _marker: PhantomData<&'a SkipNode<T>>,
 }
 
+unsafe impl<'a, T: Sync> Send for Iter<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter::new(self.front, self.back, self.len)
+    }
+}
+
+impl<'a, T> Iter<'a, T> {
+    pub(crate) fn new(front: Link<T>, back: Link<T>, len: usize) -> Self {
+        Iter {
+            front,
+            back,
+            len,
+            _marker: PhantomData,
+        }
+    }
+}
+
 impl<'a, T> Iterator for Iter<'a, T> {
     type Item = &'a T;
 
@@ -354,13 +852,13 @@ impl<'a, T> Iterator for Iter<'a, T> {
         if self.len == 0 {
             None
         } else {
-            match self.head {
+            match self.front {
                 Some(node) => {
                     self.len -= 1;
 
                     unsafe {
                         let node = &*node.as_ptr();
-                        self.head = node.next[0];
+                        self.front = node.next[0];
                         node.val.as_ref()
                     }
                 }
@@ -375,12 +873,54 @@ impl<'a, T> Iterator for Iter<'a, T> {
     }
 }
 
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            None
+        } else {
+            match self.back {
+                Some(node) => {
+                    self.len -= 1;
+
+                    unsafe {
+                        let node = &*node.as_ptr();
+                        self.back = node.prev;
+                        node.val.as_ref()
+                    }
+                }
+                None => None,
+            }
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
 pub struct IterMut<'a, T: 'a> {
-    head: Link<T>,
+    front: Link<T>,
+    back: Link<T>,
     len: usize,
     _marker: PhantomData<&'a mut SkipNode<T>>,
 }
 
+unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
+
+impl<'a, T> IterMut<'a, T> {
+    pub(crate) fn new(front: Link<T>, back: Link<T>, len: usize) -> Self {
+        IterMut {
+            front,
+            back,
+            len,
+            _marker: PhantomData,
+        }
+    }
+}
+
 impl<'a, T> Iterator for IterMut<'a, T> {
     type Item = &'a mut T;
 
@@ -389,13 +929,13 @@ impl<'a, T> Iterator for IterMut<'a, T> {
         if self.len == 0 {
             None
         } else {
-            match self.head {
+            match self.front {
                 Some(node) => {
                     self.len -= 1;
 
                     unsafe {
                         let node = &mut *node.as_ptr();
-                        self.head = node.next[0];
+                        self.front = node.next[0];
                         node.val.as_mut()
                     }
                 }
@@ -410,6 +950,94 @@ impl<'a, T> Iterator for IterMut<'a, T> {
     }
 }
 
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            None
+        } else {
+            match self.back {
+                Some(node) => {
+                    self.len -= 1;
+
+                    unsafe {
+                        let node = &mut *node.as_ptr();
+                        self.back = node.prev;
+                        node.val.as_mut()
+                    }
+                }
+                None => None,
+            }
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<'a, T> FusedIterator for IterMut<'a, T> {}
+
+/// An iterator over a sub-range of the elements in a skiplist, created by
+/// [`OrdSkipList::range`].
+pub struct Range<'a, T: 'a> {
+    iter: Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Range<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Range<'a, T> {}
+
+impl<'a, T> FusedIterator for Range<'a, T> {}
+
+/// A mutable iterator over a sub-range of the elements in a skiplist, created
+/// by [`OrdSkipList::range_mut`].
+pub struct RangeMut<'a, T: 'a> {
+    iter: IterMut<'a, T>,
+}
+
+impl<'a, T> Iterator for RangeMut<'a, T> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for RangeMut<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for RangeMut<'a, T> {}
+
+impl<'a, T> FusedIterator for RangeMut<'a, T> {}
+
 pub struct IntoIter<T> {
     list: OrdSkipList<T>,
 }
@@ -428,6 +1056,17 @@ impl<T> Iterator for IntoIter<T> {
     }
 }
 
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
 impl<T> IntoIterator for OrdSkipList<T> {
     type Item = T;
     type IntoIter = IntoIter<T>;
@@ -448,12 +1087,24 @@ impl<T> Drop for IntoIter<T> {
 }
 
 impl<T> Extend<T> for OrdSkipList<T> {
+    /// Inserts all the elements of the iterator.
+    ///
+    /// Elements greater than the last element are appended directly at the
+    /// end, so extending by sorted input computes in *O*(*n*) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an element is already in the skiplist.
     #[inline]
     fn extend<I: IntoIterator<Item = T>>(&mut self, iterable: I) {
-        let iterator = iterable.into_iter();
-        for element in iterator {
-            self.insert(element).unwrap();
-        }
+        self._try_extend(iterable).unwrap()
+    }
+}
+
+impl<'a, T: 'a + Copy> Extend<&'a T> for OrdSkipList<T> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iterable: I) {
+        self.extend(iterable.into_iter().copied());
     }
 }
 
@@ -472,9 +1123,42 @@ where
     }
 }
 
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::collection::skiplist::OrdSkipList;
+
+    impl<T: Serialize> Serialize for OrdSkipList<T> {
+        /// Serializes the elements as a sequence, in ascending order.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.iter())
+        }
+    }
+
+    impl<'de, T: Deserialize<'de> + Ord> Deserialize<'de> for OrdSkipList<T> {
+        /// Deserializes a sequence of elements, duplicates are rejected.
+        ///
+        /// A sorted sequence is built in *O*(*n*) time.
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let elements = Vec::<T>::deserialize(deserializer)?;
+            let mut l = OrdSkipList::new();
+            l._try_extend(elements).map_err(D::Error::custom)?;
+            Ok(l)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::collection::skiplist::level_generator::DefaultLevelGenerator;
+    use std::fmt::Debug;
+    use std::iter;
+
+    use crate::collection::error::CollectionError;
+    use crate::collection::skiplist::level_generator::{
+        DefaultLevelGenerator, GenerateLevel, PerfectLevelGenerator,
+    };
     use crate::collection::skiplist::{Options, OrdSkipList};
 
     #[test]
@@ -517,6 +1201,20 @@ mod tests {
         assert_eq!(sl.length, 0);
     }
 
+    #[test]
+    fn ord_with_options_incompatible() {
+        let options = |level_bound| Options::<i32> {
+            cmp: None,
+            level_bound: Some(level_bound),
+            level_generator: Some(Box::new(DefaultLevelGenerator::new(4, 0.5).unwrap())),
+        };
+        assert!(OrdSkipList::ord_with_options(options(4)).is_ok());
+        assert!(matches!(
+            OrdSkipList::ord_with_options(options(8)),
+            Err(CollectionError::IncompatibleParameters(_))
+        ));
+    }
+
     #[test]
     fn with_options() {
         struct Foo {
@@ -648,4 +1346,459 @@ mod tests {
         // Compiling err:
         // l.print();
     }
+
+    #[test]
+    fn set_operations() {
+        let a: OrdSkipList<i32> = vec![1, 3, 5, 7, 9].into_iter().collect();
+        let b: OrdSkipList<i32> = vec![3, 4, 5, 6].into_iter().collect();
+
+        assert_eq!(
+            a.union(&b).copied().collect::<Vec<_>>(),
+            vec![1, 3, 4, 5, 6, 7, 9]
+        );
+        assert_eq!(a.intersection(&b).copied().collect::<Vec<_>>(), vec![3, 5]);
+        assert_eq!(a.difference(&b).copied().collect::<Vec<_>>(), vec![1, 7, 9]);
+        assert_eq!(
+            a.symmetric_difference(&b).copied().collect::<Vec<_>>(),
+            vec![1, 4, 6, 7, 9]
+        );
+
+        let c: OrdSkipList<i32> = vec![3, 9].into_iter().collect();
+        assert!(c.is_subset(&a));
+        assert!(!a.is_subset(&c));
+        assert!(!a.is_disjoint(&b));
+        assert!(!c.is_disjoint(&b));
+        assert!(c.is_disjoint(&vec![2, 4].into_iter().collect()));
+    }
+
+    #[test]
+    fn index_access() {
+        let mut l: OrdSkipList<i32> = OrdSkipList::new();
+        for i in (0..100).rev() {
+            l.insert(i * 2).unwrap();
+        }
+
+        for i in 0..100 {
+            assert_eq!(l.get(i as usize), Some(&(i * 2)));
+            assert_eq!(l.index_of(&(i * 2)), Some(i as usize));
+            assert_eq!(l.index_of(&(i * 2 + 1)), None);
+        }
+        assert_eq!(l.get(100), None);
+
+        assert_eq!(l.remove_at(10).unwrap(), 20);
+        assert_eq!(l.get(10), Some(&22));
+        assert_eq!(l.index_of(&22), Some(10));
+        assert_eq!(l.length(), 99);
+        assert!(matches!(
+            l.remove_at(99),
+            Err(CollectionError::IndexOutOfRange { index: 99, len: 99 })
+        ));
+    }
+
+    #[test]
+    fn first_and_last() {
+        let mut l: OrdSkipList<i32> = OrdSkipList::new();
+        assert_eq!(l.first(), None);
+        assert_eq!(l.last(), None);
+        assert_eq!(l.pop_back(), None);
+
+        for i in [5, 1, 9, 3, 7] {
+            l.insert(i).unwrap();
+        }
+        assert_eq!(l.first(), Some(&1));
+        assert_eq!(l.last(), Some(&9));
+
+        assert_eq!(l.pop_back(), Some(9));
+        assert_eq!(l.pop_front(), Some(1));
+        assert_eq!(l.last(), Some(&7));
+        assert_eq!(l.iter().copied().collect::<Vec<_>>(), vec![3, 5, 7]);
+    }
+
+    #[test]
+    fn random_index_consistency() {
+        use crate::collection::test_util::random_ops;
+        use rand::Rng;
+
+        let mut l: OrdSkipList<u32> = OrdSkipList::new();
+        let mut v: Vec<u32> = Vec::new();
+        random_ops(2000, |rng, _| {
+            let x = rng.gen_range(0..500);
+            match v.binary_search(&x) {
+                Ok(i) => {
+                    if rng.gen_bool(0.5) {
+                        assert_eq!(l.remove(&x), Some(v.remove(i)));
+                    } else {
+                        assert_eq!(l.remove_at(i).unwrap(), v.remove(i));
+                    }
+                }
+                Err(i) => {
+                    l.insert(x).unwrap();
+                    v.insert(i, x);
+                }
+            }
+            let idx = rng.gen_range(0..v.len().max(1));
+            assert_eq!(l.get(idx), v.get(idx));
+        });
+
+        assert_eq!(l.length(), v.len());
+        assert!(l.iter().rev().eq(v.iter().rev()));
+        for (i, x) in v.iter().enumerate() {
+            assert_eq!(l.index_of(x), Some(i));
+            assert_eq!(l.get(i), Some(x));
+        }
+    }
+
+    #[test]
+    fn double_ended_iter() {
+        let mut l: OrdSkipList<i32> = (0..10).collect();
+        assert_eq!(
+            l.iter().rev().copied().collect::<Vec<_>>(),
+            (0..10).rev().collect::<Vec<_>>()
+        );
+
+        let mut it = l.iter();
+        assert_eq!(it.next(), Some(&0));
+        assert_eq!(it.next_back(), Some(&9));
+        assert_eq!(it.size_hint(), (8, Some(8)));
+        assert_eq!(it.by_ref().rev().take(7).count(), 7);
+        assert_eq!(it.next(), Some(&1));
+        assert_eq!(it.next_back(), None);
+
+        l.iter_mut().rev().for_each(|x| *x *= 2);
+        l.remove(&8).unwrap();
+        l.pop_back().unwrap();
+        assert_eq!(
+            l.iter().rev().copied().collect::<Vec<_>>(),
+            vec![16, 14, 12, 10, 6, 4, 2, 0]
+        );
+    }
+
+    #[test]
+    fn range() {
+        use std::ops::Bound::{Excluded, Included, Unbounded};
+
+        let mut l: OrdSkipList<i32> = (0..20).map(|x| x * 10).collect();
+        let collect = |l: &OrdSkipList<i32>, r: (_, _)| l.range(r).copied().collect::<Vec<_>>();
+
+        assert_eq!(
+            l.range(30..60).copied().collect::<Vec<_>>(),
+            vec![30, 40, 50]
+        );
+        assert_eq!(
+            l.range(25..=60).copied().collect::<Vec<_>>(),
+            vec![30, 40, 50, 60]
+        );
+        assert_eq!(l.range(..20).copied().collect::<Vec<_>>(), vec![0, 10]);
+        assert_eq!(l.range(175..).copied().collect::<Vec<_>>(), vec![180, 190]);
+        assert_eq!(l.range(..).count(), 20);
+        assert_eq!(collect(&l, (Excluded(30), Included(60))), vec![40, 50, 60]);
+        assert_eq!(collect(&l, (Excluded(185), Unbounded)), vec![190]);
+        assert_eq!(collect(&l, (Excluded(190), Unbounded)), Vec::<i32>::new());
+        assert_eq!(l.range(61..69).count(), 0);
+        assert_eq!(collect(&l, (Included(60), Excluded(30))), Vec::<i32>::new());
+        assert_eq!(l.range(60..60).count(), 0);
+        assert_eq!(l.range(-10..-1).count(), 0);
+
+        let r = l.range(30..=90);
+        assert_eq!(r.size_hint(), (7, Some(7)));
+        assert_eq!(
+            r.rev().copied().collect::<Vec<_>>(),
+            vec![90, 80, 70, 60, 50, 40, 30]
+        );
+
+        l.range_mut(100..).for_each(|x| *x += 1);
+        assert_eq!(l.range(95..).next(), Some(&101));
+        assert_eq!(l.range_mut(..=10).next_back().map(|x| *x), Some(10));
+        assert_eq!(l.length(), 20);
+    }
+
+    #[test]
+    fn bounds() {
+        let l: OrdSkipList<i32> = vec![1, 3, 5, 7].into_iter().collect();
+
+        assert_eq!(l.lower_bound(&0), Some(&1));
+        assert_eq!(l.lower_bound(&3), Some(&3));
+        assert_eq!(l.lower_bound(&4), Some(&5));
+        assert_eq!(l.lower_bound(&8), None);
+
+        assert_eq!(l.upper_bound(&0), Some(&1));
+        assert_eq!(l.upper_bound(&3), Some(&5));
+        assert_eq!(l.upper_bound(&7), None);
+
+        let empty: OrdSkipList<i32> = OrdSkipList::new();
+        assert_eq!(empty.lower_bound(&0), None);
+        assert_eq!(empty.range(..).next_back(), None);
+    }
+
+    fn assert_links<T: PartialEq + Debug>(l: &OrdSkipList<T>, v: &[T]) {
+        assert_eq!(l.length(), v.len());
+        assert!(l.iter().eq(v.iter()));
+        assert!(l.iter().rev().eq(v.iter().rev()));
+        for (i, x) in v.iter().enumerate() {
+            assert_eq!(l.get(i), Some(x));
+        }
+        assert_eq!(l.get(v.len()), None);
+    }
+
+    #[test]
+    fn from_sorted_iter() {
+        let l = OrdSkipList::from_sorted_iter(0..1000).unwrap();
+        assert_links(&l, &(0..1000).collect::<Vec<_>>());
+        assert_eq!(l.index_of(&500), Some(500));
+
+        let empty = OrdSkipList::<i32>::from_sorted_iter(iter::empty()).unwrap();
+        assert!(empty.is_empty());
+
+        assert!(matches!(
+            OrdSkipList::from_sorted_iter(vec![1, 2, 2]),
+            Err(CollectionError::DuplicateKey)
+        ));
+        assert!(matches!(
+            OrdSkipList::from_sorted_iter(vec![1, 3, 2]),
+            Err(CollectionError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn extend_sorted_and_unsorted() {
+        let mut l: OrdSkipList<i32> = (0..100).map(|x| x * 2).collect();
+        l.extend(vec![5, 7, 300, 301, 1, 302]);
+        let mut v: Vec<_> = (0..100).map(|x| x * 2).collect();
+        v.extend(vec![5, 7, 300, 301, 1, 302]);
+        v.sort_unstable();
+        assert_links(&l, &v);
+    }
+
+    #[test]
+    fn append() {
+        // Disjoint ranges are attached.
+        let mut a: OrdSkipList<i32> = (0..50).collect();
+        let mut b: OrdSkipList<i32> = (50..120).collect();
+        a.append(&mut b);
+        assert!(b.is_empty());
+        assert_links(&a, &(0..120).collect::<Vec<_>>());
+
+        // The emptied skiplist is still usable.
+        b.insert(7).unwrap();
+        assert_links(&b, &[7]);
+
+        // Overlapping ones are merged, dropping the duplicates of `other`.
+        let mut a: OrdSkipList<i32> = (0..60).filter(|x| x % 3 == 0).collect();
+        let mut b: OrdSkipList<i32> = (0..60).filter(|x| x % 2 == 0).collect();
+        a.append(&mut b);
+        assert!(b.is_empty());
+        let v: Vec<_> = (0..60).filter(|x| x % 3 == 0 || x % 2 == 0).collect();
+        assert_links(&a, &v);
+
+        let mut empty = OrdSkipList::new();
+        empty.append(&mut a);
+        assert!(a.is_empty());
+        assert_links(&empty, &v);
+        empty.append(&mut a);
+        assert_links(&empty, &v);
+    }
+
+    #[test]
+    fn split_off() {
+        for key in [-1, 0, 1, 37, 99, 100, 150] {
+            let mut a: OrdSkipList<i32> = (0..100).collect();
+            let b = a.split_off(&key);
+            let at = key.clamp(0, 100);
+            assert_links(&a, &(0..at).collect::<Vec<_>>());
+            assert_links(&b, &(at..100).collect::<Vec<_>>());
+        }
+
+        // Both halves keep working as regular skiplists.
+        let mut a: OrdSkipList<i32> = (0..10).map(|x| x * 10).collect();
+        let mut b = a.split_off(&45);
+        a.insert(44).unwrap();
+        b.insert(46).unwrap();
+        assert_eq!(b.remove(&90), Some(90));
+        assert_links(&a, &[0, 10, 20, 30, 40, 44]);
+        assert_links(&b, &[46, 50, 60, 70, 80]);
+
+        a.append(&mut b);
+        assert_links(&a, &[0, 10, 20, 30, 40, 44, 46, 50, 60, 70, 80]);
+    }
+
+    #[test]
+    fn split_off_with_cmp() {
+        let mut a = OrdSkipList::with_options(Options {
+            cmp: Some(Box::new(|x: &i32, y: &i32| y.cmp(x))),
+            level_bound: Some(4),
+            level_generator: None,
+        })
+        .unwrap();
+        a.extend(0..20);
+        let mut b = a.split_off(&10);
+        assert_links(&a, &(11..20).rev().collect::<Vec<_>>());
+        b.insert(5).unwrap_err();
+        b.insert(-1).unwrap();
+        assert_links(&b, &(-1..=10).rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn split_off_and_clone_keep_level_generator() {
+        let mut a = OrdSkipList::ord_with_options(Options {
+            cmp: None,
+            level_bound: None,
+            level_generator: Some(Box::new(PerfectLevelGenerator::new(5).unwrap())),
+        })
+        .unwrap();
+        a.extend(0..8);
+        let mut b = a.split_off(&4);
+        let mut c = a.clone();
+
+        // The clone draws a level for each of its 4 elements.
+        let levels_after = |drawn: usize| {
+            let mut g = PerfectLevelGenerator::new(5).unwrap();
+            (0..drawn + 8)
+                .map(|_| g.random())
+                .skip(drawn)
+                .collect::<Vec<_>>()
+        };
+        for (l, drawn) in [(&mut a, 8), (&mut b, 8), (&mut c, 12)] {
+            assert_eq!(l.level_generator.level_bound(), 5);
+            let got: Vec<_> = (0..8).map(|_| l.level_generator.random()).collect();
+            assert_eq!(got, levels_after(drawn));
+        }
+    }
+
+    #[test]
+    fn retain() {
+        let mut l: OrdSkipList<i32> = (0..200).collect();
+        l.retain(|x| x % 7 != 0);
+        assert_links(&l, &(0..200).filter(|x| x % 7 != 0).collect::<Vec<_>>());
+
+        l.insert(7).unwrap();
+        assert_eq!(l.index_of(&7), Some(6));
+
+        l.retain(|_| false);
+        assert!(l.is_empty());
+        assert_eq!(l.first(), None);
+    }
+
+    #[test]
+    fn random_bulk_operations() {
+        use crate::collection::test_util::random_ops;
+        use rand::Rng;
+
+        let mut l: OrdSkipList<u32> = OrdSkipList::new();
+        let mut v: Vec<u32> = Vec::new();
+        random_ops(200, |rng, _| {
+            let key = rng.gen_range(0..1000);
+            match rng.gen_range(0..4) {
+                0 => {
+                    let other = l.split_off(&key);
+                    let at = v.partition_point(|x| *x < key);
+                    let rest = v.split_off(at);
+                    assert_links(&other, &rest);
+                    if rng.gen_bool(0.5) {
+                        l.extend(other);
+                        v.extend(rest);
+                    }
+                }
+                1 => {
+                    let m = rng.gen_range(1..10);
+                    l.retain(|x| x % m != 0);
+                    v.retain(|x| x % m != 0);
+                }
+                _ => {
+                    let mut other: Vec<u32> = (0..20).map(|_| rng.gen_range(0..1000)).collect();
+                    other.sort_unstable();
+                    other.dedup();
+                    let mut o = OrdSkipList::from_sorted_iter(other.clone()).unwrap();
+                    l.append(&mut o);
+                    v.extend(other);
+                    v.sort_unstable();
+                    v.dedup();
+                }
+            }
+            assert_links(&l, &v);
+        });
+    }
+
+    #[test]
+    fn std_traits() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        fn hash_of<T: Hash>(x: &T) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            x.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let a: OrdSkipList<i32> = vec![3, 1, 2].into_iter().collect();
+        let mut b = a.clone();
+        assert_eq!(a, b);
+        assert_eq!(hash_of(&a), hash_of(&b));
+        assert_eq!(format!("{:?}", a), "{1, 2, 3}");
+        assert_eq!((a[0], a[2]), (1, 3));
+
+        b.extend(&[0, 5]);
+        assert_ne!(a, b);
+        assert!(b < a);
+        assert_eq!(b.length(), 5);
+        assert_eq!(b.iter().len(), 5);
+        assert_eq!(b.range(1..).len(), 4);
+        assert_eq!(
+            b.clone().into_iter().rev().collect::<Vec<_>>(),
+            vec![5, 3, 2, 1, 0]
+        );
+
+        // The clone keeps the comparator of the original.
+        let mut desc = OrdSkipList::with_options(Options {
+            cmp: Some(Box::new(|x: &i32, y: &i32| y.cmp(x))),
+            level_bound: Some(4),
+            level_generator: None,
+        })
+        .unwrap();
+        desc.extend(0..10);
+        let mut copy = desc.clone();
+        copy.insert(10).unwrap();
+        assert_eq!(copy.first(), Some(&10));
+        assert_eq!(copy.length(), desc.length() + 1);
+
+        let mut it = a.iter();
+        it.by_ref().for_each(drop);
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of range")]
+    fn index_out_of_range() {
+        let l: OrdSkipList<i32> = (0..3).collect();
+        let _ = l[3];
+    }
+
+    #[test]
+    fn send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<OrdSkipList<String>>();
+        assert_send_sync::<crate::collection::skiplist::Iter<'_, String>>();
+
+        let l: OrdSkipList<i32> = (0..100).collect();
+        let sum = std::thread::scope(|s| {
+            let h = s.spawn(|| l.iter().sum::<i32>());
+            h.join().unwrap()
+        });
+        assert_eq!(sum, 4950);
+
+        let moved = std::thread::spawn(move || l.length()).join().unwrap();
+        assert_eq!(moved, 100);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let l: OrdSkipList<i32> = vec![3, 1, 2].into_iter().collect();
+        let json = serde_json::to_string(&l).unwrap();
+        assert_eq!(json, "[1,2,3]");
+        assert_eq!(serde_json::from_str::<OrdSkipList<i32>>(&json).unwrap(), l);
+
+        let l: OrdSkipList<i32> = serde_json::from_str("[1,4,9,5,2]").unwrap();
+        assert_links(&l, &[1, 2, 4, 5, 9]);
+        assert!(serde_json::from_str::<OrdSkipList<i32>>("[1,2,1]").is_err());
+    }
 }
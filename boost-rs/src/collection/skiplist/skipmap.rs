@@ -0,0 +1,1004 @@
+//! A skiplist based map, which keeps its entries sorted by key.
+//!
+//! Unlike an `OrdSkipList` of `(K, V)` tuples, the comparator of the map only
+//! looks at the keys, and inserting an existing key replaces its value.
+
+use std::cmp::Ordering;
+use std::iter;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Bound, RangeBounds};
+use std::ptr::NonNull;
+
+use crate::collection::error::CollectionError;
+use crate::collection::skiplist::level_generator::{DefaultLevelGenerator, GenerateLevel};
+use crate::collection::skiplist::ordskiplist::{self, Comparator, Options};
+use crate::collection::skiplist::skipnode::{Path, RangeEnds, SkipNode};
+
+/// An ordered map based on a skiplist.
+///
+/// By default, the keys are sorted with `a.cmp(b)`, a custom comparator for
+/// the keys can be given by [`SkipMap::with_options`]. The comparator must be
+/// well-behaved, just like the one of [`OrdSkipList`](crate::collection::skiplist::OrdSkipList).
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::skiplist::SkipMap;
+///
+/// let mut m = SkipMap::new();
+/// assert_eq!(m.insert(3, "c"), None);
+/// assert_eq!(m.insert(1, "a"), None);
+/// assert_eq!(m.insert(3, "C"), Some("c"));
+///
+/// assert_eq!(m.get(&3), Some(&"C"));
+/// assert_eq!(m.keys().copied().collect::<Vec<_>>(), vec![1, 3]);
+/// ```
+pub struct SkipMap<K, V> {
+    length: usize,
+    head: NonNull<SkipNode<(K, V)>>,
+    cmp: Comparator<K>,
+    level_generator: Box<dyn GenerateLevel>,
+    _marker: PhantomData<Box<SkipNode<(K, V)>>>,
+}
+
+impl<K: Ord, V> SkipMap<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ord_with_options(mut options: Options<K>) -> Result<Self, CollectionError> {
+        if options.cmp.is_none() {
+            options.cmp = Some(Box::new(|x, y| x.cmp(y)))
+        }
+        Self::with_options(options)
+    }
+}
+
+impl<K: Ord, V> Default for SkipMap<K, V> {
+    fn default() -> Self {
+        Self::_new(
+            Box::new(|x, y| x.cmp(y)),
+            Box::new(DefaultLevelGenerator::default()),
+        )
+    }
+}
+
+impl<K, V> SkipMap<K, V> {
+    pub fn with_options(mut options: Options<K>) -> Result<Self, CollectionError> {
+        let g = options.take_level_generator()?;
+        Ok(Self::_new(options.take_comparator()?, g))
+    }
+
+    fn _new(cmp: Comparator<K>, level_generator: Box<dyn GenerateLevel>) -> Self {
+        Self {
+            length: 0,
+            head: NonNull::from(Box::leak(Box::new(SkipNode::head(
+                level_generator.level_bound(),
+            )))),
+            cmp,
+            level_generator,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of entries in the map.
+    #[inline]
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if the map contains no entries.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns `true` if the map contains the given key.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self._find(key).is_some()
+    }
+
+    /// Returns a reference to the value of the given key.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.get_key_value(key).map(|(_, v)| v)
+    }
+
+    /// Returns the stored key and a reference to the value of the given key.
+    pub fn get_key_value(&self, key: &K) -> Option<(&K, &V)> {
+        self._find(key).map(|node| unsafe {
+            let (k, v) = (*node.as_ptr()).val.as_ref().unwrap();
+            (k, v)
+        })
+    }
+
+    /// Returns a mutable reference to the value of the given key.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self._find(key)
+            .map(|node| unsafe { &mut (*node.as_ptr()).val.as_mut().unwrap().1 })
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map already contains the key, its value is replaced and the old
+    /// value is returned. The stored key is not updated.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.entry(key) {
+            Entry::Occupied(mut e) => Some(e.insert(value)),
+            Entry::Vacant(e) => {
+                e.insert(value);
+                None
+            }
+        }
+    }
+
+    /// Removes the given key from the map, returning its value.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Removes the given key from the map, returning the stored key and its value.
+    pub fn remove_entry(&mut self, key: &K) -> Option<(K, V)> {
+        self._path_of(key).map(|path| self._remove_after(&path))
+    }
+
+    /// Gets the entry of the given key for in-place manipulation.
+    ///
+    /// The position of the key is only searched once, no matter which
+    /// operation is done on the entry afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use boost_rs::collection::skiplist::SkipMap;
+    ///
+    /// let mut count = SkipMap::new();
+    /// for w in ["a", "b", "a"] {
+    ///     *count.entry(w).or_insert(0) += 1;
+    /// }
+    /// assert_eq!(count.get(&"a"), Some(&2));
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        let path = self._path_to(&key);
+        if self._is_key_after(path.nodes[0], &key) {
+            Entry::Occupied(OccupiedEntry { path, map: self })
+        } else {
+            Entry::Vacant(VacantEntry {
+                key,
+                path,
+                map: self,
+            })
+        }
+    }
+
+    /// Returns the first key-value pair of the map.
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.iter().next()
+    }
+
+    /// Returns the last key-value pair of the map.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.iter().next_back()
+    }
+
+    /// Removes and returns the first key-value pair of the map.
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        if self.length == 0 {
+            return None;
+        }
+        let path = unsafe { SkipNode::find_path(self.head, |_, _| false) };
+        Some(self._remove_after(&path))
+    }
+
+    /// Removes and returns the last key-value pair of the map.
+    ///
+    /// This operation should compute in *O*(log *n*) time.
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        let len = self.length;
+        if len == 0 {
+            return None;
+        }
+        let path = unsafe { SkipNode::find_path(self.head, |_, pos| pos < len) };
+        Some(self._remove_after(&path))
+    }
+
+    /// Clears the map, removing all entries.
+    pub fn clear(&mut self) {
+        while self.pop_first().is_some() {}
+    }
+
+    /// Gets an iterator over the entries of the map, sorted by key.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        self._iter(&..)
+    }
+
+    /// Gets a mutable iterator over the entries of the map, sorted by key.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        self._iter_mut(&..)
+    }
+
+    /// Gets an iterator over the keys of the map, in sorted order.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { iter: self.iter() }
+    }
+
+    /// Gets an iterator over the values of the map, in order by key.
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { iter: self.iter() }
+    }
+
+    /// Gets a mutable iterator over the values of the map, in order by key.
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut {
+            iter: self.iter_mut(),
+        }
+    }
+
+    /// Constructs a double-ended iterator over the entries whose keys are
+    /// within `range`, sorted by key.
+    ///
+    /// If the start bound lies after the end bound, the iterator is empty.
+    ///
+    /// Locating both ends of the range should compute in *O*(log *n*) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use boost_rs::collection::skiplist::SkipMap;
+    ///
+    /// let m: SkipMap<u64, &str> = vec![(10, "a"), (20, "b"), (30, "c")].into_iter().collect();
+    /// assert_eq!(m.range(15..=30).map(|(_, v)| *v).collect::<Vec<_>>(), vec!["b", "c"]);
+    /// ```
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Iter<'_, K, V> {
+        self._iter(&range)
+    }
+
+    /// Constructs a mutable double-ended iterator over the entries whose keys
+    /// are within `range`, sorted by key.
+    pub fn range_mut<R: RangeBounds<K>>(&mut self, range: R) -> IterMut<'_, K, V> {
+        self._iter_mut(&range)
+    }
+
+    fn _iter<R: RangeBounds<K>>(&self, range: &R) -> Iter<'_, K, V> {
+        let (front, back, len) = self._range_ends(range);
+        Iter {
+            iter: ordskiplist::Iter::new(front, back, len),
+        }
+    }
+
+    fn _iter_mut<R: RangeBounds<K>>(&mut self, range: &R) -> IterMut<'_, K, V> {
+        let (front, back, len) = self._range_ends(range);
+        IterMut {
+            iter: ordskiplist::IterMut::new(front, back, len),
+        }
+    }
+
+    fn _range_ends<R: RangeBounds<K>>(&self, range: &R) -> RangeEnds<(K, V)> {
+        unsafe { SkipNode::range_ends(self.head, range, |x: &(K, V), k| (self.cmp)(&x.0, k)) }
+    }
+
+    /// Finds the path to the first entry whose key is not less than `key`.
+    fn _path_to(&self, key: &K) -> Path<(K, V)> {
+        unsafe {
+            SkipNode::find_path(self.head, |x: &(K, V), _| {
+                (self.cmp)(&x.0, key) == Ordering::Less
+            })
+        }
+    }
+
+    /// Returns the path to the given key if the map contains it.
+    fn _path_of(&self, key: &K) -> Option<Path<(K, V)>> {
+        let path = self._path_to(key);
+        if self._is_key_after(path.nodes[0], key) {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the node of the given key.
+    fn _find(&self, key: &K) -> Option<NonNull<SkipNode<(K, V)>>> {
+        let (node, _) = unsafe {
+            SkipNode::before_start(self.head, Bound::Included(key), |x: &(K, V), k| {
+                (self.cmp)(&x.0, k)
+            })
+        };
+        if self._is_key_after(node, key) {
+            unsafe { node.as_ref().next[0] }
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the node right after the given node has the given key.
+    fn _is_key_after(&self, node: NonNull<SkipNode<(K, V)>>, key: &K) -> bool {
+        unsafe {
+            match node.as_ref().next[0] {
+                Some(next) => {
+                    (self.cmp)(&next.as_ref().val.as_ref().unwrap().0, key) == Ordering::Equal
+                }
+                None => false,
+            }
+        }
+    }
+
+    /// Returns the path to the last entry.
+    fn _tail_path(&self) -> Path<(K, V)> {
+        unsafe { SkipNode::find_path(self.head, |_, _| true) }
+    }
+
+    /// Appends a new entry at the end of the map, where `path` ends, and
+    /// moves `path` to the new node.
+    fn _push_back(&mut self, path: &mut Path<(K, V)>, entry: (K, V)) {
+        let level = self.level_generator.random();
+        let node = NonNull::from(Box::leak(Box::new(SkipNode::new(entry, level))));
+        let pos = path.positions[0] + 1;
+        unsafe {
+            SkipNode::link(path, node);
+        }
+        for i in 0..=level.min(path.nodes.len() - 1) {
+            path.nodes[i] = node;
+            path.positions[i] = pos;
+        }
+        self.length += 1;
+    }
+
+    /// Removes the node right after the node where `path` ends, which must exist.
+    fn _remove_after(&mut self, path: &Path<(K, V)>) -> (K, V) {
+        self.length -= 1;
+        unsafe {
+            let node = SkipNode::unlink(path);
+            Box::from_raw(node.as_ptr()).into_val().unwrap()
+        }
+    }
+}
+
+// The map owns its nodes, and both the comparator and the level generator are
+// `Send + Sync`.
+unsafe impl<K: Send, V: Send> Send for SkipMap<K, V> {}
+
+unsafe impl<K: Sync, V: Sync> Sync for SkipMap<K, V> {}
+
+impl<K, V> Drop for SkipMap<K, V> {
+    fn drop(&mut self) {
+        struct DropGuard<'a, K, V>(&'a mut SkipMap<K, V>);
+
+        impl<'a, K, V> Drop for DropGuard<'a, K, V> {
+            fn drop(&mut self) {
+                // Continue the same loop we do below. This only runs when a destructor has
+                // panicked. If another one panics this will abort.
+                while self.0.pop_first().is_some() {}
+                unsafe { drop(Box::from_raw(self.0.head.as_ptr())) }
+            }
+        }
+
+        while let Some(entry) = self.pop_first() {
+            let guard = DropGuard(self);
+            drop(entry);
+            mem::forget(guard);
+        }
+        unsafe { drop(Box::from_raw(self.head.as_ptr())) }
+    }
+}
+
+/// A view into a single entry in a map, which may either be vacant or occupied.
+///
+/// This enum is constructed from the [`SkipMap::entry`] method.
+pub enum Entry<'a, K: 'a, V: 'a> {
+    Vacant(VacantEntry<'a, K, V>),
+    Occupied(OccupiedEntry<'a, K, V>),
+}
+
+/// A view into a vacant entry in a `SkipMap`.
+pub struct VacantEntry<'a, K: 'a, V: 'a> {
+    key: K,
+    path: Path<(K, V)>,
+    map: &'a mut SkipMap<K, V>,
+}
+
+/// A view into an occupied entry in a `SkipMap`.
+pub struct OccupiedEntry<'a, K: 'a, V: 'a> {
+    path: Path<(K, V)>,
+    map: &'a mut SkipMap<K, V>,
+}
+
+impl<'a, K, V> Entry<'a, K, V> {
+    /// Returns a reference to the key of this entry.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Vacant(e) => e.key(),
+            Entry::Occupied(e) => e.key(),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting `default` if empty, and
+    /// returns a mutable reference to the value.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Ensures a value is in the entry by inserting the result of `default`
+    /// if empty, and returns a mutable reference to the value.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Vacant(e) => e.insert(default()),
+            Entry::Occupied(e) => e.into_mut(),
+        }
+    }
+
+    /// Same as [`Entry::or_insert_with`], but `default` is given the key.
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Vacant(e) => {
+                let value = default(e.key());
+                e.insert(value)
+            }
+            Entry::Occupied(e) => e.into_mut(),
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any
+    /// potential inserts into the map.
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(mut e) => {
+                f(e.get_mut());
+                Entry::Occupied(e)
+            }
+            Entry::Vacant(e) => Entry::Vacant(e),
+        }
+    }
+}
+
+impl<'a, K, V: Default> Entry<'a, K, V> {
+    /// Ensures a value is in the entry by inserting the default value if
+    /// empty, and returns a mutable reference to the value.
+    pub fn or_default(self) -> &'a mut V {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<'a, K, V> VacantEntry<'a, K, V> {
+    /// Returns a reference to the key which would be used when inserting.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Takes ownership of the key.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Inserts the value into the map, and returns a mutable reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let level = self.map.level_generator.random();
+        let node = NonNull::from(Box::leak(Box::new(SkipNode::new((self.key, value), level))));
+        self.map.length += 1;
+        unsafe {
+            SkipNode::link(&self.path, node);
+            &mut (*node.as_ptr()).val.as_mut().unwrap().1
+        }
+    }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    fn _entry(&self) -> NonNull<SkipNode<(K, V)>> {
+        unsafe { self.path.nodes[0].as_ref().next[0].unwrap() }
+    }
+
+    /// Returns a reference to the key of this entry.
+    pub fn key(&self) -> &K {
+        unsafe { &(*self._entry().as_ptr()).val.as_ref().unwrap().0 }
+    }
+
+    /// Returns a reference to the value of this entry.
+    pub fn get(&self) -> &V {
+        unsafe { &(*self._entry().as_ptr()).val.as_ref().unwrap().1 }
+    }
+
+    /// Returns a mutable reference to the value of this entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        unsafe { &mut (*self._entry().as_ptr()).val.as_mut().unwrap().1 }
+    }
+
+    /// Converts the entry into a mutable reference to its value, which lives
+    /// as long as the borrow of the map.
+    pub fn into_mut(self) -> &'a mut V {
+        unsafe { &mut (*self._entry().as_ptr()).val.as_mut().unwrap().1 }
+    }
+
+    /// Sets the value of the entry, and returns the old value.
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the entry from the map, and returns its key and value.
+    pub fn remove_entry(self) -> (K, V) {
+        self.map._remove_after(&self.path)
+    }
+
+    /// Removes the entry from the map, and returns its value.
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+}
+
+/// An iterator over the entries of a `SkipMap`.
+pub struct Iter<'a, K: 'a, V: 'a> {
+    iter: ordskiplist::Iter<'a, (K, V)>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(k, v)| (k, v))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(k, v)| (k, v))
+    }
+}
+
+/// A mutable iterator over the entries of a `SkipMap`.
+pub struct IterMut<'a, K: 'a, V: 'a> {
+    iter: ordskiplist::IterMut<'a, (K, V)>,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(k, v)| (&*k, v))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for IterMut<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(k, v)| (&*k, v))
+    }
+}
+
+/// An iterator over the keys of a `SkipMap`.
+pub struct Keys<'a, K: 'a, V: 'a> {
+    iter: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(k, _)| k)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Keys<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(k, _)| k)
+    }
+}
+
+/// An iterator over the values of a `SkipMap`.
+pub struct Values<'a, K: 'a, V: 'a> {
+    iter: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(_, v)| v)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Values<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(_, v)| v)
+    }
+}
+
+/// A mutable iterator over the values of a `SkipMap`.
+pub struct ValuesMut<'a, K: 'a, V: 'a> {
+    iter: IterMut<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(_, v)| v)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for ValuesMut<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(_, v)| v)
+    }
+}
+
+/// An owning iterator over the entries of a `SkipMap`, sorted by key.
+pub struct IntoIter<K, V> {
+    map: SkipMap<K, V>,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.map.pop_first()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.map.length, Some(self.map.length))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.map.pop_last()
+    }
+}
+
+impl<K, V> IntoIterator for SkipMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { map: self }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a SkipMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K, V> Extend<(K, V)> for SkipMap<K, V> {
+    /// Inserts all the entries of the iterator.
+    ///
+    /// Entries whose keys are greater than the last key are appended directly
+    /// at the end, so extending by sorted input computes in *O*(*n*) time.
+    #[inline]
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iterable: I) {
+        let mut tail: Option<Path<(K, V)>> = None;
+        for (k, v) in iterable {
+            let path = tail.get_or_insert_with(|| self._tail_path());
+            let after_last = match unsafe { path.nodes[0].as_ref().val.as_ref() } {
+                Some(last) => (self.cmp)(&last.0, &k) == Ordering::Less,
+                None => true,
+            };
+            if after_last {
+                self._push_back(path, (k, v));
+            } else {
+                tail = None;
+                self.insert(k, v);
+            }
+        }
+    }
+}
+
+impl<K: Ord, V> iter::FromIterator<(K, V)> for SkipMap<K, V> {
+    #[inline]
+    fn from_iter<I>(iter: I) -> SkipMap<K, V>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut map = SkipMap::default();
+        map.extend(iter);
+        map
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use std::fmt;
+    use std::marker::PhantomData;
+
+    use serde::de::{MapAccess, Visitor};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::collection::skiplist::SkipMap;
+
+    impl<K: Serialize, V: Serialize> Serialize for SkipMap<K, V> {
+        /// Serializes the entries as a map, in ascending order of the keys.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_map(self.iter())
+        }
+    }
+
+    impl<'de, K, V> Deserialize<'de> for SkipMap<K, V>
+    where
+        K: Deserialize<'de> + Ord,
+        V: Deserialize<'de>,
+    {
+        /// Deserializes a map, the last value of a repeated key wins.
+        ///
+        /// A map sorted by key is built in *O*(*n*) time.
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct MapVisitor<K, V>(PhantomData<(K, V)>);
+
+            impl<'de, K, V> Visitor<'de> for MapVisitor<K, V>
+            where
+                K: Deserialize<'de> + Ord,
+                V: Deserialize<'de>,
+            {
+                type Value = SkipMap<K, V>;
+
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("a map")
+                }
+
+                fn visit_map<A: MapAccess<'de>>(
+                    self,
+                    mut access: A,
+                ) -> Result<Self::Value, A::Error> {
+                    let mut entries = Vec::with_capacity(access.size_hint().unwrap_or(0));
+                    while let Some(entry) = access.next_entry()? {
+                        entries.push(entry);
+                    }
+                    Ok(entries.into_iter().collect())
+                }
+            }
+
+            deserializer.deserialize_map(MapVisitor(PhantomData))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::ops::Bound::Included;
+
+    use crate::collection::skiplist::skipmap::{Entry, SkipMap};
+    use crate::collection::skiplist::Options;
+
+    #[test]
+    fn insert_and_get() {
+        let mut m = SkipMap::new();
+        assert!(m.is_empty());
+        assert_eq!(m.insert(2, "b"), None);
+        assert_eq!(m.insert(1, "a"), None);
+        assert_eq!(m.insert(2, "B"), Some("b"));
+        assert_eq!(m.length(), 2);
+
+        assert_eq!(m.get(&1), Some(&"a"));
+        assert_eq!(m.get(&2), Some(&"B"));
+        assert_eq!(m.get(&3), None);
+        assert!(m.contains_key(&1));
+        assert!(!m.contains_key(&0));
+
+        *m.get_mut(&1).unwrap() = "A";
+        assert_eq!(m.get_key_value(&1), Some((&1, &"A")));
+    }
+
+    #[test]
+    fn remove() {
+        let mut m: SkipMap<i32, i32> = (0..10).map(|x| (x, x * x)).collect();
+        assert_eq!(m.remove(&3), Some(9));
+        assert_eq!(m.remove(&3), None);
+        assert_eq!(m.remove_entry(&4), Some((4, 16)));
+        assert_eq!(m.pop_first(), Some((0, 0)));
+        assert_eq!(m.pop_last(), Some((9, 81)));
+        assert_eq!(m.length(), 6);
+        assert_eq!(
+            m.keys().copied().collect::<Vec<_>>(),
+            vec![1, 2, 5, 6, 7, 8]
+        );
+
+        m.clear();
+        assert!(m.is_empty());
+        assert_eq!(m.pop_last(), None);
+    }
+
+    #[test]
+    fn entry() {
+        let mut m: SkipMap<&str, usize> = SkipMap::new();
+        for w in "a b c a b a".split(' ') {
+            *m.entry(w).or_default() += 1;
+        }
+        assert_eq!(
+            m.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+            vec![("a", 3), ("b", 2), ("c", 1)]
+        );
+
+        m.entry("b").and_modify(|v| *v *= 10).or_insert(0);
+        m.entry("d").and_modify(|v| *v *= 10).or_insert(7);
+        assert_eq!(m.get(&"b"), Some(&20));
+        assert_eq!(m.get(&"d"), Some(&7));
+        assert_eq!(*m.entry("e").or_insert_with_key(|k| k.len()), 1);
+
+        match m.entry("c") {
+            Entry::Occupied(mut e) => {
+                assert_eq!(e.key(), &"c");
+                assert_eq!(e.insert(5), 1);
+                assert_eq!(e.remove(), 5);
+            }
+            Entry::Vacant(_) => unreachable!(),
+        }
+        match m.entry("f") {
+            Entry::Vacant(e) => assert_eq!(e.into_key(), "f"),
+            Entry::Occupied(_) => unreachable!(),
+        }
+        assert_eq!(
+            m.keys().copied().collect::<Vec<_>>(),
+            vec!["a", "b", "d", "e"]
+        );
+    }
+
+    #[test]
+    fn iterators() {
+        let mut m: SkipMap<i32, String> = (0..5).rev().map(|x| (x, x.to_string())).collect();
+
+        assert_eq!(m.keys().copied().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(
+            m.keys().rev().copied().collect::<Vec<_>>(),
+            vec![4, 3, 2, 1, 0]
+        );
+        assert_eq!(
+            m.values().cloned().collect::<Vec<_>>(),
+            vec!["0", "1", "2", "3", "4"]
+        );
+
+        m.values_mut().for_each(|v| v.push('!'));
+        for (k, v) in m.iter_mut().rev().take(2) {
+            *v = format!("{}?", k);
+        }
+        assert_eq!(
+            (&m).into_iter()
+                .map(|(_, v)| v.as_str())
+                .collect::<Vec<_>>(),
+            vec!["0!", "1!", "2!", "3?", "4?"]
+        );
+
+        assert_eq!(m.first_key_value(), Some((&0, &"0!".to_string())));
+        assert_eq!(m.last_key_value(), Some((&4, &"4?".to_string())));
+
+        let mut it = m.into_iter();
+        assert_eq!(it.next_back(), Some((4, "4?".to_string())));
+        assert_eq!(it.next(), Some((0, "0!".to_string())));
+        assert_eq!(it.size_hint(), (3, Some(3)));
+    }
+
+    #[test]
+    fn range() {
+        let mut m: SkipMap<u64, u64> = (1..=10).map(|x| (x * 100, x)).collect();
+
+        assert_eq!(
+            m.range(250..=500).map(|(_, v)| *v).collect::<Vec<_>>(),
+            vec![3, 4, 5]
+        );
+        assert_eq!(
+            m.range(..200).map(|(k, _)| *k).collect::<Vec<_>>(),
+            vec![100]
+        );
+        assert_eq!(m.range(901..).count(), 1);
+        assert_eq!(m.range(1001..).count(), 0);
+        assert_eq!(m.range(..).next_back(), Some((&1000, &10)));
+
+        m.range_mut(300..500).for_each(|(_, v)| *v = 0);
+        assert_eq!(m.values().filter(|v| **v == 0).count(), 2);
+    }
+
+    #[test]
+    fn with_options_cmp() {
+        let mut m: SkipMap<i32, ()> = SkipMap::with_options(Options {
+            cmp: Some(Box::new(|x: &i32, y: &i32| y.cmp(x))),
+            level_bound: Some(8),
+            level_generator: None,
+        })
+        .unwrap();
+        for i in 0..10 {
+            m.insert(i, ());
+        }
+        assert_eq!(
+            m.keys().copied().collect::<Vec<_>>(),
+            (0..10).rev().collect::<Vec<_>>()
+        );
+        // The bounds follow the order of the comparator.
+        assert_eq!(m.range((Included(7), Included(3))).count(), 5);
+    }
+
+    #[test]
+    fn random_consistency() {
+        use crate::collection::test_util::random_ops;
+        use rand::Rng;
+
+        let mut m: SkipMap<u32, usize> = SkipMap::new();
+        let mut b: BTreeMap<u32, usize> = BTreeMap::new();
+        random_ops(3000, |rng, i| {
+            let k = rng.gen_range(0..300);
+            if rng.gen_bool(0.6) {
+                assert_eq!(m.insert(k, i), b.insert(k, i));
+            } else {
+                assert_eq!(m.remove(&k), b.remove(&k));
+            }
+        });
+
+        assert_eq!(m.length(), b.len());
+        assert!(m.iter().eq(b.iter()));
+        assert!(m.range(100..200).rev().eq(b.range(100..200).rev()));
+    }
+
+    #[test]
+    fn extend_sorted_and_unsorted() {
+        let mut m: SkipMap<i32, i32> = (0..100).map(|k| (k * 2, k)).collect();
+        m.extend(vec![(200, 0), (202, 0), (1, 1), (202, 2), (204, 0)]);
+
+        let mut b: BTreeMap<i32, i32> = (0..100).map(|k| (k * 2, k)).collect();
+        b.extend(vec![(200, 0), (202, 0), (1, 1), (202, 2), (204, 0)]);
+        assert_eq!(m.length(), b.len());
+        assert!(m.iter().eq(b.iter()));
+        assert!(m.iter().rev().eq(b.iter().rev()));
+        assert_eq!(m.get(&202), Some(&2));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let m: SkipMap<String, i32> = vec![("b".to_string(), 2), ("a".to_string(), 1)]
+            .into_iter()
+            .collect();
+        let json = serde_json::to_string(&m).unwrap();
+        assert_eq!(json, r#"{"a":1,"b":2}"#);
+
+        let m: SkipMap<String, i32> = serde_json::from_str(r#"{"c":3,"a":1,"c":4}"#).unwrap();
+        assert_eq!(m.length(), 2);
+        assert_eq!(m.get(&"c".to_string()), Some(&4));
+        assert_eq!(m.first_key_value(), Some((&"a".to_string(), &1)));
+    }
+}
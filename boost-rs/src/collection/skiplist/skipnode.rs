@@ -1,4 +1,6 @@
+use std::cmp::Ordering;
 use std::iter;
+use std::ops::{Bound, RangeBounds};
 use std::ptr::NonNull;
 
 /// A covariant pointer to a SkipNode.
@@ -11,6 +13,9 @@ use std::ptr::NonNull;
 /// https://doc.rust-lang.org/nomicon/subtyping.html
 pub(crate) type Link<T> = Option<NonNull<SkipNode<T>>>;
 
+/// The first and the last node of a range of nodes, and the number of nodes in it.
+pub(crate) type RangeEnds<T> = (Link<T>, Link<T>, usize);
+
 /// SkipNodes are make up the SkipList.  The SkipList owns the first head-node
 /// (which has no value) and each node has ownership of the next node through
 /// `next`.
@@ -23,11 +28,14 @@ pub(crate) type Link<T> = Option<NonNull<SkipNode<T>>>;
 /// The node linked by level 0 should be considered owned by this node.
 ///
 /// There is a corresponding vector of link lengths which contains the distance
-/// between current node and the next node. If there's no next node, the distance
-/// is distance between current node and last reachable node.
+/// between current node and the next node. If there's no next node, the length
+/// is meaningless and kept at 0.
+///
+/// Level 0 is also linked backwards through `prev`, so that the list can be
+/// walked in both directions.
 ///
-/// Lastly, each node contains a link to the immediately previous node in case
-/// one needs to parse the list backwards.
+/// Positions are counted from the head: the head is at position 0 and the
+/// element with index `i` is at position `i + 1`.
 #[derive(Clone, Debug)]
 pub(crate) struct SkipNode<T> {
     // item should never be None, unless the node is a head.
@@ -40,6 +48,25 @@ pub(crate) struct SkipNode<T> {
     // *must* be of length `self.level + 1`.  links[0] stores a pointer to the
     // next node, which will have to be dropped.
     pub(crate) next: Vec<Link<T>>,
+
+    // The corresponding length of each link in `next`, i.e. how many nodes of
+    // level 0 the link skips over. This vector *must* be of length `self.level + 1`.
+    pub(crate) links_len: Vec<usize>,
+
+    // The previous node on level 0, which is the head for the first node.
+    // It is only meaningful while the node is linked.
+    pub(crate) prev: Link<T>,
+}
+
+/// The nodes where a walk through the skiplist went down a level, together
+/// with their positions, indexed by level.
+///
+/// These are exactly the nodes whose links span the node right after
+/// `nodes[0]`, so they are all that has to be updated to insert or remove a
+/// node there.
+pub(crate) struct Path<T> {
+    pub(crate) nodes: Vec<NonNull<SkipNode<T>>>,
+    pub(crate) positions: Vec<usize>,
 }
 
 impl<T> SkipNode<T> {
@@ -48,7 +75,9 @@ impl<T> SkipNode<T> {
         SkipNode {
             val: None,
             level: level_bound - 1, // The head node has `level_bound-1` levels(highest level)
-            next: iter::repeat(None).take(level_bound).collect(),
+            next: iter::repeat_n(None, level_bound).collect(),
+            links_len: iter::repeat_n(0, level_bound).collect(),
+            prev: None,
         }
     }
 
@@ -58,11 +87,171 @@ impl<T> SkipNode<T> {
         SkipNode {
             val: Some(item),
             level,
-            next: iter::repeat(None).take(level + 1).collect(),
+            next: iter::repeat_n(None, level + 1).collect(),
+            links_len: iter::repeat_n(0, level + 1).collect(),
+            prev: None,
         }
     }
 
     pub fn into_val(self) -> Option<T> {
         self.val
     }
+
+    /// Walks the skiplist down from `head`, moving right on each level as long
+    /// as `go_right` holds for the next node, which is given the value and the
+    /// position of the next node.
+    ///
+    /// Returns the node where the walk stops on level 0 and its position.
+    pub(crate) unsafe fn find<F>(head: NonNull<Self>, mut go_right: F) -> (NonNull<Self>, usize)
+    where
+        F: FnMut(&T, usize) -> bool,
+    {
+        let mut cur = head;
+        let mut pos = 0;
+        for i in (0..=head.as_ref().level).rev() {
+            while let Some(next) = cur.as_ref().next[i] {
+                let next_pos = pos + cur.as_ref().links_len[i];
+                if !go_right(next.as_ref().val.as_ref().unwrap(), next_pos) {
+                    break;
+                }
+                cur = next;
+                pos = next_pos;
+            }
+        }
+        (cur, pos)
+    }
+
+    /// Same as [`SkipNode::find`], but records where the walk goes down on
+    /// every level.
+    pub(crate) unsafe fn find_path<F>(head: NonNull<Self>, mut go_right: F) -> Path<T>
+    where
+        F: FnMut(&T, usize) -> bool,
+    {
+        let levels = head.as_ref().level + 1;
+        let mut path = Path {
+            nodes: vec![head; levels],
+            positions: vec![0; levels],
+        };
+
+        let mut cur = head;
+        let mut pos = 0;
+        for i in (0..levels).rev() {
+            while let Some(next) = cur.as_ref().next[i] {
+                let next_pos = pos + cur.as_ref().links_len[i];
+                if !go_right(next.as_ref().val.as_ref().unwrap(), next_pos) {
+                    break;
+                }
+                cur = next;
+                pos = next_pos;
+            }
+            path.nodes[i] = cur;
+            path.positions[i] = pos;
+        }
+        path
+    }
+
+    /// Finds the node right before the first element within the start `bound`,
+    /// together with its position. Elements are compared against the bound
+    /// with `cmp`.
+    pub(crate) unsafe fn before_start<Q: ?Sized, F>(
+        head: NonNull<Self>,
+        bound: Bound<&Q>,
+        cmp: F,
+    ) -> (NonNull<Self>, usize)
+    where
+        F: Fn(&T, &Q) -> Ordering,
+    {
+        match bound {
+            Bound::Included(v) => Self::find(head, |x, _| cmp(x, v) == Ordering::Less),
+            Bound::Excluded(v) => Self::find(head, |x, _| cmp(x, v) != Ordering::Greater),
+            Bound::Unbounded => (head, 0),
+        }
+    }
+
+    /// Finds the last element within the end `bound`, together with its
+    /// position. The head is returned if there is no such element.
+    pub(crate) unsafe fn last_within<Q: ?Sized, F>(
+        head: NonNull<Self>,
+        bound: Bound<&Q>,
+        cmp: F,
+    ) -> (NonNull<Self>, usize)
+    where
+        F: Fn(&T, &Q) -> Ordering,
+    {
+        match bound {
+            Bound::Included(v) => Self::find(head, |x, _| cmp(x, v) != Ordering::Greater),
+            Bound::Excluded(v) => Self::find(head, |x, _| cmp(x, v) == Ordering::Less),
+            Bound::Unbounded => Self::find(head, |_, _| true),
+        }
+    }
+
+    /// Returns the first and the last node within `range`, and the number of
+    /// elements between them.
+    pub(crate) unsafe fn range_ends<Q: ?Sized, R, F>(
+        head: NonNull<Self>,
+        range: &R,
+        cmp: F,
+    ) -> RangeEnds<T>
+    where
+        R: RangeBounds<Q>,
+        F: Fn(&T, &Q) -> Ordering,
+    {
+        let (before, start) = Self::before_start(head, range.start_bound(), &cmp);
+        let (last, end) = Self::last_within(head, range.end_bound(), &cmp);
+        (
+            before.as_ref().next[0],
+            Some(last),
+            end.saturating_sub(start),
+        )
+    }
+
+    /// Links `node` right after the node where `path` ends on level 0.
+    pub(crate) unsafe fn link(path: &Path<T>, node: NonNull<Self>) {
+        let new_pos = path.positions[0] + 1;
+        let new = &mut *node.as_ptr();
+        for (i, (prev, &prev_pos)) in path.nodes.iter().zip(&path.positions).enumerate() {
+            let prev = &mut *prev.as_ptr();
+            if i <= new.level {
+                new.next[i] = prev.next[i];
+                if prev.next[i].is_some() {
+                    new.links_len[i] = prev_pos + prev.links_len[i] + 1 - new_pos;
+                }
+                prev.next[i] = Some(node);
+                prev.links_len[i] = new_pos - prev_pos;
+            } else if prev.next[i].is_some() {
+                prev.links_len[i] += 1;
+            }
+        }
+
+        new.prev = Some(path.nodes[0]);
+        if let Some(next) = new.next[0] {
+            (*next.as_ptr()).prev = Some(node);
+        }
+    }
+
+    /// Unlinks the node right after the node where `path` ends on level 0, and
+    /// returns it.
+    ///
+    /// The node must exist.
+    pub(crate) unsafe fn unlink(path: &Path<T>) -> NonNull<Self> {
+        let target = path.nodes[0].as_ref().next[0].unwrap();
+        let removed = target.as_ref();
+        for (i, prev) in path.nodes.iter().enumerate() {
+            let prev = &mut *prev.as_ptr();
+            if prev.next[i] == Some(target) {
+                prev.next[i] = removed.next[i];
+                prev.links_len[i] = match prev.next[i] {
+                    Some(_) => prev.links_len[i] + removed.links_len[i] - 1,
+                    None => 0,
+                };
+            } else if prev.next[i].is_some() {
+                prev.links_len[i] -= 1;
+            }
+        }
+
+        if let Some(next) = removed.next[0] {
+            (*next.as_ptr()).prev = removed.prev;
+        }
+        target
+    }
 }
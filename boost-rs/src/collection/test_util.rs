@@ -0,0 +1,22 @@
+//! Helpers shared by the tests of the collections.
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Returns a random generator with a fixed seed, so that a failing test
+/// runs the same sequence again.
+pub(crate) fn seeded_rng() -> StdRng {
+    StdRng::seed_from_u64(0x5eed)
+}
+
+/// Runs `step` for `n` random operations, passing the shared seeded
+/// generator and the index of the operation.
+///
+/// The tests check each collection against a model from `std` after
+/// every step.
+pub(crate) fn random_ops<F: FnMut(&mut StdRng, usize)>(n: usize, mut step: F) {
+    let mut rng = seeded_rng();
+    for i in 0..n {
+        step(&mut rng, i);
+    }
+}
@@ -0,0 +1,617 @@
+//! A compressed radix trie keyed by byte strings.
+//!
+//! Every edge of the trie is labeled with a non-empty byte string, and the
+//! labels of the children of a node start with distinct bytes. A node without
+//! a value always has at least two children (except for the root), so the
+//! number of nodes is linear in the number of keys.
+//!
+//! Wikipedia:
+//!  - https://en.wikipedia.org/wiki/Radix_tree
+
+use std::fmt::{self, Debug};
+use std::iter::{FromIterator, FusedIterator};
+use std::mem;
+
+/// Create a **Trie** from a list of key-value pairs
+///
+/// ## Example
+///
+/// ```
+/// # fn main() {
+/// use boost_rs::trie;
+/// let routes = trie!{
+///     "/api" => 1,
+///     "/api/users" => 2,
+/// };
+/// assert_eq!(routes.get("/api"), Some(&1));
+/// assert_eq!(routes.longest_prefix("/api/users/42"), Some((&b"/api/users"[..], &2)));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! trie {
+    // trailing comma case
+    ($($key:expr => $value:expr,)+) => (trie!($($key => $value),+));
+
+    ( $($key:expr => $value:expr),* ) => {
+        {
+            let mut _trie = $crate::collection::trie::Trie::new();
+            $(
+                let _ = _trie.insert($key, $value);
+            )*
+            _trie
+        }
+    };
+}
+
+#[derive(Clone, PartialEq, Eq)]
+struct Node<V> {
+    // The label of the edge from the parent, which is empty for the root.
+    label: Vec<u8>,
+    value: Option<V>,
+    // The children, sorted by the first byte of their labels.
+    children: Vec<Node<V>>,
+}
+
+impl<V> Node<V> {
+    fn new(label: Vec<u8>, value: Option<V>) -> Self {
+        Node {
+            label,
+            value,
+            children: Vec::new(),
+        }
+    }
+
+    /// Returns the index of the child whose label starts with `byte`.
+    fn child_index(&self, byte: u8) -> Result<usize, usize> {
+        self.children.binary_search_by_key(&byte, |c| c.label[0])
+    }
+
+    /// Returns the child whose label is a prefix of `key`.
+    fn child_on(&self, key: &[u8]) -> Option<&Node<V>> {
+        let i = self.child_index(*key.first()?).ok()?;
+        Some(&self.children[i]).filter(|c| key.starts_with(&c.label))
+    }
+
+    /// Splits the label of the node at `at`, the node keeps the first part
+    /// and gets a single child with the rest.
+    fn split(&mut self, at: usize) {
+        let rest = Node {
+            label: self.label.split_off(at),
+            value: self.value.take(),
+            children: mem::take(&mut self.children),
+        };
+        self.children.push(rest);
+    }
+
+    /// Merges the only child into the node, if the node has no value.
+    fn compress(&mut self) {
+        if self.value.is_none() && self.children.len() == 1 {
+            let child = self.children.pop().unwrap();
+            self.label.extend(child.label);
+            self.value = child.value;
+            self.children = child.children;
+        }
+    }
+}
+
+/// A map from byte strings to values, based on a compressed radix trie.
+///
+/// Keys can be anything which can be viewed as bytes, such as `&str`,
+/// `String` or `Vec<u8>`. The entries are iterated in lexicographic order of
+/// their keys.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::collection::trie::Trie;
+///
+/// let mut words = Trie::new();
+/// words.insert("car", 1);
+/// words.insert("cart", 2);
+/// words.insert("cat", 3);
+/// words.insert("dog", 4);
+///
+/// let completions: Vec<_> = words.starts_with("ca").map(|(k, _)| k).collect();
+/// assert_eq!(completions, vec![b"car".to_vec(), b"cart".to_vec(), b"cat".to_vec()]);
+///
+/// assert_eq!(words.remove("car"), Some(1));
+/// assert_eq!(words.get("cart"), Some(&2));
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct Trie<V> {
+    root: Node<V>,
+    length: usize,
+}
+
+impl<V> Default for Trie<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> Trie<V> {
+    pub fn new() -> Self {
+        Trie {
+            root: Node::new(Vec::new(), None),
+            length: 0,
+        }
+    }
+
+    /// Returns the number of entries in the trie.
+    #[inline]
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if the trie contains no entries.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Clears the trie, removing all entries.
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Inserts a key-value pair into the trie, and returns the old value of
+    /// the key.
+    ///
+    /// This operation should compute in *O*(*k*) time, where *k* is the
+    /// length of the key.
+    pub fn insert<K: AsRef<[u8]>>(&mut self, key: K, value: V) -> Option<V> {
+        let mut key = key.as_ref();
+        let mut node = &mut self.root;
+        loop {
+            if key.is_empty() {
+                let old = node.value.replace(value);
+                if old.is_none() {
+                    self.length += 1;
+                }
+                return old;
+            }
+            match node.child_index(key[0]) {
+                Err(i) => {
+                    node.children
+                        .insert(i, Node::new(key.to_vec(), Some(value)));
+                    self.length += 1;
+                    return None;
+                }
+                Ok(i) => {
+                    let child = &mut node.children[i];
+                    let common = _common_prefix_len(&child.label, key);
+                    if common < child.label.len() {
+                        child.split(common);
+                    }
+                    key = &key[common..];
+                    node = child;
+                }
+            }
+        }
+    }
+
+    /// Returns a reference to the value of the given key.
+    ///
+    /// This operation should compute in *O*(*k*) time, where *k* is the
+    /// length of the key.
+    pub fn get<K: AsRef<[u8]> + ?Sized>(&self, key: &K) -> Option<&V> {
+        let mut key = key.as_ref();
+        let mut node = &self.root;
+        while !key.is_empty() {
+            node = node.child_on(key)?;
+            key = &key[node.label.len()..];
+        }
+        node.value.as_ref()
+    }
+
+    /// Returns a mutable reference to the value of the given key.
+    pub fn get_mut<K: AsRef<[u8]> + ?Sized>(&mut self, key: &K) -> Option<&mut V> {
+        let mut key = key.as_ref();
+        let mut node = &mut self.root;
+        while !key.is_empty() {
+            let i = node.child_index(key[0]).ok()?;
+            node = &mut node.children[i];
+            if !key.starts_with(&node.label) {
+                return None;
+            }
+            key = &key[node.label.len()..];
+        }
+        node.value.as_mut()
+    }
+
+    /// Returns `true` if the trie contains the given key.
+    pub fn contains_key<K: AsRef<[u8]> + ?Sized>(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes the given key from the trie, returning its value.
+    ///
+    /// This operation should compute in *O*(*k*) time, where *k* is the
+    /// length of the key.
+    pub fn remove<K: AsRef<[u8]> + ?Sized>(&mut self, key: &K) -> Option<V> {
+        let value = Self::_remove(&mut self.root, key.as_ref())?;
+        self.length -= 1;
+        Some(value)
+    }
+
+    /// Removes `key` from the subtree of `node`, and compresses the nodes on
+    /// the way back.
+    fn _remove(node: &mut Node<V>, key: &[u8]) -> Option<V> {
+        if key.is_empty() {
+            return node.value.take();
+        }
+
+        let i = node.child_index(key[0]).ok()?;
+        let child = &mut node.children[i];
+        if !key.starts_with(&child.label) {
+            return None;
+        }
+        let value = Self::_remove(child, &key[child.label.len()..])?;
+        if child.value.is_none() && child.children.is_empty() {
+            node.children.remove(i);
+        } else {
+            child.compress();
+        }
+        Some(value)
+    }
+
+    /// Returns the entry with the longest key which is a prefix of `key`.
+    ///
+    /// This is the lookup of a routing table: the most specific route wins.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use boost_rs::collection::trie::Trie;
+    ///
+    /// let mut routes = Trie::new();
+    /// routes.insert("10.", "private");
+    /// routes.insert("10.1.", "office");
+    ///
+    /// assert_eq!(routes.longest_prefix("10.1.2.3"), Some((&b"10.1."[..], &"office")));
+    /// assert_eq!(routes.longest_prefix("10.2.0.1"), Some((&b"10."[..], &"private")));
+    /// assert_eq!(routes.longest_prefix("192.168.0.1"), None);
+    /// ```
+    pub fn longest_prefix<'k, K: AsRef<[u8]> + ?Sized>(
+        &self,
+        key: &'k K,
+    ) -> Option<(&'k [u8], &V)> {
+        let key = key.as_ref();
+        let mut node = &self.root;
+        let mut depth = 0;
+        let mut found = node.value.as_ref().map(|v| (0, v));
+        while let Some(child) = node.child_on(&key[depth..]) {
+            node = child;
+            depth += node.label.len();
+            if let Some(v) = node.value.as_ref() {
+                found = Some((depth, v));
+            }
+        }
+        found.map(|(len, v)| (&key[..len], v))
+    }
+
+    /// Gets an iterator over the entries whose keys start with `prefix`, in
+    /// lexicographic order.
+    pub fn starts_with<K: AsRef<[u8]> + ?Sized>(&self, prefix: &K) -> Iter<'_, V> {
+        let mut prefix = prefix.as_ref();
+        let mut node = &self.root;
+        let mut path = Vec::new();
+        while !prefix.is_empty() {
+            let child = match node.child_index(prefix[0]) {
+                Ok(i) => &node.children[i],
+                Err(_) => return Iter::empty(),
+            };
+            if child.label.starts_with(prefix) {
+                // The prefix ends within the label of the child.
+                return Iter::new(path, child);
+            }
+            if !prefix.starts_with(&child.label) {
+                return Iter::empty();
+            }
+            path.extend_from_slice(&child.label);
+            prefix = &prefix[child.label.len()..];
+            node = child;
+        }
+        Iter::new(path, node)
+    }
+
+    /// Gets an iterator over the entries of the trie, in lexicographic order
+    /// of the keys.
+    pub fn iter(&self) -> Iter<'_, V> {
+        Iter::new(Vec::new(), &self.root)
+    }
+
+    /// Gets an iterator over the keys of the trie, in lexicographic order.
+    pub fn keys(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
+        self.iter().map(|(k, _)| k)
+    }
+
+    /// Gets an iterator over the values of the trie, in lexicographic order
+    /// of the keys.
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.iter().map(|(_, v)| v)
+    }
+}
+
+/// Returns the length of the longest common prefix of `a` and `b`.
+fn _common_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+impl<V: Debug> Debug for Trie<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Key(Vec<u8>);
+
+        impl Debug for Key {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "b\"{}\"", self.0.escape_ascii())
+            }
+        }
+
+        f.debug_map()
+            .entries(self.iter().map(|(k, v)| (Key(k), v)))
+            .finish()
+    }
+}
+
+impl<K: AsRef<[u8]>, V> Extend<(K, V)> for Trie<V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<K: AsRef<[u8]>, V> FromIterator<(K, V)> for Trie<V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut trie = Self::new();
+        trie.extend(iter);
+        trie
+    }
+}
+
+impl<'a, V> IntoIterator for &'a Trie<V> {
+    type Item = (Vec<u8>, &'a V);
+    type IntoIter = Iter<'a, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the entries of a `Trie`, in lexicographic order.
+///
+/// The keys are rebuilt from the labels on the way, so they are yielded as
+/// owned byte strings.
+#[derive(Clone)]
+pub struct Iter<'a, V> {
+    // The nodes to visit, with the length of the key of their parents.
+    stack: Vec<(usize, &'a Node<V>)>,
+    key: Vec<u8>,
+}
+
+impl<'a, V> Iter<'a, V> {
+    fn new(path: Vec<u8>, node: &'a Node<V>) -> Self {
+        Iter {
+            stack: vec![(path.len(), node)],
+            key: path,
+        }
+    }
+
+    fn empty() -> Self {
+        Iter {
+            stack: Vec::new(),
+            key: Vec::new(),
+        }
+    }
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (Vec<u8>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((depth, node)) = self.stack.pop() {
+            self.key.truncate(depth);
+            self.key.extend_from_slice(&node.label);
+            let depth = self.key.len();
+            self.stack
+                .extend(node.children.iter().rev().map(|child| (depth, child)));
+            if let Some(v) = node.value.as_ref() {
+                return Some((self.key.clone(), v));
+            }
+        }
+        None
+    }
+}
+
+impl<'a, V> FusedIterator for Iter<'a, V> {}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::collection::trie::Trie;
+
+    impl<V: Serialize> Serialize for Trie<V> {
+        /// Serializes the entries as a sequence of key-value pairs, in
+        /// lexicographic order of the keys.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.iter())
+        }
+    }
+
+    impl<'de, V: Deserialize<'de>> Deserialize<'de> for Trie<V> {
+        /// Deserializes a sequence of key-value pairs, the last value of a
+        /// repeated key wins.
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Vec::<(Vec<u8>, V)>::deserialize(deserializer)
+                .map(|entries| entries.into_iter().collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::collection::trie::Trie;
+
+    fn keys<V>(it: impl Iterator<Item = (Vec<u8>, V)>) -> Vec<String> {
+        it.map(|(k, _)| String::from_utf8(k).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_insert_and_get() {
+        let mut t = Trie::new();
+        assert_eq!(t.insert("romane", 1), None);
+        assert_eq!(t.insert("romanus", 2), None);
+        assert_eq!(t.insert("romulus", 3), None);
+        assert_eq!(t.insert("rom", 4), None);
+        assert_eq!(t.insert("romane", 10), Some(1));
+        assert_eq!(t.length(), 4);
+
+        assert_eq!(t.get("romane"), Some(&10));
+        assert_eq!(t.get("rom"), Some(&4));
+        assert_eq!(t.get("ro"), None);
+        assert_eq!(t.get("roman"), None);
+        assert_eq!(t.get("romanes"), None);
+        assert_eq!(t.get(""), None);
+        assert!(t.contains_key(&b"romulus"[..]));
+
+        *t.get_mut("rom").unwrap() += 1;
+        assert_eq!(t.get("rom"), Some(&5));
+        assert_eq!(t.get_mut("roman"), None);
+
+        t.insert("", 0);
+        assert_eq!(t.get(""), Some(&0));
+        assert_eq!(t.length(), 5);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut t: Trie<i32> = vec![("test", 1), ("team", 2), ("toast", 3), ("te", 4)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(t.remove("tea"), None);
+        assert_eq!(t.remove("te"), Some(4));
+        assert_eq!(t.remove("te"), None);
+        assert_eq!(t.length(), 3);
+        assert_eq!(t.remove("team"), Some(2));
+        assert_eq!(t.get("test"), Some(&1));
+
+        // The trie is compressed back, so it is equal to a new one.
+        let expected: Trie<i32> = vec![("test", 1), ("toast", 3)].into_iter().collect();
+        assert_eq!(t, expected);
+
+        assert_eq!(t.remove("test"), Some(1));
+        assert_eq!(t.remove("toast"), Some(3));
+        assert!(t.is_empty());
+        assert_eq!(t, Trie::new());
+    }
+
+    #[test]
+    fn test_starts_with() {
+        let t = trie! {
+            "car" => 1,
+            "cart" => 2,
+            "carbon" => 3,
+            "cat" => 4,
+            "dog" => 5,
+        };
+
+        assert_eq!(keys(t.starts_with("car")), vec!["car", "carbon", "cart"]);
+        assert_eq!(
+            keys(t.starts_with("ca")),
+            vec!["car", "carbon", "cart", "cat"]
+        );
+        assert_eq!(keys(t.starts_with("carb")), vec!["carbon"]);
+        assert_eq!(keys(t.starts_with("")), keys(t.iter()));
+        assert_eq!(t.starts_with("cab").count(), 0);
+        assert_eq!(t.starts_with("cartoon").count(), 0);
+        assert_eq!(t.starts_with("e").count(), 0);
+    }
+
+    #[test]
+    fn test_longest_prefix() {
+        let mut t = Trie::new();
+        assert_eq!(t.longest_prefix("abc"), None);
+        t.insert("a", 1);
+        t.insert("abcd", 2);
+        t.insert("abx", 3);
+
+        assert_eq!(t.longest_prefix("abcdef"), Some((&b"abcd"[..], &2)));
+        assert_eq!(t.longest_prefix("abc"), Some((&b"a"[..], &1)));
+        assert_eq!(t.longest_prefix("abx"), Some((&b"abx"[..], &3)));
+        assert_eq!(t.longest_prefix("b"), None);
+
+        t.insert("", 0);
+        assert_eq!(t.longest_prefix("b"), Some((&b""[..], &0)));
+    }
+
+    #[test]
+    fn test_iter_order() {
+        let t: Trie<usize> = vec!["b", "abc", "a", "", "ab", "ba", "abd"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, k)| (k, i))
+            .collect();
+        assert_eq!(keys(t.iter()), vec!["", "a", "ab", "abc", "abd", "b", "ba"]);
+        assert_eq!(
+            t.values().copied().collect::<Vec<_>>(),
+            vec![3, 2, 4, 1, 6, 0, 5]
+        );
+        assert_eq!(
+            format!("{:?}", trie! {"a" => 1, "b\n" => 2}),
+            r#"{b"a": 1, b"b\n": 2}"#
+        );
+    }
+
+    #[test]
+    fn test_macro() {
+        let t = trie! {
+            "one" => 1,
+            String::from("two") => 2,
+        };
+        assert_eq!(t.length(), 2);
+        assert_eq!(t.get("two"), Some(&2));
+
+        let empty: Trie<i32> = trie! {};
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_random_consistency() {
+        use crate::collection::test_util::random_ops;
+        use rand::Rng;
+
+        let mut t = Trie::new();
+        let mut m = BTreeMap::new();
+        random_ops(5000, |rng, i| {
+            let len = rng.gen_range(0..6);
+            let key: Vec<u8> = (0..len).map(|_| rng.gen_range(b'a'..b'd')).collect();
+            if rng.gen_bool(0.6) {
+                assert_eq!(t.insert(&key, i), m.insert(key, i));
+            } else {
+                assert_eq!(t.remove(&key), m.remove(&key));
+            }
+            assert_eq!(t.length(), m.len());
+        });
+
+        assert!(t.iter().map(|(k, v)| (k, *v)).eq(m.clone().into_iter()));
+        let prefix = b"ab";
+        assert!(t
+            .starts_with(prefix)
+            .map(|(k, v)| (k, *v))
+            .eq(m.into_iter().filter(|(k, _)| k.starts_with(prefix))));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let t = trie! {"b" => 2, "a" => 1};
+        let json = serde_json::to_string(&t).unwrap();
+        assert_eq!(json, "[[[97],1],[[98],2]]");
+        assert_eq!(serde_json::from_str::<Trie<i32>>(&json).unwrap(), t);
+    }
+}
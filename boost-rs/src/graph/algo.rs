@@ -0,0 +1,381 @@
+//! Common graph algorithms.
+//!
+//! Wikipedia:
+//!  - https://en.wikipedia.org/wiki/Topological_sorting
+//!  - https://en.wikipedia.org/wiki/Dijkstra%27s_algorithm
+//!  - https://en.wikipedia.org/wiki/Component_(graph_theory)
+//!  - https://en.wikipedia.org/wiki/Kruskal%27s_algorithm
+
+use thiserror::Error;
+
+use crate::collection::disjoint_set::DisjointSet;
+use crate::collection::error::CollectionError;
+use crate::collection::heap::IndexedPriorityQueue;
+use crate::graph::{DiGraph, Graph, UnGraph};
+use crate::types::ops::NumOps;
+
+/// The error of [`toposort`], a graph with a cycle.
+#[derive(Error, Clone, Copy, Debug, PartialEq, Eq)]
+#[error("cycle detected: the node {node} is on a cycle")]
+pub struct Cycle {
+    node: usize,
+}
+
+impl Cycle {
+    /// Returns a node on the cycle.
+    pub fn node(&self) -> usize {
+        self.node
+    }
+}
+
+/// Sorts the nodes of a directed acyclic graph, so that every edge goes from
+/// an earlier node to a later one.
+///
+/// The graph is searched depth-first from the nodes in index order, and the
+/// nodes are returned in reverse post-order. If the graph has a cycle,
+/// [`Cycle`] is returned with a node on the cycle.
+///
+/// This operation should compute in *O*(*V* + *E*) time.
+pub fn toposort<N, E>(graph: &DiGraph<N, E>) -> Result<Vec<usize>, Cycle> {
+    const WHITE: u8 = 0;
+    const GRAY: u8 = 1;
+    const BLACK: u8 = 2;
+
+    let n = graph.node_count();
+    let mut color = vec![WHITE; n];
+    let mut order = Vec::with_capacity(n);
+    // The nodes on the current path, and the position of the next edge to
+    // follow from each of them.
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+
+    for start in 0..n {
+        if color[start] != WHITE {
+            continue;
+        }
+        color[start] = GRAY;
+        stack.push((start, 0));
+        while let Some((node, pos)) = stack.last_mut() {
+            let node = *node;
+            match graph._adjacent(node).get(*pos) {
+                Some(&(next, _)) => {
+                    *pos += 1;
+                    match color[next] {
+                        WHITE => {
+                            color[next] = GRAY;
+                            stack.push((next, 0));
+                        }
+                        GRAY => return Err(Cycle { node: next }),
+                        _ => {}
+                    }
+                }
+                None => {
+                    color[node] = BLACK;
+                    order.push(node);
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    order.reverse();
+    Ok(order)
+}
+
+/// The shortest paths from a source node, computed by [`dijkstra`].
+#[derive(Clone, Debug)]
+pub struct ShortestPaths<E> {
+    source: usize,
+    distances: Vec<Option<E>>,
+    predecessors: Vec<Option<usize>>,
+}
+
+impl<E> ShortestPaths<E> {
+    /// Returns the source node.
+    pub fn source(&self) -> usize {
+        self.source
+    }
+
+    /// Returns the distance from the source to `target`, or `None` if
+    /// `target` is unreachable.
+    pub fn distance(&self, target: usize) -> Option<&E> {
+        self.distances.get(target)?.as_ref()
+    }
+
+    /// Returns the nodes on a shortest path from the source to `target`, both
+    /// included, or `None` if `target` is unreachable.
+    pub fn path_to(&self, target: usize) -> Option<Vec<usize>> {
+        self.distance(target)?;
+        let mut path = vec![target];
+        let mut node = target;
+        while let Some(prev) = self.predecessors[node] {
+            path.push(prev);
+            node = prev;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Finds the shortest paths from `source` to every node, with the edge
+/// weights as lengths.
+///
+/// Returns `InvalidParameter` if an edge weight reachable from `source` is
+/// negative, which is less than `E::default()`.
+///
+/// This operation should compute in *O*((*V* + *E*) log *V*) time.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::graph::{algo, UnGraph};
+///
+/// let mut g = UnGraph::new();
+/// for _ in 0..4 {
+///     g.add_node(());
+/// }
+/// g.add_edge(0, 1, 7).unwrap();
+/// g.add_edge(0, 2, 2).unwrap();
+/// g.add_edge(2, 1, 3).unwrap();
+///
+/// let paths = algo::dijkstra(&g, 0).unwrap();
+/// assert_eq!(paths.distance(1), Some(&5));
+/// assert_eq!(paths.path_to(1), Some(vec![0, 2, 1]));
+/// assert_eq!(paths.distance(3), None);
+/// ```
+pub fn dijkstra<N, E, const DIRECTED: bool>(
+    graph: &Graph<N, E, DIRECTED>,
+    source: usize,
+) -> Result<ShortestPaths<E>, CollectionError>
+where
+    E: NumOps + Ord + Copy + Default + 'static,
+{
+    graph._check(source)?;
+    let n = graph.node_count();
+    let mut distances: Vec<Option<E>> = vec![None; n];
+    let mut predecessors = vec![None; n];
+    let mut done = vec![false; n];
+    let mut queue = IndexedPriorityQueue::new_min();
+
+    distances[source] = Some(E::default());
+    queue.push(source, E::default())?;
+    while let Some((node, dist)) = queue.pop() {
+        done[node] = true;
+        for &(next, edge) in graph._adjacent(node) {
+            let weight = *graph.edge_weight(edge).unwrap();
+            if weight < E::default() {
+                return Err(CollectionError::InvalidParameter(format!(
+                    "the edge {} has a negative weight",
+                    edge
+                )));
+            }
+            if done[next] {
+                continue;
+            }
+            let candidate = dist + weight;
+            match distances[next] {
+                Some(old) if old <= candidate => {}
+                Some(_) => {
+                    queue.change_priority(&next, candidate)?;
+                    distances[next] = Some(candidate);
+                    predecessors[next] = Some(node);
+                }
+                None => {
+                    queue.push(next, candidate)?;
+                    distances[next] = Some(candidate);
+                    predecessors[next] = Some(node);
+                }
+            }
+        }
+    }
+
+    Ok(ShortestPaths {
+        source,
+        distances,
+        predecessors,
+    })
+}
+
+/// Groups the nodes into connected components, the components of a directed
+/// graph are weakly connected.
+///
+/// Every component is sorted, and the components are ordered by their least
+/// nodes.
+///
+/// This operation should compute in *O*(*V* + *E* α(*V*)) time.
+pub fn connected_components<N, E, const DIRECTED: bool>(
+    graph: &Graph<N, E, DIRECTED>,
+) -> Vec<Vec<usize>> {
+    let mut sets = DisjointSet::with_length(graph.node_count());
+    for (source, target, _) in graph.edges() {
+        let _ = sets.union(source, target);
+    }
+    sets.sets().collect()
+}
+
+/// Finds a minimum spanning forest with Kruskal's algorithm, and returns the
+/// indices of its edges in the order they are chosen.
+///
+/// Edges of equal weights are considered in the order they are added. A
+/// disconnected graph gets a spanning tree for every connected component.
+///
+/// This operation should compute in *O*(*E* log *E*) time.
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::graph::{algo, UnGraph};
+///
+/// let mut g = UnGraph::new();
+/// for _ in 0..3 {
+///     g.add_node(());
+/// }
+/// let ab = g.add_edge(0, 1, 1).unwrap();
+/// let bc = g.add_edge(1, 2, 2).unwrap();
+/// g.add_edge(0, 2, 3).unwrap();
+///
+/// assert_eq!(algo::minimum_spanning_tree(&g), vec![ab, bc]);
+/// ```
+pub fn minimum_spanning_tree<N, E: Ord>(graph: &UnGraph<N, E>) -> Vec<usize> {
+    let mut edges: Vec<usize> = (0..graph.edge_count()).collect();
+    edges.sort_by_key(|&e| graph.edge_weight(e));
+
+    let mut sets = DisjointSet::with_length(graph.node_count());
+    let mut tree = Vec::with_capacity(graph.node_count().saturating_sub(1));
+    for e in edges {
+        let (source, target) = graph.edge_endpoints(e).unwrap();
+        if matches!(sets.union(source, target), Ok(true)) {
+            tree.push(e);
+        }
+    }
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collection::error::CollectionError;
+    use crate::graph::algo::{
+        connected_components, dijkstra, minimum_spanning_tree, toposort, Cycle,
+    };
+    use crate::graph::{DiGraph, UnGraph};
+
+    fn digraph(n: usize, edges: &[(usize, usize, i32)]) -> DiGraph<(), i32> {
+        let mut g = DiGraph::new();
+        for _ in 0..n {
+            g.add_node(());
+        }
+        for &(u, v, w) in edges {
+            g.add_edge(u, v, w).unwrap();
+        }
+        g
+    }
+
+    fn ungraph(n: usize, edges: &[(usize, usize, i32)]) -> UnGraph<(), i32> {
+        let mut g = UnGraph::new();
+        for _ in 0..n {
+            g.add_node(());
+        }
+        for &(u, v, w) in edges {
+            g.add_edge(u, v, w).unwrap();
+        }
+        g
+    }
+
+    #[test]
+    fn test_toposort() {
+        let g = digraph(5, &[(3, 1, 0), (1, 0, 0), (4, 0, 0), (3, 2, 0), (2, 0, 0)]);
+        let order = toposort(&g).unwrap();
+        assert_eq!(order.len(), 5);
+        let mut position = [0; 5];
+        for (i, &node) in order.iter().enumerate() {
+            position[node] = i;
+        }
+        for (u, v, _) in g.edges() {
+            assert!(position[u] < position[v]);
+        }
+
+        assert!(toposort(&digraph(0, &[])).unwrap().is_empty());
+
+        let cyclic = digraph(4, &[(0, 1, 0), (1, 2, 0), (2, 3, 0), (3, 1, 0)]);
+        let cycle = toposort(&cyclic).unwrap_err();
+        assert!((1..4).contains(&cycle.node()));
+        assert_eq!(toposort(&digraph(2, &[(1, 1, 0)])), Err(Cycle { node: 1 }));
+        assert_eq!(
+            cycle.to_string(),
+            format!("cycle detected: the node {} is on a cycle", cycle.node())
+        );
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let g = digraph(
+            6,
+            &[
+                (0, 1, 7),
+                (0, 2, 9),
+                (0, 5, 14),
+                (1, 2, 10),
+                (1, 3, 15),
+                (2, 3, 11),
+                (2, 5, 2),
+                (3, 4, 6),
+                (5, 4, 9),
+            ],
+        );
+        let paths = dijkstra(&g, 0).unwrap();
+        assert_eq!(paths.source(), 0);
+        let distances: Vec<_> = (0..6).map(|t| paths.distance(t).copied()).collect();
+        assert_eq!(
+            distances,
+            vec![Some(0), Some(7), Some(9), Some(20), Some(20), Some(11)]
+        );
+        assert_eq!(paths.path_to(4), Some(vec![0, 2, 5, 4]));
+        assert_eq!(paths.path_to(0), Some(vec![0]));
+
+        let paths = dijkstra(&g, 4).unwrap();
+        assert_eq!(paths.distance(0), None);
+        assert_eq!(paths.path_to(0), None);
+        assert_eq!(paths.distance(9), None);
+
+        assert!(matches!(
+            dijkstra(&g, 6),
+            Err(CollectionError::IndexOutOfRange { index: 6, len: 6 })
+        ));
+        assert!(matches!(
+            dijkstra(&digraph(2, &[(0, 1, -1)]), 0),
+            Err(CollectionError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let g = ungraph(6, &[(0, 3, 0), (4, 1, 0), (3, 5, 0)]);
+        assert_eq!(
+            connected_components(&g),
+            vec![vec![0, 3, 5], vec![1, 4], vec![2]]
+        );
+
+        let g = digraph(3, &[(2, 0, 0)]);
+        assert_eq!(connected_components(&g), vec![vec![0, 2], vec![1]]);
+    }
+
+    #[test]
+    fn test_minimum_spanning_tree() {
+        let g = ungraph(
+            5,
+            &[
+                (0, 1, 4),
+                (0, 2, 1),
+                (1, 2, 2),
+                (1, 3, 5),
+                (2, 3, 8),
+                (3, 3, 0),
+            ],
+        );
+        let tree = minimum_spanning_tree(&g);
+        assert_eq!(tree, vec![1, 2, 3]);
+        let total: i32 = tree.iter().map(|&e| g.edge_weight(e).unwrap()).sum();
+        assert_eq!(total, 8);
+
+        assert!(minimum_spanning_tree(&ungraph(3, &[])).is_empty());
+    }
+}
@@ -0,0 +1,306 @@
+//! Graphs stored as adjacency lists, and common graph algorithms.
+//!
+//! The nodes and the edges of a [`Graph`] are numbered from 0 in the order
+//! they are added, and carry weights of the types `N` and `E`.
+//! [`DiGraph`] is a directed graph and [`UnGraph`] is an undirected one.
+//!
+//! - [`Bfs`] and [`Dfs`] iterate over the nodes reachable from a node;
+//! - [`algo::toposort`] sorts a directed acyclic graph topologically;
+//! - [`algo::dijkstra`] finds the shortest paths from a node;
+//! - [`algo::connected_components`] groups the connected nodes;
+//! - [`algo::minimum_spanning_tree`] finds a minimum spanning forest.
+//!
+//! # Examples
+//!
+//! ```
+//! use boost_rs::graph::{algo, DiGraph};
+//!
+//! // Build steps and their durations in seconds.
+//! let mut g = DiGraph::new();
+//! let fetch = g.add_node("fetch");
+//! let build = g.add_node("build");
+//! let test = g.add_node("test");
+//! let lint = g.add_node("lint");
+//! g.add_edge(fetch, build, 30).unwrap();
+//! g.add_edge(build, test, 120).unwrap();
+//! g.add_edge(fetch, lint, 10).unwrap();
+//!
+//! assert_eq!(algo::toposort(&g).unwrap(), vec![fetch, lint, build, test]);
+//! let paths = algo::dijkstra(&g, fetch).unwrap();
+//! assert_eq!(paths.distance(test), Some(&150));
+//! ```
+//!
+//! Wikipedia:
+//!  - https://en.wikipedia.org/wiki/Adjacency_list
+
+use std::fmt::{self, Debug};
+
+use crate::collection::error::CollectionError;
+
+pub use self::traversal::{Bfs, Dfs};
+
+pub mod algo;
+pub mod traversal;
+
+/// A directed graph.
+pub type DiGraph<N, E> = Graph<N, E, true>;
+
+/// An undirected graph.
+pub type UnGraph<N, E> = Graph<N, E, false>;
+
+#[derive(Clone, Debug)]
+struct Edge<E> {
+    source: usize,
+    target: usize,
+    weight: E,
+}
+
+/// A graph stored as adjacency lists, which is directed if `DIRECTED`.
+///
+/// An edge of an undirected graph is in the adjacency lists of both of its
+/// endpoints. Parallel edges and self-loops are allowed.
+#[derive(Clone)]
+pub struct Graph<N, E, const DIRECTED: bool> {
+    nodes: Vec<N>,
+    edges: Vec<Edge<E>>,
+    // The neighbors of every node and the edges to them, in the order the
+    // edges are added.
+    adjacency: Vec<Vec<(usize, usize)>>,
+}
+
+impl<N, E, const DIRECTED: bool> Default for Graph<N, E, DIRECTED> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N, E, const DIRECTED: bool> Graph<N, E, DIRECTED> {
+    pub fn new() -> Self {
+        Self::with_capacity(0, 0)
+    }
+
+    /// Creates an empty graph with space for `nodes` nodes and `edges` edges.
+    pub fn with_capacity(nodes: usize, edges: usize) -> Self {
+        Graph {
+            nodes: Vec::with_capacity(nodes),
+            edges: Vec::with_capacity(edges),
+            adjacency: Vec::with_capacity(nodes),
+        }
+    }
+
+    /// Returns `true` if the graph is directed.
+    #[inline]
+    pub fn is_directed(&self) -> bool {
+        DIRECTED
+    }
+
+    /// Returns the number of nodes.
+    #[inline]
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the number of edges.
+    #[inline]
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Adds a node, and returns its index.
+    pub fn add_node(&mut self, weight: N) -> usize {
+        self.nodes.push(weight);
+        self.adjacency.push(Vec::new());
+        self.nodes.len() - 1
+    }
+
+    /// Adds an edge from `source` to `target`, and returns its index.
+    pub fn add_edge(
+        &mut self,
+        source: usize,
+        target: usize,
+        weight: E,
+    ) -> Result<usize, CollectionError> {
+        self._check(source)?;
+        self._check(target)?;
+        let e = self.edges.len();
+        self.edges.push(Edge {
+            source,
+            target,
+            weight,
+        });
+        self.adjacency[source].push((target, e));
+        if !DIRECTED && source != target {
+            self.adjacency[target].push((source, e));
+        }
+        Ok(e)
+    }
+
+    /// Returns the weight of the node.
+    pub fn node_weight(&self, node: usize) -> Option<&N> {
+        self.nodes.get(node)
+    }
+
+    /// Returns the mutable weight of the node.
+    pub fn node_weight_mut(&mut self, node: usize) -> Option<&mut N> {
+        self.nodes.get_mut(node)
+    }
+
+    /// Returns the weight of the edge.
+    pub fn edge_weight(&self, edge: usize) -> Option<&E> {
+        self.edges.get(edge).map(|e| &e.weight)
+    }
+
+    /// Returns the mutable weight of the edge.
+    pub fn edge_weight_mut(&mut self, edge: usize) -> Option<&mut E> {
+        self.edges.get_mut(edge).map(|e| &mut e.weight)
+    }
+
+    /// Returns the source and the target of the edge.
+    pub fn edge_endpoints(&self, edge: usize) -> Option<(usize, usize)> {
+        self.edges.get(edge).map(|e| (e.source, e.target))
+    }
+
+    /// Returns the first edge from `source` to `target`, either way if the
+    /// graph is undirected.
+    pub fn find_edge(&self, source: usize, target: usize) -> Option<usize> {
+        self.adjacency
+            .get(source)?
+            .iter()
+            .find(|&&(n, _)| n == target)
+            .map(|&(_, e)| e)
+    }
+
+    /// Gets an iterator over the neighbors of the node and the weights of the
+    /// edges to them, in the order the edges are added.
+    ///
+    /// The neighbors of a directed graph are the targets of the outgoing
+    /// edges.
+    pub fn neighbors(
+        &self,
+        node: usize,
+    ) -> Result<impl Iterator<Item = (usize, &E)> + '_, CollectionError> {
+        self._check(node)?;
+        Ok(self.adjacency[node]
+            .iter()
+            .map(move |&(n, e)| (n, &self.edges[e].weight)))
+    }
+
+    /// Returns the number of the edges from the node, a self-loop counts
+    /// once.
+    pub fn degree(&self, node: usize) -> Result<usize, CollectionError> {
+        self._check(node)?;
+        Ok(self.adjacency[node].len())
+    }
+
+    /// Gets an iterator over the nodes and their weights.
+    pub fn nodes(&self) -> impl Iterator<Item = (usize, &N)> + '_ {
+        self.nodes.iter().enumerate()
+    }
+
+    /// Gets an iterator over the sources, the targets and the weights of the
+    /// edges, in the order they are added.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, &E)> + '_ {
+        self.edges.iter().map(|e| (e.source, e.target, &e.weight))
+    }
+
+    /// Gets a breadth-first iterator over the nodes reachable from `start`.
+    pub fn bfs(&self, start: usize) -> Result<Bfs<'_, N, E, DIRECTED>, CollectionError> {
+        self._check(start)?;
+        Ok(Bfs::new(self, start))
+    }
+
+    /// Gets a depth-first, pre-order iterator over the nodes reachable from
+    /// `start`.
+    pub fn dfs(&self, start: usize) -> Result<Dfs<'_, N, E, DIRECTED>, CollectionError> {
+        self._check(start)?;
+        Ok(Dfs::new(self, start))
+    }
+
+    /// Returns the neighbors of the node and the edges to them.
+    #[inline]
+    pub(crate) fn _adjacent(&self, node: usize) -> &[(usize, usize)] {
+        &self.adjacency[node]
+    }
+
+    pub(crate) fn _check(&self, node: usize) -> Result<(), CollectionError> {
+        if node >= self.nodes.len() {
+            return Err(CollectionError::IndexOutOfRange {
+                index: node,
+                len: self.nodes.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<N: Debug, E: Debug, const DIRECTED: bool> Debug for Graph<N, E, DIRECTED> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(if DIRECTED { "DiGraph" } else { "UnGraph" })
+            .field("nodes", &self.nodes)
+            .field("edges", &self.edges().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collection::error::CollectionError;
+    use crate::graph::{DiGraph, UnGraph};
+
+    #[test]
+    fn test_directed() {
+        let mut g = DiGraph::new();
+        let a = g.add_node('a');
+        let b = g.add_node('b');
+        let c = g.add_node('c');
+        let ab = g.add_edge(a, b, 1).unwrap();
+        g.add_edge(a, c, 2).unwrap();
+        g.add_edge(c, c, 3).unwrap();
+        assert!(matches!(
+            g.add_edge(a, 3, 0),
+            Err(CollectionError::IndexOutOfRange { index: 3, len: 3 })
+        ));
+
+        assert!(g.is_directed());
+        assert_eq!((g.node_count(), g.edge_count()), (3, 3));
+        assert_eq!(g.find_edge(a, b), Some(ab));
+        assert_eq!(g.find_edge(b, a), None);
+        assert_eq!(g.edge_endpoints(ab), Some((a, b)));
+        assert_eq!(
+            g.neighbors(a).unwrap().collect::<Vec<_>>(),
+            vec![(b, &1), (c, &2)]
+        );
+        assert_eq!(g.degree(b).unwrap(), 0);
+        assert_eq!(g.degree(c).unwrap(), 1);
+        assert!(g.neighbors(7).is_err());
+
+        *g.node_weight_mut(b).unwrap() = 'B';
+        *g.edge_weight_mut(ab).unwrap() += 10;
+        assert_eq!(g.node_weight(b), Some(&'B'));
+        assert_eq!(g.edge_weight(ab), Some(&11));
+        assert_eq!(
+            format!("{:?}", g),
+            "DiGraph { nodes: ['a', 'B', 'c'], edges: [(0, 1, 11), (0, 2, 2), (2, 2, 3)] }"
+        );
+    }
+
+    #[test]
+    fn test_undirected() {
+        let mut g: UnGraph<(), &str> = UnGraph::with_capacity(3, 2);
+        for _ in 0..3 {
+            g.add_node(());
+        }
+        let e = g.add_edge(0, 1, "x").unwrap();
+        g.add_edge(2, 2, "loop").unwrap();
+
+        assert!(!g.is_directed());
+        assert_eq!(g.find_edge(1, 0), Some(e));
+        assert_eq!(g.neighbors(1).unwrap().collect::<Vec<_>>(), vec![(0, &"x")]);
+        assert_eq!(g.degree(2).unwrap(), 1);
+        assert_eq!(g.nodes().count(), 3);
+        assert_eq!(
+            g.edges().collect::<Vec<_>>(),
+            vec![(0, 1, &"x"), (2, 2, &"loop")]
+        );
+    }
+}
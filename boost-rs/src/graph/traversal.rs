@@ -0,0 +1,138 @@
+//! Breadth-first and depth-first iterators over the nodes of a graph.
+
+use std::collections::VecDeque;
+use std::iter::FusedIterator;
+
+use crate::collection::bitset::BitSet;
+use crate::graph::Graph;
+
+/// A breadth-first iterator over the nodes reachable from a node, created by
+/// [`Graph::bfs`].
+///
+/// # Examples
+///
+/// ```
+/// use boost_rs::graph::UnGraph;
+///
+/// let mut g = UnGraph::new();
+/// for name in ["a", "b", "c", "d"] {
+///     g.add_node(name);
+/// }
+/// g.add_edge(0, 1, ()).unwrap();
+/// g.add_edge(1, 2, ()).unwrap();
+/// g.add_edge(0, 3, ()).unwrap();
+///
+/// assert_eq!(g.bfs(0).unwrap().collect::<Vec<_>>(), vec![0, 1, 3, 2]);
+/// assert_eq!(g.dfs(0).unwrap().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+/// ```
+pub struct Bfs<'a, N, E, const DIRECTED: bool> {
+    graph: &'a Graph<N, E, DIRECTED>,
+    queue: VecDeque<usize>,
+    // The nodes which have been queued.
+    discovered: BitSet,
+}
+
+impl<'a, N, E, const DIRECTED: bool> Bfs<'a, N, E, DIRECTED> {
+    pub(crate) fn new(graph: &'a Graph<N, E, DIRECTED>, start: usize) -> Self {
+        let mut discovered = BitSet::with_capacity(graph.node_count());
+        discovered.insert(start);
+        Bfs {
+            graph,
+            queue: VecDeque::from(vec![start]),
+            discovered,
+        }
+    }
+}
+
+impl<'a, N, E, const DIRECTED: bool> Iterator for Bfs<'a, N, E, DIRECTED> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let node = self.queue.pop_front()?;
+        for &(next, _) in self.graph._adjacent(node) {
+            if self.discovered.insert(next) {
+                self.queue.push_back(next);
+            }
+        }
+        Some(node)
+    }
+}
+
+impl<'a, N, E, const DIRECTED: bool> FusedIterator for Bfs<'a, N, E, DIRECTED> {}
+
+/// A depth-first iterator over the nodes reachable from a node in pre-order,
+/// created by [`Graph::dfs`].
+///
+/// The neighbors of every node are visited in the order their edges are
+/// added.
+pub struct Dfs<'a, N, E, const DIRECTED: bool> {
+    graph: &'a Graph<N, E, DIRECTED>,
+    stack: Vec<usize>,
+    visited: BitSet,
+}
+
+impl<'a, N, E, const DIRECTED: bool> Dfs<'a, N, E, DIRECTED> {
+    pub(crate) fn new(graph: &'a Graph<N, E, DIRECTED>, start: usize) -> Self {
+        Dfs {
+            graph,
+            stack: vec![start],
+            visited: BitSet::with_capacity(graph.node_count()),
+        }
+    }
+}
+
+impl<'a, N, E, const DIRECTED: bool> Iterator for Dfs<'a, N, E, DIRECTED> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while let Some(node) = self.stack.pop() {
+            if !self.visited.insert(node) {
+                continue;
+            }
+            // Pushed in reverse, so that the first neighbor is popped first.
+            for &(next, _) in self.graph._adjacent(node).iter().rev() {
+                if !self.visited.contains(next) {
+                    self.stack.push(next);
+                }
+            }
+            return Some(node);
+        }
+        None
+    }
+}
+
+impl<'a, N, E, const DIRECTED: bool> FusedIterator for Dfs<'a, N, E, DIRECTED> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::DiGraph;
+
+    fn graph() -> DiGraph<(), ()> {
+        // 0 -> 1 -> 3, 0 -> 2 -> 3 -> 4, 4 -> 1, and 5 is unreachable.
+        let mut g = DiGraph::new();
+        for _ in 0..6 {
+            g.add_node(());
+        }
+        for (u, v) in [(0, 1), (0, 2), (1, 3), (2, 3), (3, 4), (4, 1), (5, 0)] {
+            g.add_edge(u, v, ()).unwrap();
+        }
+        g
+    }
+
+    #[test]
+    fn test_bfs() {
+        let g = graph();
+        assert_eq!(g.bfs(0).unwrap().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(g.bfs(3).unwrap().collect::<Vec<_>>(), vec![3, 4, 1]);
+        assert_eq!(g.bfs(5).unwrap().count(), 6);
+        assert!(g.bfs(6).is_err());
+    }
+
+    #[test]
+    fn test_dfs() {
+        let g = graph();
+        assert_eq!(g.dfs(0).unwrap().collect::<Vec<_>>(), vec![0, 1, 3, 4, 2]);
+        assert_eq!(g.dfs(2).unwrap().collect::<Vec<_>>(), vec![2, 3, 4, 1]);
+        assert!(g.dfs(6).is_err());
+    }
+}
@@ -12,6 +12,9 @@ pub mod types;
 #[cfg(feature = "collection")]
 pub mod collection;
 
+#[cfg(feature = "graph")]
+pub mod graph;
+
 #[cfg(feature = "sort")]
 pub mod sort;
 
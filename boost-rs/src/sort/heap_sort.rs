@@ -114,7 +114,7 @@ mod tests {
     fn empty() {
         let mut arr: Vec<i32> = Vec::new();
         heap_sort(&mut arr);
-        assert_eq!(&arr, &[]);
+        assert_eq!(&arr, &[] as &[i32]);
     }
 
     #[test]
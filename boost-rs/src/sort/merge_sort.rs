@@ -167,7 +167,7 @@ mod tests {
     fn empty() {
         let mut res = Vec::<u8>::new();
         merge_sort(&mut res);
-        assert_eq!(res, vec![]);
+        assert_eq!(res, Vec::<u8>::new());
     }
 
     #[test]
@@ -11,8 +11,36 @@ empty_trait_impl!(Integer for isize i8 i16 i32 i64 i128);
 
 empty_trait_impl!(SignedInteger for isize i8 i16 i32 i64 i128);
 
+/// Types with a least and a greatest value.
+pub trait Bounded {
+    const MIN: Self;
+    const MAX: Self;
+}
+
+macro_rules! bounded_impl {
+    ($($t:ty)*) => ($(
+        impl Bounded for $t {
+            const MIN: Self = <$t>::MIN;
+            const MAX: Self = <$t>::MAX;
+        }
+    )*)
+}
+
+bounded_impl!(usize u8 u16 u32 u64 u128 isize i8 i16 i32 i64 i128);
+
 #[cfg(test)]
 mod tests {
+    use crate::types::integer::Bounded;
+
     #[test]
     fn test_integer() {}
+
+    #[test]
+    fn test_bounded() {
+        fn bounds<T: Bounded>() -> (T, T) {
+            (T::MIN, T::MAX)
+        }
+        assert_eq!(bounds::<u8>(), (0, 255));
+        assert_eq!(bounds::<i64>(), (i64::MIN, i64::MAX));
+    }
 }
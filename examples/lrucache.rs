@@ -6,17 +6,17 @@ fn main() {
     l.put("2".to_string(), 2);
     l.put("3".to_string(), 3);
     l.put("4".to_string(), 4);
-    l.traverse();
+    println!("{:?}", l);
 
     assert_eq!(l.get(&"1".to_string()), Some(&1));
     assert_eq!(l.get(&"2".to_string()), Some(&2));
     assert_eq!(l.get(&"3".to_string()), Some(&3));
     assert_eq!(l.get(&"4".to_string()), Some(&4));
     assert_eq!(l.get(&"5".to_string()), None);
-    l.traverse();
+    println!("{:?}", l);
 
     l.put("5".to_string(), 5);
     assert_eq!(l.get(&"5".to_string()), Some(&5));
     assert_eq!(l.get(&"1".to_string()), None); // Cache cleaned
-    l.traverse();
+    println!("{:?}", l);
 }